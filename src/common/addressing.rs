@@ -0,0 +1,168 @@
+//     open-dis-rust - Rust implementation of the IEEE 1278.1-2012 Distributed Interactive
+//                     Simulation (DIS) application protocol
+//     Copyright (C) 2025 Cameron Howell
+//
+//     Licensed under the BSD 2-Clause License
+
+//! The receivers a PDU can be addressed to, IEEE 1278.1-2012 §5.6.5.1 and §6.2.28
+//!
+//! A receiving entity ID names a simulation with the `NO_ENTITY` entity number, or one of its
+//! entities, and may use the `ALL_SITES`, `ALL_APPLIC` and `ALL_ENTITIES` wildcards. A
+//! [`Target`] spells out which of these a PDU is meant for and converts to and from the
+//! receiving entity ID. Whether an incoming PDU is for us is decided by
+//! [`EntityId::addresses_simulation`], [`EntityId::addresses_entity`],
+//! [`SimulationAddress::addresses`] and [`SimulationIdentifier::addresses`].
+//!
+//! [`SimulationIdentifier::addresses`]: crate::common::data_types::SimulationIdentifier::addresses
+
+use crate::common::{
+    constants::{ALL_APPLIC, ALL_ENTITIES, ALL_SITES, NO_ENTITY},
+    data_types::{entity_id::EntityId, simulation_address::SimulationAddress},
+};
+
+/// The receivers of an addressed PDU
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Target {
+    /// Every simulation in the exercise
+    AllSimulations,
+    /// Every simulation at a site
+    Site(u16),
+    /// One simulation, as opposed to its entities
+    Simulation(SimulationAddress),
+    /// Every entity of the simulations at an address, which may use wildcards
+    AllEntities(SimulationAddress),
+    /// One entity
+    Entity(EntityId),
+}
+
+impl Target {
+    /// The receiving entity ID that addresses this target
+    #[must_use]
+    pub const fn entity_id(self) -> EntityId {
+        match self {
+            Self::AllSimulations => EntityId::new(ALL_SITES, ALL_APPLIC, NO_ENTITY),
+            Self::Site(site_id) => EntityId::new(site_id, ALL_APPLIC, NO_ENTITY),
+            Self::Simulation(address) => {
+                EntityId::new(address.site_id, address.application_id, NO_ENTITY)
+            }
+            Self::AllEntities(address) => {
+                EntityId::new(address.site_id, address.application_id, ALL_ENTITIES)
+            }
+            Self::Entity(id) => id,
+        }
+    }
+
+    /// The target a receiving entity ID addresses
+    #[must_use]
+    pub const fn from_entity_id(id: EntityId) -> Self {
+        let address = id.simulation_address;
+        match id.entity_id {
+            NO_ENTITY if address.site_id == ALL_SITES && address.application_id == ALL_APPLIC => {
+                Self::AllSimulations
+            }
+            NO_ENTITY if address.application_id == ALL_APPLIC => Self::Site(address.site_id),
+            NO_ENTITY => Self::Simulation(address),
+            ALL_ENTITIES => Self::AllEntities(address),
+            _ => Self::Entity(id),
+        }
+    }
+
+    /// Whether the target includes more than one simulation or entity
+    #[must_use]
+    pub const fn is_wildcard(self) -> bool {
+        let id = self.entity_id();
+        id.simulation_address.is_wildcard() || id.entity_id == ALL_ENTITIES
+    }
+
+    /// Whether a PDU sent to this target is for the simulation at `address`
+    #[must_use]
+    pub const fn addresses_simulation(self, address: SimulationAddress) -> bool {
+        self.entity_id().addresses_simulation(address)
+    }
+
+    /// Whether a PDU sent to this target is for the entity `entity`
+    #[must_use]
+    pub const fn addresses_entity(self, entity: EntityId) -> bool {
+        self.entity_id().addresses_entity(entity)
+    }
+}
+
+impl From<Target> for EntityId {
+    fn from(target: Target) -> Self {
+        target.entity_id()
+    }
+}
+
+impl From<EntityId> for Target {
+    fn from(id: EntityId) -> Self {
+        Self::from_entity_id(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::data_types::SimulationIdentifier;
+
+    const HERE: SimulationAddress = SimulationAddress::new(3, 7);
+
+    #[test]
+    fn wildcards_address_simulations() {
+        assert!(Target::AllSimulations.addresses_simulation(HERE));
+        assert!(Target::Site(3).addresses_simulation(HERE));
+        assert!(!Target::Site(4).addresses_simulation(HERE));
+        assert!(Target::Simulation(HERE).addresses_simulation(HERE));
+        assert!(!Target::Simulation(SimulationAddress::new(3, 8)).addresses_simulation(HERE));
+        assert!(EntityId::new(ALL_SITES, 7, NO_ENTITY).addresses_simulation(HERE));
+        assert!(EntityId::new(3, 7, 12).addresses_simulation(HERE));
+        assert!(!EntityId::new(ALL_SITES, 8, NO_ENTITY).addresses_simulation(HERE));
+    }
+
+    #[test]
+    fn entities_are_addressed_by_number_or_all_entities() {
+        let entity = EntityId::new(3, 7, 12);
+        assert!(Target::Entity(entity).addresses_entity(entity));
+        assert!(!Target::Entity(EntityId::new(3, 7, 13)).addresses_entity(entity));
+        assert!(Target::AllEntities(HERE).addresses_entity(entity));
+        assert!(
+            Target::AllEntities(SimulationAddress::new(ALL_SITES, ALL_APPLIC))
+                .addresses_entity(entity)
+        );
+        assert!(!Target::Simulation(HERE).addresses_entity(entity));
+        assert!(!Target::AllSimulations.addresses_entity(entity));
+    }
+
+    #[test]
+    fn targets_round_trip_through_entity_ids() {
+        let targets = [
+            Target::AllSimulations,
+            Target::Site(3),
+            Target::Simulation(HERE),
+            Target::AllEntities(HERE),
+            Target::Entity(EntityId::new(3, 7, 12)),
+        ];
+        for target in targets {
+            assert_eq!(Target::from(EntityId::from(target)), target);
+        }
+        assert!(Target::Site(3).is_wildcard());
+        assert!(Target::AllEntities(HERE).is_wildcard());
+        assert!(!Target::Simulation(HERE).is_wildcard());
+    }
+
+    #[test]
+    fn simulation_identifiers_match_reference_numbers() {
+        let here = SimulationIdentifier {
+            simulation_address: HERE,
+            reference_number: 2,
+        };
+        let everyone = SimulationIdentifier {
+            simulation_address: SimulationAddress::new(ALL_SITES, ALL_APPLIC),
+            reference_number: 2,
+        };
+        assert!(everyone.addresses(here));
+        assert!(!everyone.addresses(SimulationIdentifier {
+            reference_number: 1,
+            ..here
+        }));
+    }
+}
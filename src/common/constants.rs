@@ -76,6 +76,12 @@ pub const fn validate_marking_length(len: usize) -> bool {
     len <= MAX_ENTITY_MARKING_LENGTH
 }
 
+#[must_use]
+/// Compile-time calculation of the padding octets needed to align a record to a 64-bit boundary
+pub const fn padding_to_64_bits(length_octets: usize) -> usize {
+    (8 - length_octets % 8) % 8
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -99,4 +105,12 @@ mod tests {
         assert!(validate_marking_length(MAX_ENTITY_MARKING_LENGTH));
         assert!(!validate_marking_length(MAX_ENTITY_MARKING_LENGTH + 1));
     }
+
+    #[test]
+    fn test_padding_to_64_bits() {
+        assert_eq!(padding_to_64_bits(0), 0);
+        assert_eq!(padding_to_64_bits(12), 4);
+        assert_eq!(padding_to_64_bits(16), 0);
+        assert_eq!(padding_to_64_bits(17), 7);
+    }
 }
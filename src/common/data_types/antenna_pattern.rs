@@ -2,10 +2,22 @@
 //     Copyright (C) 2025 Cameron Howell
 //
 //     Licensed under the BSD-2-Clause License
-use crate::pdu_macro::{FieldDeserialize, FieldDeserializeWithLen, FieldLen, FieldSerialize};
+use crate::{
+    common::{
+        SerializedLength,
+        constants::padding_to_64_bits,
+        data_types::beam_antenna_pattern::BeamAntennaPattern,
+        enums::{TransmitterAntennaPatternReferenceSystem, TransmitterAntennaPatternType},
+    },
+    pdu_macro::{FieldDeserialize, FieldDeserializeWithLen, FieldLen, FieldSerialize},
+};
 use bytes::{Buf, BufMut, BytesMut};
 
-#[derive(Clone, Debug, Default)]
+/// The antenna pattern carried in the Transmitter PDU, kept as the raw octets on the wire
+///
+/// Its meaning depends on the `antenna_pattern_type` of the Transmitter PDU; use
+/// [`AntennaPattern::decode`] to interpret it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct AntennaPattern {
     record_specific_fields: Vec<u8>,
 }
@@ -24,7 +36,8 @@ impl FieldDeserialize for AntennaPattern {
 
 impl FieldDeserializeWithLen for AntennaPattern {
     fn deserialize_with_len<B: Buf>(buf: &mut B, len: usize) -> Self {
-        let len_u16 = u16::try_from(len).expect("Should not be larger than u16");
+        // The length field on the wire is 16 bits wide
+        let len_u16 = u16::try_from(len).unwrap_or(u16::MAX);
         Self::deserialize(buf, len_u16).unwrap_or_default()
     }
 }
@@ -43,6 +56,24 @@ impl AntennaPattern {
         }
     }
 
+    #[must_use]
+    pub const fn from_bytes(record_specific_fields: Vec<u8>) -> Self {
+        Self {
+            record_specific_fields,
+        }
+    }
+
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.record_specific_fields
+    }
+
+    /// Interprets the pattern according to the antenna pattern type it was sent with
+    #[must_use]
+    pub fn decode(&self, pattern_type: TransmitterAntennaPatternType) -> AntennaPatternRecord {
+        AntennaPatternRecord::decode(pattern_type, &self.record_specific_fields)
+    }
+
     pub fn serialize(&self, buf: &mut BytesMut) {
         for i in 0..self.record_specific_fields.len() {
             buf.put_u8(self.record_specific_fields[i]);
@@ -64,3 +95,124 @@ impl AntennaPattern {
         })
     }
 }
+
+impl From<&AntennaPatternRecord> for AntennaPattern {
+    fn from(record: &AntennaPatternRecord) -> Self {
+        let mut buf = BytesMut::new();
+        record.serialize(&mut buf);
+        Self::from_bytes(buf.to_vec())
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+/// Spherical harmonic antenna pattern record
+///
+/// The pattern is described by `(order + 1)²` coefficients; the record is padded to a 64-bit
+/// boundary.
+pub struct SphericalHarmonicAntennaPattern {
+    pub order: u8,
+    pub coefficients: Vec<f32>,
+    pub reference_system: TransmitterAntennaPatternReferenceSystem,
+}
+
+impl SphericalHarmonicAntennaPattern {
+    #[must_use]
+    pub const fn new(
+        order: u8,
+        coefficients: Vec<f32>,
+        reference_system: TransmitterAntennaPatternReferenceSystem,
+    ) -> Self {
+        Self {
+            order,
+            coefficients,
+            reference_system,
+        }
+    }
+
+    /// Number of coefficients described by `order`
+    #[must_use]
+    pub const fn coefficient_count(order: u8) -> usize {
+        (order as usize + 1) * (order as usize + 1)
+    }
+
+    const fn unpadded_length(&self) -> usize {
+        2 + 4 * self.coefficients.len()
+    }
+
+    /// Length in octets of the record, including padding
+    #[must_use]
+    pub const fn length(&self) -> usize {
+        self.unpadded_length() + padding_to_64_bits(self.unpadded_length())
+    }
+
+    pub fn serialize(&self, buf: &mut BytesMut) {
+        buf.put_u8(self.order);
+        for coefficient in &self.coefficients {
+            buf.put_f32(*coefficient);
+        }
+        buf.put_u8(self.reference_system as u8);
+        buf.put_bytes(0u8, padding_to_64_bits(self.unpadded_length()));
+    }
+
+    pub fn deserialize<B: Buf>(buf: &mut B) -> Self {
+        let order = buf.get_u8();
+        let count = Self::coefficient_count(order).min(buf.remaining().saturating_sub(1) / 4);
+        let coefficients = (0..count).map(|_| buf.get_f32()).collect();
+        let reference_system = if buf.has_remaining() {
+            TransmitterAntennaPatternReferenceSystem::deserialize(buf)
+        } else {
+            TransmitterAntennaPatternReferenceSystem::default()
+        };
+        let record = Self {
+            order,
+            coefficients,
+            reference_system,
+        };
+        buf.advance(padding_to_64_bits(record.unpadded_length()).min(buf.remaining()));
+        record
+    }
+}
+
+/// The antenna pattern of a Transmitter PDU, interpreted by antenna pattern type
+#[derive(Clone, Debug, PartialEq)]
+pub enum AntennaPatternRecord {
+    Beam(BeamAntennaPattern),
+    SphericalHarmonic(SphericalHarmonicAntennaPattern),
+    /// A pattern type this crate does not model, or that was too short to decode
+    Other(Vec<u8>),
+}
+
+impl AntennaPatternRecord {
+    /// Interprets `bytes` as an antenna pattern of `pattern_type`
+    #[must_use]
+    pub fn decode(pattern_type: TransmitterAntennaPatternType, bytes: &[u8]) -> Self {
+        let mut buf = bytes;
+        match pattern_type {
+            TransmitterAntennaPatternType::Beam if bytes.len() >= BeamAntennaPattern::LENGTH => {
+                Self::Beam(BeamAntennaPattern::deserialize(&mut buf))
+            }
+            TransmitterAntennaPatternType::Sphericalharmonic if !bytes.is_empty() => {
+                Self::SphericalHarmonic(SphericalHarmonicAntennaPattern::deserialize(&mut buf))
+            }
+            _ => Self::Other(bytes.to_vec()),
+        }
+    }
+
+    /// The antenna pattern type of this record, or `None` for a raw pattern
+    #[must_use]
+    pub const fn pattern_type(&self) -> Option<TransmitterAntennaPatternType> {
+        match self {
+            Self::Beam(_) => Some(TransmitterAntennaPatternType::Beam),
+            Self::SphericalHarmonic(_) => Some(TransmitterAntennaPatternType::Sphericalharmonic),
+            Self::Other(_) => None,
+        }
+    }
+
+    pub fn serialize(&self, buf: &mut BytesMut) {
+        match self {
+            Self::Beam(record) => record.serialize(buf),
+            Self::SphericalHarmonic(record) => record.serialize(buf),
+            Self::Other(bytes) => buf.put_slice(bytes),
+        }
+    }
+}
@@ -0,0 +1,111 @@
+//     open-dis-rust - Rust implementation of the IEEE-1278.1 Distributed Interactive Simulation
+//     Copyright (C) 2025 Cameron Howell
+//
+//     Licensed under the BSD-2-Clause License
+
+use bytes::{Buf, BufMut, BytesMut};
+
+use crate::{
+    common::{
+        SerializedLength, data_types::EulerAngles, enums::TransmitterAntennaPatternReferenceSystem,
+    },
+    pdu_macro::{FieldDeserialize, FieldLen, FieldSerialize},
+};
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+/// Implemented according to IEEE 1278.1-2012 §6.2.9
+pub struct BeamAntennaPattern {
+    /// Direction of the main beam relative to the reference system
+    pub beam_direction: EulerAngles,
+    /// Full width of the main beam in azimuth, in radians
+    pub azimuth_beamwidth: f32,
+    /// Full width of the main beam in elevation, in radians
+    pub elevation_beamwidth: f32,
+    pub reference_system: TransmitterAntennaPatternReferenceSystem,
+    padding: u8,
+    padding2: u16,
+    /// Magnitude of the Z-component of the electric field
+    pub ez: f32,
+    /// Magnitude of the X-component of the electric field
+    pub ex: f32,
+    /// Phase angle between Ez and Ex, in radians
+    pub phase: f32,
+    padding3: u32,
+}
+
+impl BeamAntennaPattern {
+    #[must_use]
+    #[allow(clippy::too_many_arguments)]
+    pub const fn new(
+        beam_direction: EulerAngles,
+        azimuth_beamwidth: f32,
+        elevation_beamwidth: f32,
+        reference_system: TransmitterAntennaPatternReferenceSystem,
+        ez: f32,
+        ex: f32,
+        phase: f32,
+    ) -> Self {
+        Self {
+            beam_direction,
+            azimuth_beamwidth,
+            elevation_beamwidth,
+            reference_system,
+            padding: 0,
+            padding2: 0,
+            ez,
+            ex,
+            phase,
+            padding3: 0,
+        }
+    }
+
+    pub fn serialize(&self, buf: &mut BytesMut) {
+        self.beam_direction.serialize(buf);
+        buf.put_f32(self.azimuth_beamwidth);
+        buf.put_f32(self.elevation_beamwidth);
+        buf.put_u8(self.reference_system as u8);
+        buf.put_u8(self.padding);
+        buf.put_u16(self.padding2);
+        buf.put_f32(self.ez);
+        buf.put_f32(self.ex);
+        buf.put_f32(self.phase);
+        buf.put_u32(self.padding3);
+    }
+
+    pub fn deserialize<B: Buf>(buf: &mut B) -> Self {
+        Self {
+            beam_direction: EulerAngles::deserialize(buf),
+            azimuth_beamwidth: buf.get_f32(),
+            elevation_beamwidth: buf.get_f32(),
+            reference_system: TransmitterAntennaPatternReferenceSystem::deserialize(buf),
+            padding: buf.get_u8(),
+            padding2: buf.get_u16(),
+            ez: buf.get_f32(),
+            ex: buf.get_f32(),
+            phase: buf.get_f32(),
+            padding3: buf.get_u32(),
+        }
+    }
+}
+
+impl FieldSerialize for BeamAntennaPattern {
+    fn serialize_field(&self, buf: &mut BytesMut) {
+        self.serialize(buf);
+    }
+}
+
+impl FieldDeserialize for BeamAntennaPattern {
+    fn deserialize_field<B: Buf>(buf: &mut B) -> Self {
+        Self::deserialize(buf)
+    }
+}
+
+impl FieldLen for BeamAntennaPattern {
+    fn field_len(&self) -> usize {
+        Self::LENGTH
+    }
+}
+
+impl SerializedLength for BeamAntennaPattern {
+    const LENGTH: usize = 40;
+}
@@ -4,16 +4,27 @@
 //
 //     Licensed under the BSD 2-Clause License
 
+use std::time::Duration;
+
 use bytes::{Buf, BufMut, BytesMut};
+use chrono::{DateTime, TimeDelta, Utc};
+
+use crate::{
+    common::{dis_error::DISError, timestamp},
+    pdu_macro::{FieldDeserialize, FieldLen, FieldSerialize},
+};
 
-use crate::pdu_macro::{FieldDeserialize, FieldLen, FieldSerialize};
+const NANOS_PER_HOUR: u128 = 3_600_000_000_000;
 
-#[derive(Copy, Clone, Debug, Default)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
 /// Implemented according to IEEE 1278.1-2012 §6.2.14
 pub struct ClockTime {
     /// The hours since 0000h 1 January 1970 UTC (The Epoch)
     pub hour: u32,
     /// Time past the hour indicated in the hour field
+    ///
+    /// Formatted as a timestamp (§6.2.88): the upper 31 bits count units of 3600 s / 2^31 and
+    /// the least significant bit is set for absolute time.
     pub time_past_hour: u32,
 }
 
@@ -26,6 +37,44 @@ impl ClockTime {
         }
     }
 
+    /// Number of timestamp units in an hour
+    pub const UNITS_PER_HOUR: u32 = timestamp::UNITS_PER_HOUR;
+
+    /// A clock time `elapsed` after the epoch, or after some other reference if relative
+    ///
+    /// Hours beyond `u32::MAX` saturate; time past the hour is truncated to whole units.
+    #[must_use]
+    pub fn from_duration(elapsed: Duration, absolute: bool) -> Self {
+        let nanos = elapsed.as_nanos();
+        let hour = u32::try_from(nanos / NANOS_PER_HOUR).unwrap_or(u32::MAX);
+        let units = nanos % NANOS_PER_HOUR * u128::from(Self::UNITS_PER_HOUR) / NANOS_PER_HOUR;
+        #[allow(clippy::cast_possible_truncation)]
+        let units = units as u32;
+        Self {
+            hour,
+            time_past_hour: timestamp::from_units(units, absolute),
+        }
+    }
+
+    /// The time elapsed since the epoch, or since the reference if relative
+    #[must_use]
+    pub fn to_duration(&self) -> Duration {
+        let units = u128::from(timestamp::units(self.time_past_hour));
+        let nanos = u128::from(self.hour) * NANOS_PER_HOUR
+            + units * NANOS_PER_HOUR / u128::from(Self::UNITS_PER_HOUR);
+        #[allow(clippy::cast_possible_truncation)]
+        Duration::new(
+            (nanos / 1_000_000_000) as u64,
+            (nanos % 1_000_000_000) as u32,
+        )
+    }
+
+    /// Whether the time is absolute, i.e. counted from the epoch
+    #[must_use]
+    pub const fn is_absolute(&self) -> bool {
+        timestamp::is_absolute(self.time_past_hour)
+    }
+
     pub fn serialize(&self, buf: &mut BytesMut) {
         buf.put_u32(self.hour);
         buf.put_u32(self.time_past_hour);
@@ -56,3 +105,97 @@ impl FieldLen for ClockTime {
         8
     }
 }
+
+impl TryFrom<DateTime<Utc>> for ClockTime {
+    type Error = DISError;
+
+    /// An absolute clock time; times before the epoch cannot be represented
+    fn try_from(time: DateTime<Utc>) -> Result<Self, Self::Error> {
+        let elapsed = (time - DateTime::UNIX_EPOCH).to_std().map_err(|_| {
+            DISError::invalid_field(
+                "ClockTime".to_string(),
+                time.to_rfc3339(),
+                "before the Unix epoch".to_string(),
+            )
+        })?;
+        Ok(Self::from_duration(elapsed, true))
+    }
+}
+
+impl TryFrom<ClockTime> for DateTime<Utc> {
+    type Error = DISError;
+
+    /// The UTC time of a clock time counted from the epoch
+    fn try_from(time: ClockTime) -> Result<Self, Self::Error> {
+        TimeDelta::from_std(time.to_duration())
+            .ok()
+            .and_then(|elapsed| Self::UNIX_EPOCH.checked_add_signed(elapsed))
+            .ok_or_else(|| {
+                DISError::invalid_field(
+                    "ClockTime".to_string(),
+                    format!("{}h", time.hour),
+                    "outside the range of chrono::DateTime".to_string(),
+                )
+            })
+    }
+}
+
+impl TryFrom<TimeDelta> for ClockTime {
+    type Error = DISError;
+
+    /// A relative clock time; negative durations cannot be represented
+    fn try_from(elapsed: TimeDelta) -> Result<Self, Self::Error> {
+        let elapsed = elapsed.to_std().map_err(|_| {
+            DISError::invalid_field(
+                "ClockTime".to_string(),
+                elapsed.to_string(),
+                "negative duration".to_string(),
+            )
+        })?;
+        Ok(Self::from_duration(elapsed, false))
+    }
+}
+
+impl From<ClockTime> for TimeDelta {
+    fn from(time: ClockTime) -> Self {
+        Self::from_std(time.to_duration()).unwrap_or(Self::MAX)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn converts_to_and_from_chrono() {
+        let time = Utc
+            .with_ymd_and_hms(2025, 3, 14, 15, 9, 26)
+            .single()
+            .expect("a valid date");
+        let clock_time = ClockTime::try_from(time).expect("after the epoch");
+        assert!(clock_time.is_absolute());
+        assert_eq!(clock_time.hour, 483_879);
+        assert_eq!(clock_time.time_past_hour >> 1, 337_632_151);
+
+        let back = DateTime::<Utc>::try_from(clock_time).expect("in range");
+        assert!((time - back).abs() < TimeDelta::microseconds(2));
+
+        let before_epoch = Utc
+            .with_ymd_and_hms(1969, 12, 31, 23, 0, 0)
+            .single()
+            .expect("a valid date");
+        assert!(ClockTime::try_from(before_epoch).is_err());
+    }
+
+    #[test]
+    fn relative_times_convert_to_time_deltas() {
+        let elapsed = TimeDelta::minutes(90);
+        let clock_time = ClockTime::try_from(elapsed).expect("not negative");
+        assert!(!clock_time.is_absolute());
+        assert_eq!(clock_time, ClockTime::new(1, ClockTime::UNITS_PER_HOUR));
+        assert_eq!(TimeDelta::from(clock_time), elapsed);
+        assert!(ClockTime::try_from(-elapsed).is_err());
+    }
+}
@@ -5,8 +5,141 @@
 //     Licensed under the BSD 2-Clause License
 
 use bytes::{Buf, BufMut, BytesMut};
+use num_traits::FromPrimitive;
 
-use crate::pdu_macro::{FieldDeserialize, FieldLen, FieldSerialize};
+use crate::{
+    common::{
+        SerializedLength,
+        data_types::{
+            EntityCoordinateVector, EntityId, EntityType, EulerAngles, WorldCoordinate,
+            linear_velocity::LinearVelocity,
+        },
+        dis_error::DISError,
+        enums::VariableRecordTypes,
+    },
+    pdu_macro::{FieldDeserialize, FieldLen, FieldMinLen, FieldSerialize},
+};
+
+/// A value that fits the 32-bit value of a fixed datum record
+pub trait FixedDatumValue: Sized {
+    fn to_fixed_datum(&self) -> u32;
+    fn from_fixed_datum(value: u32) -> Self;
+}
+
+impl FixedDatumValue for u32 {
+    fn to_fixed_datum(&self) -> u32 {
+        *self
+    }
+
+    fn from_fixed_datum(value: u32) -> Self {
+        value
+    }
+}
+
+impl FixedDatumValue for i32 {
+    fn to_fixed_datum(&self) -> u32 {
+        u32::from_ne_bytes(self.to_ne_bytes())
+    }
+
+    fn from_fixed_datum(value: u32) -> Self {
+        Self::from_ne_bytes(value.to_ne_bytes())
+    }
+}
+
+impl FixedDatumValue for f32 {
+    fn to_fixed_datum(&self) -> u32 {
+        self.to_bits()
+    }
+
+    fn from_fixed_datum(value: u32) -> Self {
+        Self::from_bits(value)
+    }
+}
+
+/// A value carried in the value field of a variable datum record
+pub trait VariableDatumValue: Sized {
+    /// The octets of the value and its length in bits
+    fn to_variable_datum(&self) -> (Vec<u8>, u32);
+
+    /// The value held in `length_bits` bits of `value`, or `None` if they do not hold one
+    fn from_variable_datum(value: &[u8], length_bits: u32) -> Option<Self>;
+}
+
+/// Number of bits in `octets` octets, as written in the datum length field
+fn bit_length(octets: usize) -> u32 {
+    u32::try_from(octets * 8).unwrap_or(u32::MAX)
+}
+
+macro_rules! impl_variable_datum_for_number {
+    ($($t:ty),*) => {
+        $(
+            impl VariableDatumValue for $t {
+                fn to_variable_datum(&self) -> (Vec<u8>, u32) {
+                    let octets = self.to_be_bytes().to_vec();
+                    let length_bits = bit_length(octets.len());
+                    (octets, length_bits)
+                }
+
+                fn from_variable_datum(value: &[u8], length_bits: u32) -> Option<Self> {
+                    if length_bits != bit_length(size_of::<$t>()) {
+                        return None;
+                    }
+                    value
+                        .get(..size_of::<$t>())
+                        .and_then(|octets| octets.try_into().ok())
+                        .map(<$t>::from_be_bytes)
+                }
+            }
+        )*
+    };
+}
+
+impl_variable_datum_for_number!(u8, u16, u32, u64, i8, i16, i32, i64, f32, f64);
+
+macro_rules! impl_variable_datum_for_record {
+    ($($t:ty),*) => {
+        $(
+            impl VariableDatumValue for $t {
+                fn to_variable_datum(&self) -> (Vec<u8>, u32) {
+                    let mut buf = BytesMut::with_capacity(<$t>::LENGTH);
+                    self.serialize(&mut buf);
+                    (buf.to_vec(), bit_length(<$t>::LENGTH))
+                }
+
+                fn from_variable_datum(value: &[u8], length_bits: u32) -> Option<Self> {
+                    if length_bits != bit_length(<$t>::LENGTH) || value.len() < <$t>::LENGTH {
+                        return None;
+                    }
+                    let mut buf = value;
+                    Some(<$t>::deserialize(&mut buf))
+                }
+            }
+        )*
+    };
+}
+
+impl_variable_datum_for_record!(
+    EntityId,
+    EntityType,
+    EntityCoordinateVector,
+    EulerAngles,
+    LinearVelocity,
+    WorldCoordinate
+);
+
+/// A string is carried as its UTF-8 octets, eight bits each, without a terminator
+impl VariableDatumValue for String {
+    fn to_variable_datum(&self) -> (Vec<u8>, u32) {
+        (self.as_bytes().to_vec(), bit_length(self.len()))
+    }
+
+    fn from_variable_datum(value: &[u8], length_bits: u32) -> Option<Self> {
+        let octets = value.get(..usize::try_from(length_bits / 8).ok()?)?;
+        let text = std::str::from_utf8(octets).ok()?;
+        // Some simulations pad strings with NULs to a fixed width
+        Some(text.trim_end_matches('\0').to_string())
+    }
+}
 
 #[derive(Clone, Debug)]
 pub struct FixedDatumRecord {
@@ -15,6 +148,30 @@ pub struct FixedDatumRecord {
 }
 
 impl FixedDatumRecord {
+    #[must_use]
+    pub fn new<T: FixedDatumValue>(datum_id: VariableRecordTypes, value: &T) -> Self {
+        Self {
+            datum_id: datum_id as u32,
+            datum_value: value.to_fixed_datum(),
+        }
+    }
+
+    /// The datum ID, if it is a known variable record type
+    #[must_use]
+    pub fn datum_type(&self) -> Option<VariableRecordTypes> {
+        VariableRecordTypes::from_u32(self.datum_id)
+    }
+
+    /// The value interpreted as `T`
+    #[must_use]
+    pub fn value<T: FixedDatumValue>(&self) -> T {
+        T::from_fixed_datum(self.datum_value)
+    }
+
+    pub fn set_value<T: FixedDatumValue>(&mut self, value: &T) {
+        self.datum_value = value.to_fixed_datum();
+    }
+
     pub fn serialize(&self, buf: &mut BytesMut) {
         buf.put_u32(self.datum_id);
         buf.put_u32(self.datum_value);
@@ -48,6 +205,10 @@ impl FieldLen for FixedDatumRecord {
     }
 }
 
+impl FieldMinLen for FixedDatumRecord {
+    const MIN_LEN: usize = 8;
+}
+
 #[derive(Debug, Clone)]
 pub struct VariableDatumRecord {
     pub datum_id: u32,
@@ -56,41 +217,85 @@ pub struct VariableDatumRecord {
 }
 
 impl VariableDatumRecord {
+    #[must_use]
+    pub fn new<T: VariableDatumValue>(datum_id: VariableRecordTypes, value: &T) -> Self {
+        let (value, length_bits) = value.to_variable_datum();
+        Self {
+            datum_id: datum_id as u32,
+            length_bits,
+            value,
+        }
+    }
+
+    /// The datum ID, if it is a known variable record type
+    #[must_use]
+    pub fn datum_type(&self) -> Option<VariableRecordTypes> {
+        VariableRecordTypes::from_u32(self.datum_id)
+    }
+
+    /// The value interpreted as `T`, or `None` if the datum does not hold a `T`
+    #[must_use]
+    pub fn value<T: VariableDatumValue>(&self) -> Option<T> {
+        T::from_variable_datum(&self.value, self.length_bits)
+    }
+
+    /// Sets the value and its length in bits
+    pub fn set_value<T: VariableDatumValue>(&mut self, value: &T) {
+        (self.value, self.length_bits) = value.to_variable_datum();
+    }
+
     const fn bytes_count(length_bits: u32) -> usize {
         (length_bits as usize).div_ceil(8)
     }
 
+    /// Number of octets of the value field including the padding to a 64-bit boundary
+    const fn padded_bytes_count(length_bits: u32) -> usize {
+        (length_bits as usize).div_ceil(64) * 8
+    }
+
+    /// Checks that the datum length matches the number of octets in the value
+    ///
+    /// # Errors
+    /// Returns `DISError::InvalidFieldValue` if the value holds more or fewer octets than the
+    /// datum length calls for.
+    pub fn validate(&self) -> Result<(), DISError> {
+        let expected_bytes = Self::bytes_count(self.length_bits);
+        if self.value.len() == expected_bytes {
+            Ok(())
+        } else {
+            Err(DISError::invalid_field(
+                "length_bits".to_string(),
+                self.length_bits.to_string(),
+                format!(
+                    "the value holds {} octets, {expected_bytes} expected",
+                    self.value.len()
+                ),
+            ))
+        }
+    }
+
+    /// Writes the record; a value that does not match the datum length (see
+    /// [`Self::validate`]) is truncated or zero-filled so the record stays well formed. PDUs
+    /// refuse to serialize such a record instead.
     pub fn serialize(&self, buf: &mut BytesMut) {
         buf.put_u32(self.datum_id);
         buf.put_u32(self.length_bits);
 
         let expected_bytes = Self::bytes_count(self.length_bits);
-        if self.value.len() != expected_bytes {
-            // return Err(DISError::SerializationError { msg: "Length of datum does not equal expected bytes", source: None });
-        }
-        for i in 0..self.value.len() {
-            buf.put_u8(self.value[i]);
-        }
-
-        let pad_bits = (64u32 - (self.length_bits % 64)) % 64;
-        let pad_bytes = (pad_bits / 8) as usize;
-        if pad_bytes > 0 {
-            let zeros = vec![0u8; pad_bytes];
-            for z in zeros {
-                buf.put_u8(z);
-            }
-        }
+        let written = self.value.len().min(expected_bytes);
+        buf.put_slice(&self.value[..written]);
+        buf.put_bytes(0, Self::padded_bytes_count(self.length_bits) - written);
     }
 
     pub fn deserialize<B: Buf>(buf: &mut B) -> Self {
         let datum_id = buf.get_u32();
         let length_bits = buf.get_u32();
-        let value_bytes = Self::bytes_count(length_bits);
+        let value_bytes = Self::bytes_count(length_bits).min(buf.remaining());
 
         let mut value = vec![0u8; value_bytes];
-        for _ in 0..value_bytes {
-            value.push(buf.get_u8());
-        }
+        buf.copy_to_slice(&mut value);
+        let padding = Self::padded_bytes_count(length_bits) - Self::bytes_count(length_bits);
+        buf.advance(padding.min(buf.remaining()));
 
         Self {
             datum_id,
@@ -104,6 +309,10 @@ impl FieldSerialize for VariableDatumRecord {
     fn serialize_field(&self, buf: &mut BytesMut) {
         self.serialize(buf);
     }
+
+    fn validate_field(&self) -> Result<(), DISError> {
+        self.validate()
+    }
 }
 
 impl FieldDeserialize for VariableDatumRecord {
@@ -114,6 +323,138 @@ impl FieldDeserialize for VariableDatumRecord {
 
 impl FieldLen for VariableDatumRecord {
     fn field_len(&self) -> usize {
-        self.datum_id.field_len() + self.length_bits.field_len() + self.value.field_len()
+        self.datum_id.field_len()
+            + self.length_bits.field_len()
+            + Self::padded_bytes_count(self.length_bits)
+    }
+}
+
+impl FieldMinLen for VariableDatumRecord {
+    /// The datum ID and length in bits
+    const MIN_LEN: usize = 8;
+}
+
+/// The fixed and variable datum records of a PDU, built and read by datum ID
+#[derive(Clone, Debug, Default)]
+pub struct DatumSet {
+    pub fixed: Vec<FixedDatumRecord>,
+    pub variable: Vec<VariableDatumRecord>,
+}
+
+impl DatumSet {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            fixed: Vec::new(),
+            variable: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_fixed<T: FixedDatumValue>(
+        mut self,
+        datum_id: VariableRecordTypes,
+        value: &T,
+    ) -> Self {
+        self.push_fixed(datum_id, value);
+        self
+    }
+
+    #[must_use]
+    pub fn with_variable<T: VariableDatumValue>(
+        mut self,
+        datum_id: VariableRecordTypes,
+        value: &T,
+    ) -> Self {
+        self.push_variable(datum_id, value);
+        self
+    }
+
+    pub fn push_fixed<T: FixedDatumValue>(&mut self, datum_id: VariableRecordTypes, value: &T) {
+        self.fixed.push(FixedDatumRecord::new(datum_id, value));
+    }
+
+    pub fn push_variable<T: VariableDatumValue>(
+        &mut self,
+        datum_id: VariableRecordTypes,
+        value: &T,
+    ) {
+        self.variable
+            .push(VariableDatumRecord::new(datum_id, value));
+    }
+
+    /// The value of the first fixed datum with the given ID
+    #[must_use]
+    pub fn fixed<T: FixedDatumValue>(&self, datum_id: VariableRecordTypes) -> Option<T> {
+        self.fixed
+            .iter()
+            .find(|record| record.datum_id == datum_id as u32)
+            .map(FixedDatumRecord::value)
+    }
+
+    /// The value of the first variable datum with the given ID, if it holds a `T`
+    #[must_use]
+    pub fn variable<T: VariableDatumValue>(&self, datum_id: VariableRecordTypes) -> Option<T> {
+        self.variable
+            .iter()
+            .find(|record| record.datum_id == datum_id as u32)
+            .and_then(VariableDatumRecord::value)
     }
+
+    #[must_use]
+    pub fn number_of_fixed_datum_records(&self) -> u32 {
+        u32::try_from(self.fixed.len()).unwrap_or(u32::MAX)
+    }
+
+    #[must_use]
+    pub fn number_of_variable_datum_records(&self) -> u32 {
+        u32::try_from(self.variable.len()).unwrap_or(u32::MAX)
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.fixed.is_empty() && self.variable.is_empty()
+    }
+
+    /// Checks every variable datum record, see [`VariableDatumRecord::validate`]
+    ///
+    /// # Errors
+    /// Returns the error of the first variable datum record that does not validate.
+    pub fn validate(&self) -> Result<(), DISError> {
+        self.variable
+            .iter()
+            .try_for_each(VariableDatumRecord::validate)
+    }
+}
+
+/// Adds `datum_set` and `set_datum_set` to PDUs that carry fixed and variable datum records
+macro_rules! impl_datum_set {
+    ($($pdu:ty),* $(,)?) => {
+        $(
+            impl $pdu {
+                /// The fixed and variable datum records of this PDU
+                #[must_use]
+                pub fn datum_set(&self) -> $crate::common::data_types::datum_records::DatumSet {
+                    $crate::common::data_types::datum_records::DatumSet {
+                        fixed: self.fixed_datum_records.clone(),
+                        variable: self.variable_datum_records.clone(),
+                    }
+                }
+
+                /// Sets the fixed and variable datum records and their counts
+                pub fn set_datum_set(
+                    &mut self,
+                    datums: $crate::common::data_types::datum_records::DatumSet,
+                ) {
+                    self.number_of_fixed_datum_records = datums.number_of_fixed_datum_records();
+                    self.number_of_variable_datum_records =
+                        datums.number_of_variable_datum_records();
+                    self.fixed_datum_records = datums.fixed;
+                    self.variable_datum_records = datums.variable;
+                }
+            }
+        )*
+    };
 }
+
+pub(crate) use impl_datum_set;
@@ -3,17 +3,63 @@
 //
 //     Licensed under the BSD-2-Clause License
 
+#![allow(clippy::must_use_candidate)]
+
 use bytes::{Buf, BufMut, BytesMut};
+use modular_bitfield::prelude::*;
 
 use crate::{
     common::{
         data_types::{EntityCoordinateVector, EventId},
-        enums::{ComponentDamageStatus, ComponentIdentification, ComponentVisualSmokeColor},
+        enums::{
+            ComponentDamageStatus, ComponentIdentification, ComponentVisualDamageStatusSmoke,
+            ComponentVisualDamageStatusSurfaceDamage, ComponentVisualSmokeColor,
+            VariableRecordTypes,
+        },
     },
-    pdu_macro::{FieldDeserialize, FieldLen, FieldSerialize},
+    pdu_macro::{FieldDeserialize, FieldLen, FieldMinLen, FieldSerialize},
 };
 
-#[derive(Copy, Clone, Debug, Default)]
+// SISO-REF-010-2023 ComponentVisualDamageStatus [UID 317]
+#[bitfield(bits = 8)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ComponentVisualDamageStatus {
+    pub is_fire_present: bool,
+    pub smoke: ComponentVisualDamageStatusSmoke,
+    pub surface_damage: ComponentVisualDamageStatusSurfaceDamage,
+    #[skip]
+    __reserved: B3,
+}
+
+impl ComponentVisualDamageStatus {
+    #[must_use]
+    pub const fn to_u8(&self) -> u8 {
+        self.into_bytes()[0]
+    }
+
+    #[must_use]
+    pub const fn from_u8(bits: u8) -> Self {
+        Self::from_bytes([bits])
+    }
+}
+
+impl Default for ComponentVisualDamageStatus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FieldLen for ComponentVisualDamageStatus {
+    fn field_len(&self) -> usize {
+        1
+    }
+}
+
+/// Length in octets of a DE Damage Description record
+const DE_DAMAGE_DESCRIPTION_LENGTH: u16 = 40;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+/// Implemented according to IEEE 1278.1-2012 §6.2.20.4
 pub struct DirectedEnergyDamage {
     pub record_type: u32,
     pub record_length: u16,
@@ -23,7 +69,7 @@ pub struct DirectedEnergyDamage {
     pub temperature: f32,
     pub component_identification: ComponentIdentification,
     pub component_damage_status: ComponentDamageStatus,
-    pub component_visual_damage_status: u8, // TODO(cameron): implement ComponentVisualDamageStatus record
+    pub component_visual_damage_status: ComponentVisualDamageStatus,
     pub component_visual_smoke_color: ComponentVisualSmokeColor,
     pub fire_event_id: EventId,
     padding2: u16,
@@ -40,7 +86,7 @@ impl DirectedEnergyDamage {
         temperature: f32,
         component_identification: ComponentIdentification,
         component_damage_status: ComponentDamageStatus,
-        component_visual_damage_status: u8,
+        component_visual_damage_status: ComponentVisualDamageStatus,
         component_visual_smoke_color: ComponentVisualSmokeColor,
         fire_event_id: EventId,
     ) -> Self {
@@ -60,6 +106,53 @@ impl DirectedEnergyDamage {
         }
     }
 
+    /// Creates a DE Damage Description record for the given component with the record type and
+    /// length already filled in
+    #[must_use]
+    pub fn for_component(
+        component_identification: ComponentIdentification,
+        component_damage_status: ComponentDamageStatus,
+    ) -> Self {
+        Self {
+            component_identification,
+            component_damage_status,
+            ..Self::default()
+        }
+    }
+
+    /// Sets the location, diameter and temperature of the damaged area
+    #[must_use]
+    pub const fn with_damage_area(
+        mut self,
+        damage_location: EntityCoordinateVector,
+        damage_diameter: f32,
+        temperature: f32,
+    ) -> Self {
+        self.damage_location = damage_location;
+        self.damage_diameter = damage_diameter;
+        self.temperature = temperature;
+        self
+    }
+
+    /// Sets the visual effects image generators should render for the damaged component
+    #[must_use]
+    pub const fn with_visual_damage(
+        mut self,
+        component_visual_damage_status: ComponentVisualDamageStatus,
+        component_visual_smoke_color: ComponentVisualSmokeColor,
+    ) -> Self {
+        self.component_visual_damage_status = component_visual_damage_status;
+        self.component_visual_smoke_color = component_visual_smoke_color;
+        self
+    }
+
+    /// Sets the event ID of the DE Fire PDU that caused the damage
+    #[must_use]
+    pub const fn with_fire_event_id(mut self, fire_event_id: EventId) -> Self {
+        self.fire_event_id = fire_event_id;
+        self
+    }
+
     pub fn serialize(&self, buf: &mut BytesMut) {
         buf.put_u32(self.record_type);
         buf.put_u16(self.record_length);
@@ -69,7 +162,7 @@ impl DirectedEnergyDamage {
         buf.put_f32(self.temperature);
         buf.put_u8(self.component_identification as u8);
         buf.put_u8(self.component_damage_status as u8);
-        buf.put_u8(self.component_visual_damage_status);
+        buf.put_u8(self.component_visual_damage_status.to_u8());
         buf.put_u8(self.component_visual_smoke_color as u8);
         self.fire_event_id.serialize(buf);
         buf.put_u16(self.padding2);
@@ -85,7 +178,7 @@ impl DirectedEnergyDamage {
             temperature: buf.get_f32(),
             component_identification: ComponentIdentification::deserialize(buf),
             component_damage_status: ComponentDamageStatus::deserialize(buf),
-            component_visual_damage_status: buf.get_u8(),
+            component_visual_damage_status: ComponentVisualDamageStatus::from_u8(buf.get_u8()),
             component_visual_smoke_color: ComponentVisualSmokeColor::deserialize(buf),
             fire_event_id: EventId::deserialize(buf),
             padding2: buf.get_u16(),
@@ -93,6 +186,25 @@ impl DirectedEnergyDamage {
     }
 }
 
+impl Default for DirectedEnergyDamage {
+    fn default() -> Self {
+        Self {
+            record_type: VariableRecordTypes::DirectedEnergyDamageDescriptionrecord as u32,
+            record_length: DE_DAMAGE_DESCRIPTION_LENGTH,
+            padding: 0_u16,
+            damage_location: EntityCoordinateVector::default(),
+            damage_diameter: 0.0,
+            temperature: 0.0,
+            component_identification: ComponentIdentification::default(),
+            component_damage_status: ComponentDamageStatus::default(),
+            component_visual_damage_status: ComponentVisualDamageStatus::default(),
+            component_visual_smoke_color: ComponentVisualSmokeColor::default(),
+            fire_event_id: EventId::default(),
+            padding2: 0_u16,
+        }
+    }
+}
+
 impl FieldSerialize for DirectedEnergyDamage {
     fn serialize_field(&self, buf: &mut BytesMut) {
         self.serialize(buf);
@@ -121,3 +233,7 @@ impl FieldLen for DirectedEnergyDamage {
             + self.padding2.field_len()
     }
 }
+
+impl FieldMinLen for DirectedEnergyDamage {
+    const MIN_LEN: usize = DE_DAMAGE_DESCRIPTION_LENGTH as usize;
+}
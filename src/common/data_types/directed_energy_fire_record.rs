@@ -0,0 +1,313 @@
+//     open-dis-rust - Rust implementation of the IEEE-1278.1 Distributed Interactive Simulation
+//     Copyright (C) 2025 Cameron Howell
+//
+//     Licensed under the BSD-2-Clause License
+
+use bytes::{Buf, BufMut, BytesMut};
+use num_traits::FromPrimitive;
+
+use crate::{
+    common::{
+        SerializedLength,
+        constants::padding_to_64_bits,
+        data_types::{
+            EntityCoordinateVector, EntityId, Vector3Float, WorldCoordinate,
+            beam_antenna_pattern::BeamAntennaPattern,
+        },
+        enums::{ComponentIdentification, DEPrecisionAimpointBeamSpotType, VariableRecordTypes},
+    },
+    pdu_macro::{FieldDeserialize, FieldLen, FieldMinLen, FieldSerialize},
+};
+
+/// Length in octets of the record type, record length and padding fields
+const DE_RECORD_HEADER_LENGTH: usize = 8;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+/// DE Target Energy Deposition record, carried in the DE Area Aimpoint record
+pub struct DETargetEnergyDeposition {
+    pub target_entity_id: EntityId,
+    padding: u16,
+    /// Peak irradiance of the deposited energy in W/m²
+    pub peak_irradiance: f32,
+}
+
+impl DETargetEnergyDeposition {
+    #[must_use]
+    pub const fn new(target_entity_id: EntityId, peak_irradiance: f32) -> Self {
+        Self {
+            target_entity_id,
+            padding: 0,
+            peak_irradiance,
+        }
+    }
+
+    pub fn serialize(&self, buf: &mut BytesMut) {
+        self.target_entity_id.serialize(buf);
+        buf.put_u16(self.padding);
+        buf.put_f32(self.peak_irradiance);
+    }
+
+    pub fn deserialize<B: Buf>(buf: &mut B) -> Self {
+        Self {
+            target_entity_id: EntityId::deserialize(buf),
+            padding: buf.get_u16(),
+            peak_irradiance: buf.get_f32(),
+        }
+    }
+}
+
+impl SerializedLength for DETargetEnergyDeposition {
+    const LENGTH: usize = 12;
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+/// Implemented according to IEEE 1278.1-2012 §6.2.20.3
+pub struct DEPrecisionAimpoint {
+    pub target_spot_location: WorldCoordinate,
+    pub target_spot_entity_location: EntityCoordinateVector,
+    pub target_spot_velocity: Vector3Float,
+    pub target_spot_acceleration: Vector3Float,
+    pub target_entity_id: EntityId,
+    pub target_component_id: ComponentIdentification,
+    pub beam_spot_type: DEPrecisionAimpointBeamSpotType,
+    /// Semi-major axis of the beam spot cross section in meters
+    pub beam_spot_cross_section_semi_major_axis: f32,
+    /// Semi-minor axis of the beam spot cross section in meters
+    pub beam_spot_cross_section_semi_minor_axis: f32,
+    /// Orientation of the beam spot cross section in radians
+    pub beam_spot_cross_section_orientation_angle: f32,
+    /// Peak irradiance of the beam spot in W/m²
+    pub peak_irradiance: f32,
+    padding: u32,
+}
+
+impl DEPrecisionAimpoint {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn serialize(&self, buf: &mut BytesMut) {
+        self.target_spot_location.serialize(buf);
+        self.target_spot_entity_location.serialize(buf);
+        self.target_spot_velocity.serialize(buf);
+        self.target_spot_acceleration.serialize(buf);
+        self.target_entity_id.serialize(buf);
+        buf.put_u8(self.target_component_id as u8);
+        buf.put_u8(self.beam_spot_type as u8);
+        buf.put_f32(self.beam_spot_cross_section_semi_major_axis);
+        buf.put_f32(self.beam_spot_cross_section_semi_minor_axis);
+        buf.put_f32(self.beam_spot_cross_section_orientation_angle);
+        buf.put_f32(self.peak_irradiance);
+        buf.put_u32(self.padding);
+    }
+
+    pub fn deserialize<B: Buf>(buf: &mut B) -> Self {
+        Self {
+            target_spot_location: WorldCoordinate::deserialize(buf),
+            target_spot_entity_location: EntityCoordinateVector::deserialize(buf),
+            target_spot_velocity: Vector3Float::deserialize(buf),
+            target_spot_acceleration: Vector3Float::deserialize(buf),
+            target_entity_id: EntityId::deserialize(buf),
+            target_component_id: ComponentIdentification::deserialize(buf),
+            beam_spot_type: DEPrecisionAimpointBeamSpotType::deserialize(buf),
+            beam_spot_cross_section_semi_major_axis: buf.get_f32(),
+            beam_spot_cross_section_semi_minor_axis: buf.get_f32(),
+            beam_spot_cross_section_orientation_angle: buf.get_f32(),
+            peak_irradiance: buf.get_f32(),
+            padding: buf.get_u32(),
+        }
+    }
+}
+
+impl SerializedLength for DEPrecisionAimpoint {
+    /// Length of the record-specific fields, excluding the record header
+    const LENGTH: usize = 88;
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+/// Implemented according to IEEE 1278.1-2012 §6.2.20.2
+///
+/// The record counts are derived from the lists on serialization.
+pub struct DEAreaAimpoint {
+    pub beam_antenna_patterns: Vec<BeamAntennaPattern>,
+    pub target_energy_depositions: Vec<DETargetEnergyDeposition>,
+}
+
+impl DEAreaAimpoint {
+    #[must_use]
+    pub const fn new(
+        beam_antenna_patterns: Vec<BeamAntennaPattern>,
+        target_energy_depositions: Vec<DETargetEnergyDeposition>,
+    ) -> Self {
+        Self {
+            beam_antenna_patterns,
+            target_energy_depositions,
+        }
+    }
+
+    /// Length in octets of the record-specific fields, excluding padding
+    #[must_use]
+    pub const fn body_length(&self) -> usize {
+        4 + self.beam_antenna_patterns.len() * BeamAntennaPattern::LENGTH
+            + self.target_energy_depositions.len() * DETargetEnergyDeposition::LENGTH
+    }
+
+    pub fn serialize(&self, buf: &mut BytesMut) {
+        buf.put_u16(u16::try_from(self.beam_antenna_patterns.len()).unwrap_or(u16::MAX));
+        buf.put_u16(u16::try_from(self.target_energy_depositions.len()).unwrap_or(u16::MAX));
+        for pattern in &self.beam_antenna_patterns {
+            pattern.serialize(buf);
+        }
+        for deposition in &self.target_energy_depositions {
+            deposition.serialize(buf);
+        }
+    }
+
+    pub fn deserialize<B: Buf>(buf: &mut B) -> Self {
+        let available = buf.remaining();
+        Self::deserialize_within(buf, available)
+    }
+
+    /// Deserializes the record from `available` octets, keeping only the beam antenna patterns
+    /// and target energy depositions that fit
+    fn deserialize_within<B: Buf>(buf: &mut B, available: usize) -> Self {
+        let mut available = available.saturating_sub(4);
+        let beam_antenna_pattern_count =
+            usize::from(buf.get_u16()).min(available / BeamAntennaPattern::LENGTH);
+        available -= beam_antenna_pattern_count * BeamAntennaPattern::LENGTH;
+        let target_energy_deposition_count =
+            usize::from(buf.get_u16()).min(available / DETargetEnergyDeposition::LENGTH);
+        Self {
+            beam_antenna_patterns: (0..beam_antenna_pattern_count)
+                .map(|_| BeamAntennaPattern::deserialize(buf))
+                .collect(),
+            target_energy_depositions: (0..target_energy_deposition_count)
+                .map(|_| DETargetEnergyDeposition::deserialize(buf))
+                .collect(),
+        }
+    }
+}
+
+/// A record carried in the DE Fire PDU, keyed by its record type
+#[derive(Clone, Debug, PartialEq)]
+pub enum DirectedEnergyFireRecord {
+    PrecisionAimpoint(DEPrecisionAimpoint),
+    AreaAimpoint(DEAreaAimpoint),
+    /// A record type this crate does not model; the record-specific fields are kept verbatim
+    Unknown {
+        record_type: u32,
+        record_specific_fields: Vec<u8>,
+    },
+}
+
+impl Default for DirectedEnergyFireRecord {
+    fn default() -> Self {
+        Self::PrecisionAimpoint(DEPrecisionAimpoint::default())
+    }
+}
+
+impl DirectedEnergyFireRecord {
+    /// The raw record type, as written on the wire
+    #[must_use]
+    pub const fn record_type(&self) -> u32 {
+        match self {
+            Self::PrecisionAimpoint(_) => VariableRecordTypes::DEPrecisionAimpointrecord as u32,
+            Self::AreaAimpoint(_) => VariableRecordTypes::DEAreaAimpointrecord as u32,
+            Self::Unknown { record_type, .. } => *record_type,
+        }
+    }
+
+    const fn body_length(&self) -> usize {
+        match self {
+            Self::PrecisionAimpoint(_) => DEPrecisionAimpoint::LENGTH,
+            Self::AreaAimpoint(record) => record.body_length(),
+            Self::Unknown {
+                record_specific_fields,
+                ..
+            } => record_specific_fields.len(),
+        }
+    }
+
+    const fn padding_length(&self) -> usize {
+        padding_to_64_bits(DE_RECORD_HEADER_LENGTH + self.body_length())
+    }
+
+    /// The length of the whole record in octets, including padding
+    #[must_use]
+    pub fn record_length(&self) -> u16 {
+        u16::try_from(self.field_len()).unwrap_or(u16::MAX)
+    }
+
+    pub fn serialize(&self, buf: &mut BytesMut) {
+        buf.put_u32(self.record_type());
+        buf.put_u16(self.record_length());
+        buf.put_u16(0u16);
+        match self {
+            Self::PrecisionAimpoint(record) => record.serialize(buf),
+            Self::AreaAimpoint(record) => record.serialize(buf),
+            Self::Unknown {
+                record_specific_fields,
+                ..
+            } => buf.put_slice(record_specific_fields),
+        }
+        buf.put_bytes(0u8, self.padding_length());
+    }
+
+    pub fn deserialize<B: Buf>(buf: &mut B) -> Self {
+        let record_type = buf.get_u32();
+        let record_length = usize::from(buf.get_u16());
+        let _padding = buf.get_u16();
+
+        let available = record_length
+            .saturating_sub(DE_RECORD_HEADER_LENGTH)
+            .min(buf.remaining());
+        let remaining_before = buf.remaining();
+        let record = match VariableRecordTypes::from_u32(record_type) {
+            Some(VariableRecordTypes::DEPrecisionAimpointrecord)
+                if available >= DEPrecisionAimpoint::LENGTH =>
+            {
+                Self::PrecisionAimpoint(DEPrecisionAimpoint::deserialize(buf))
+            }
+            Some(VariableRecordTypes::DEAreaAimpointrecord) if available >= 4 => {
+                Self::AreaAimpoint(DEAreaAimpoint::deserialize_within(buf, available))
+            }
+            _ => {
+                let mut record_specific_fields = vec![0u8; available];
+                buf.copy_to_slice(&mut record_specific_fields);
+                Self::Unknown {
+                    record_type,
+                    record_specific_fields,
+                }
+            }
+        };
+
+        // Skip the padding that aligns the record to a 64-bit boundary
+        let consumed = remaining_before - buf.remaining();
+        buf.advance(available.saturating_sub(consumed).min(buf.remaining()));
+
+        record
+    }
+}
+
+impl FieldSerialize for DirectedEnergyFireRecord {
+    fn serialize_field(&self, buf: &mut BytesMut) {
+        self.serialize(buf);
+    }
+}
+
+impl FieldDeserialize for DirectedEnergyFireRecord {
+    fn deserialize_field<B: Buf>(buf: &mut B) -> Self {
+        Self::deserialize(buf)
+    }
+}
+
+impl FieldLen for DirectedEnergyFireRecord {
+    fn field_len(&self) -> usize {
+        DE_RECORD_HEADER_LENGTH + self.body_length() + self.padding_length()
+    }
+}
+
+impl FieldMinLen for DirectedEnergyFireRecord {
+    const MIN_LEN: usize = DE_RECORD_HEADER_LENGTH;
+}
@@ -0,0 +1,72 @@
+//     open-dis-rust - Rust implementation of the IEEE-1278.1 Distributed Interactive Simulation
+//     Copyright (C) 2025 Cameron Howell
+//
+//     Licensed under the BSD-2-Clause License
+
+use num_traits::FromPrimitive;
+
+use crate::common::enums::{SignalEncodingClass, SignalEncodingType};
+
+/// Mask of the 14 bits that follow the encoding class
+const VALUE_MASK: u16 = 0x3FFF;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+/// The encoding scheme field of the Signal PDU, according to IEEE 1278.1-2012 §7.7.3
+///
+/// The two most significant bits hold the encoding class. The remaining 14 bits hold the
+/// encoding type for encoded audio, or the number of TDL messages for raw binary data.
+pub struct EncodingScheme {
+    pub class: SignalEncodingClass,
+    /// Encoding type or TDL message count, limited to 14 bits
+    pub value: u16,
+}
+
+impl EncodingScheme {
+    #[must_use]
+    pub const fn new(class: SignalEncodingClass, value: u16) -> Self {
+        Self { class, value }
+    }
+
+    /// Encoded audio of the given encoding type
+    #[must_use]
+    pub const fn audio(encoding_type: SignalEncodingType) -> Self {
+        Self::new(SignalEncodingClass::Encodedaudio, encoding_type as u16)
+    }
+
+    /// Raw binary data carrying `message_count` TDL messages
+    #[must_use]
+    pub const fn tdl_messages(message_count: u16) -> Self {
+        Self::new(SignalEncodingClass::RawBinaryData, message_count)
+    }
+
+    #[must_use]
+    pub fn from_u16(value: u16) -> Self {
+        Self {
+            class: SignalEncodingClass::from_u16(value >> 14).unwrap_or_default(),
+            value: value & VALUE_MASK,
+        }
+    }
+
+    #[must_use]
+    pub const fn to_u16(&self) -> u16 {
+        (self.class as u16) << 14 | (self.value & VALUE_MASK)
+    }
+
+    /// The audio encoding type, if this scheme carries encoded audio of a known type
+    #[must_use]
+    pub fn encoding_type(&self) -> Option<SignalEncodingType> {
+        match self.class {
+            SignalEncodingClass::Encodedaudio => SignalEncodingType::from_u16(self.value),
+            _ => None,
+        }
+    }
+
+    /// The number of TDL messages, if this scheme carries raw binary data
+    #[must_use]
+    pub const fn tdl_message_count(&self) -> Option<u16> {
+        match self.class {
+            SignalEncodingClass::RawBinaryData => Some(self.value),
+            _ => None,
+        }
+    }
+}
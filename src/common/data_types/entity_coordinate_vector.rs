@@ -11,7 +11,7 @@ use crate::{
     pdu_macro::{FieldDeserialize, FieldLen, FieldSerialize},
 };
 
-#[derive(Copy, Clone, Debug, Default)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
 /// Implemented according to IEEE 1278.1-2012 §6.2.96
 pub struct EntityCoordinateVector {
     /// Location along the X-axis relative to the entity's origin
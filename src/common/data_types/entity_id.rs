@@ -4,13 +4,13 @@
 //
 //     Licensed under the BSD 2-Clause License
 
-use crate::common::SerializedLength;
+use crate::common::{SerializedLength, constants::ALL_ENTITIES};
 
 use super::simulation_address::SimulationAddress;
 use crate::pdu_macro::{FieldDeserialize, FieldLen, FieldSerialize};
 use bytes::{Buf, BufMut, BytesMut};
 
-#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
 /// Implemented according to IEEE 1278.1-2012 §6.2.28
 pub struct EntityId {
     /// The simulation's designation associated with all object identifiers
@@ -28,6 +28,25 @@ impl EntityId {
         }
     }
 
+    /// Whether a PDU sent to this receiving ID is for the simulation at `address`
+    ///
+    /// The simulation handles PDUs sent to itself (`NO_ENTITY`), to any of its entities, or to
+    /// all of them, so only the site and application are compared; either may be a wildcard.
+    #[must_use]
+    pub const fn addresses_simulation(self, address: SimulationAddress) -> bool {
+        self.simulation_address.addresses(address)
+    }
+
+    /// Whether a PDU sent to this receiving ID is for the entity `entity`
+    ///
+    /// The entity number matches if equal or if this one is `ALL_ENTITIES`; a receiving ID with
+    /// `NO_ENTITY` addresses the simulation, not its entities.
+    #[must_use]
+    pub const fn addresses_entity(self, entity: Self) -> bool {
+        self.simulation_address.addresses(entity.simulation_address)
+            && (self.entity_id == ALL_ENTITIES || self.entity_id == entity.entity_id)
+    }
+
     pub fn serialize(&self, buf: &mut BytesMut) {
         self.simulation_address.serialize(buf);
         buf.put_u16(self.entity_id);
@@ -4,42 +4,591 @@
 //     Licensed under the BSD-2-Clause License
 
 use bytes::{Buf, BufMut, BytesMut};
+use num_traits::FromPrimitive;
 
-use crate::pdu_macro::{FieldDeserialize, FieldLen, FieldSerialize};
+use crate::{
+    common::{
+        constants::padding_to_64_bits,
+        data_types::{EntityType, EulerAngles, Vector3Float, WorldCoordinate},
+        enums::EnvironmentalProcessRecordType,
+    },
+    pdu_macro::{FieldDeserialize, FieldLen, FieldMinLen, FieldSerialize},
+};
 
-#[derive(Clone, Debug, Default)]
+/// Length in octets of the record type, length, index and padding fields
+const ENVIRONMENT_HEADER_LENGTH: usize = 8;
+
+/// Defines a fixed-layout environment record body along with its (de)serialization helpers
+macro_rules! environment_record {
+    (
+        $(#[$meta:meta])*
+        pub struct $name:ident {
+            $(
+                $(#[$fmeta:meta])*
+                pub $field:ident : $ftype:ty,
+            )*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Copy, Clone, Debug, Default, PartialEq)]
+        #[allow(clippy::derive_partial_eq_without_eq)]
+        pub struct $name {
+            $(
+                $(#[$fmeta])*
+                pub $field: $ftype,
+            )*
+        }
+
+        impl $name {
+            /// Length in octets of the record-specific fields, excluding padding
+            #[must_use]
+            pub fn body_length(&self) -> usize {
+                0 $( + self.$field.field_len() )*
+            }
+
+            pub fn serialize(&self, buf: &mut BytesMut) {
+                $( self.$field.serialize_field(buf); )*
+            }
+
+            pub fn deserialize<B: Buf>(buf: &mut B) -> Self {
+                Self {
+                    $( $field: <$ftype as FieldDeserialize>::deserialize_field(buf), )*
+                }
+            }
+
+            /// Deserializes the record if `available` octets hold all of its fields
+            fn deserialize_within<B: Buf>(buf: &mut B, available: usize) -> Option<Self> {
+                (available >= Self::default().body_length()).then(|| Self::deserialize(buf))
+            }
+        }
+    };
+}
+
+environment_record! {
+    /// COMBIC State record, SISO-REF-010 environment record type 256
+    pub struct CombicStateRecord {
+        /// Time in milliseconds since the obscurant was created
+        pub time_since_creation: u32,
+        /// Munition that produced the obscurant
+        pub munition_source_type: EntityType,
+        pub number_of_sources: u32,
+        pub geometry_index: u16,
+        pub padding: u16,
+        pub source_type: u32,
+        pub barrage_rate: f32,
+        pub barrage_duration: f32,
+        pub barrage_crosswind_length: f32,
+        pub barrage_downwind_length: f32,
+        pub detonation_velocity: Vector3Float,
+    }
+}
+
+environment_record! {
+    /// Flare State record, SISO-REF-010 environment record type 259
+    pub struct FlareStateRecord {
+        /// Time in milliseconds since the flare was created
+        pub time_since_creation: u32,
+        /// Munition that produced the flare
+        pub munition_source_type: EntityType,
+        pub number_of_intensity: u32,
+        pub number_of_sources: u32,
+        pub geometry_index: u16,
+    }
+}
+
+environment_record! {
+    /// Bounding Sphere record, SISO-REF-010 environment record type 65536
+    pub struct BoundingSphereRecord {
+        pub centroid_location: WorldCoordinate,
+        /// Radius in meters
+        pub radius: f32,
+    }
+}
+
+/// Uniform Geometry record, SISO-REF-010 environment record type 327680
+///
+/// Indicates the process is uniform throughout the environment and carries no fields
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct UniformGeometryRecord;
+
+impl UniformGeometryRecord {
+    #[must_use]
+    pub const fn body_length(&self) -> usize {
+        0
+    }
+
+    pub const fn serialize(&self, _buf: &mut BytesMut) {}
+
+    pub const fn deserialize<B: Buf>(_buf: &mut B) -> Self {
+        Self
+    }
+}
+
+environment_record! {
+    /// Point Record 1, SISO-REF-010 environment record type 655360
+    pub struct PointRecord1 {
+        pub location: WorldCoordinate,
+    }
+}
+
+environment_record! {
+    /// Line Record 1, SISO-REF-010 environment record type 786432
+    pub struct LineRecord1 {
+        pub start_point_location: WorldCoordinate,
+        pub end_point_location: WorldCoordinate,
+    }
+}
+
+environment_record! {
+    /// Sphere Record 1, SISO-REF-010 environment record type 851968
+    pub struct SphereRecord1 {
+        pub centroid_location: WorldCoordinate,
+        /// Radius in meters
+        pub radius: f32,
+    }
+}
+
+environment_record! {
+    /// Ellipsoid Record 1, SISO-REF-010 environment record type 1048576
+    pub struct EllipsoidRecord1 {
+        pub centroid_location: WorldCoordinate,
+        /// Standard deviations along each ellipsoid axis in meters
+        pub sigma: Vector3Float,
+        pub orientation: EulerAngles,
+    }
+}
+
+environment_record! {
+    /// Cone Record 1, SISO-REF-010 environment record type 3145728
+    pub struct ConeRecord1 {
+        pub vertex_location: WorldCoordinate,
+        pub orientation: EulerAngles,
+        /// Height in meters
+        pub height: f32,
+        /// Peak angle in radians
+        pub peak_angle: f32,
+    }
+}
+
+environment_record! {
+    /// Rectangular Volume Record 1, SISO-REF-010 environment record type 5242880
+    pub struct RectangularVolumeRecord1 {
+        pub corner_location: WorldCoordinate,
+        /// Lengths along each volume axis in meters
+        pub rectangle_length: Vector3Float,
+        pub orientation: EulerAngles,
+    }
+}
+
+environment_record! {
+    /// Rectangular Volume Record 3, SISO-REF-010 environment record type 83886080
+    pub struct RectangularVolumeRecord3 {
+        pub center_location: WorldCoordinate,
+        /// Lengths along each volume axis in meters
+        pub rectangle_length: Vector3Float,
+        pub orientation: EulerAngles,
+    }
+}
+
+environment_record! {
+    /// Point Record 2, the high-fidelity variant of Point Record 1, SISO-REF-010 environment
+    /// record type 167772160
+    pub struct PointRecord2 {
+        pub location: WorldCoordinate,
+        pub velocity: Vector3Float,
+    }
+}
+
+environment_record! {
+    /// Line Record 2, the high-fidelity variant of Line Record 1, SISO-REF-010 environment
+    /// record type 201326592
+    pub struct LineRecord2 {
+        pub start_point_location: WorldCoordinate,
+        pub end_point_location: WorldCoordinate,
+        pub start_point_velocity: Vector3Float,
+        pub end_point_velocity: Vector3Float,
+    }
+}
+
+environment_record! {
+    /// Sphere Record 2, the high-fidelity variant of Sphere Record 1, SISO-REF-010 environment
+    /// record type 218103808
+    pub struct SphereRecord2 {
+        pub centroid_location: WorldCoordinate,
+        /// Radius in meters
+        pub radius: f32,
+        /// Rate of change of the radius in meters per second
+        pub radius_rate: f32,
+        pub velocity: Vector3Float,
+        pub angular_velocity: Vector3Float,
+    }
+}
+
+environment_record! {
+    /// Ellipsoid Record 2, the high-fidelity variant of Ellipsoid Record 1, SISO-REF-010
+    /// environment record type 268435456
+    pub struct EllipsoidRecord2 {
+        pub centroid_location: WorldCoordinate,
+        /// Standard deviations along each ellipsoid axis in meters
+        pub sigma: Vector3Float,
+        /// Rate of change of each standard deviation in meters per second
+        pub sigma_rate: Vector3Float,
+        pub orientation: EulerAngles,
+        pub velocity: Vector3Float,
+        pub angular_velocity: Vector3Float,
+    }
+}
+
+environment_record! {
+    /// Cone Record 2, the high-fidelity variant of Cone Record 1, SISO-REF-010 environment
+    /// record type 805306368
+    pub struct ConeRecord2 {
+        pub vertex_location: WorldCoordinate,
+        pub orientation: EulerAngles,
+        pub velocity: Vector3Float,
+        pub angular_velocity: Vector3Float,
+        /// Height in meters
+        pub height: f32,
+        /// Rate of change of the height in meters per second
+        pub height_rate: f32,
+        /// Peak angle in radians
+        pub peak_angle: f32,
+        /// Rate of change of the peak angle in radians per second
+        pub peak_angle_rate: f32,
+    }
+}
+
+environment_record! {
+    /// Rectangular Volume Record 2, the high-fidelity variant of Rectangular Volume Record 1,
+    /// SISO-REF-010 environment record type 1342177280
+    pub struct RectangularVolumeRecord2 {
+        pub corner_location: WorldCoordinate,
+        /// Lengths along each volume axis in meters
+        pub rectangle_length: Vector3Float,
+        /// Rate of change of each length in meters per second
+        pub rectangle_length_rate: Vector3Float,
+        pub orientation: EulerAngles,
+        pub velocity: Vector3Float,
+        pub angular_velocity: Vector3Float,
+    }
+}
+
+environment_record! {
+    /// Gaussian Plume record, SISO-REF-010 environment record type 1610612736
+    pub struct GaussianPlumeRecord {
+        pub source_location: WorldCoordinate,
+        pub plume_orientation: EulerAngles,
+        /// Plume length in meters
+        pub plume_length: f32,
+        /// Plume width in meters
+        pub plume_width: f32,
+        /// Plume top height in meters
+        pub plume_top_height: f32,
+        /// Rate of change of the plume length in meters per second
+        pub plume_length_rate: f32,
+        /// Height of the leading edge centroid in meters
+        pub leading_edge_centroid_height: f32,
+        pub leading_edge_velocity: Vector3Float,
+    }
+}
+
+environment_record! {
+    /// Gaussian Puff record, SISO-REF-010 environment record type 1879048192
+    pub struct GaussianPuffRecord {
+        pub puff_location: WorldCoordinate,
+        pub origination_location: WorldCoordinate,
+        /// Standard deviations along each puff axis in meters
+        pub sigma: Vector3Float,
+        /// Rate of change of each standard deviation in meters per second
+        pub sigma_rate: Vector3Float,
+        pub orientation: EulerAngles,
+        pub velocity: Vector3Float,
+        pub angular_velocity: Vector3Float,
+        /// Height of the puff centroid in meters
+        pub centroid_height: f32,
+    }
+}
+
+/// Record-specific contents of an environment record, keyed by its environment record type
+#[derive(Clone, Debug, PartialEq)]
+pub enum EnvironmentRecord {
+    CombicState(CombicStateRecord),
+    FlareState(FlareStateRecord),
+    BoundingSphere(BoundingSphereRecord),
+    UniformGeometry(UniformGeometryRecord),
+    Point1(PointRecord1),
+    Line1(LineRecord1),
+    Sphere1(SphereRecord1),
+    Ellipsoid1(EllipsoidRecord1),
+    Cone1(ConeRecord1),
+    RectangularVolume1(RectangularVolumeRecord1),
+    RectangularVolume3(RectangularVolumeRecord3),
+    Point2(PointRecord2),
+    Line2(LineRecord2),
+    Sphere2(SphereRecord2),
+    Ellipsoid2(EllipsoidRecord2),
+    Cone2(ConeRecord2),
+    RectangularVolume2(RectangularVolumeRecord2),
+    GaussianPlume(GaussianPlumeRecord),
+    GaussianPuff(GaussianPuffRecord),
+    /// A record type this crate does not model; the record-specific fields are kept verbatim
+    Unknown {
+        environment_type: u32,
+        record_specific_fields: Vec<u8>,
+    },
+}
+
+impl Default for EnvironmentRecord {
+    fn default() -> Self {
+        Self::Point1(PointRecord1::default())
+    }
+}
+
+impl EnvironmentRecord {
+    /// The raw environment record type, as written on the wire
+    #[must_use]
+    pub fn environment_type(&self) -> u32 {
+        if let Self::Unknown {
+            environment_type, ..
+        } = self
+        {
+            return *environment_type;
+        }
+        self.record_type()
+            .map_or(0, |record_type| record_type as u32)
+    }
+
+    /// The SISO-REF-010 record type, or `None` for records this crate does not model
+    #[must_use]
+    pub const fn record_type(&self) -> Option<EnvironmentalProcessRecordType> {
+        Some(match self {
+            Self::CombicState(_) => EnvironmentalProcessRecordType::COMBICState,
+            Self::FlareState(_) => EnvironmentalProcessRecordType::FlareState,
+            Self::BoundingSphere(_) => EnvironmentalProcessRecordType::BoundingSphereRecord,
+            Self::UniformGeometry(_) => EnvironmentalProcessRecordType::UniformGeometryRecord,
+            Self::Point1(_) => EnvironmentalProcessRecordType::PointRecord1,
+            Self::Line1(_) => EnvironmentalProcessRecordType::LineRecord1,
+            Self::Sphere1(_) => EnvironmentalProcessRecordType::SphereRecord1,
+            Self::Ellipsoid1(_) => EnvironmentalProcessRecordType::EllipsoidRecord1,
+            Self::Cone1(_) => EnvironmentalProcessRecordType::ConeRecord1,
+            Self::RectangularVolume1(_) => EnvironmentalProcessRecordType::RectangularVolumeRecord1,
+            Self::RectangularVolume3(_) => EnvironmentalProcessRecordType::RectangularVolumeRecord3,
+            Self::Point2(_) => EnvironmentalProcessRecordType::PointRecord2,
+            Self::Line2(_) => EnvironmentalProcessRecordType::LineRecord2,
+            Self::Sphere2(_) => EnvironmentalProcessRecordType::SphereRecord2,
+            Self::Ellipsoid2(_) => EnvironmentalProcessRecordType::EllipsoidRecord2,
+            Self::Cone2(_) => EnvironmentalProcessRecordType::ConeRecord2,
+            Self::RectangularVolume2(_) => EnvironmentalProcessRecordType::RectangularVolumeRecord2,
+            Self::GaussianPlume(_) => EnvironmentalProcessRecordType::GaussianPlumeRecord,
+            Self::GaussianPuff(_) => EnvironmentalProcessRecordType::GaussianPuffRecord,
+            Self::Unknown { .. } => return None,
+        })
+    }
+
+    /// Returns `true` for records belonging to the geometry record group
+    #[must_use]
+    pub const fn is_geometry(&self) -> bool {
+        !matches!(
+            self,
+            Self::CombicState(_) | Self::FlareState(_) | Self::Unknown { .. }
+        )
+    }
+
+    /// Length in octets of the record-specific fields, excluding padding
+    #[must_use]
+    pub fn body_length(&self) -> usize {
+        match self {
+            Self::CombicState(r) => r.body_length(),
+            Self::FlareState(r) => r.body_length(),
+            Self::BoundingSphere(r) => r.body_length(),
+            Self::UniformGeometry(r) => r.body_length(),
+            Self::Point1(r) => r.body_length(),
+            Self::Line1(r) => r.body_length(),
+            Self::Sphere1(r) => r.body_length(),
+            Self::Ellipsoid1(r) => r.body_length(),
+            Self::Cone1(r) => r.body_length(),
+            Self::RectangularVolume1(r) => r.body_length(),
+            Self::RectangularVolume3(r) => r.body_length(),
+            Self::Point2(r) => r.body_length(),
+            Self::Line2(r) => r.body_length(),
+            Self::Sphere2(r) => r.body_length(),
+            Self::Ellipsoid2(r) => r.body_length(),
+            Self::Cone2(r) => r.body_length(),
+            Self::RectangularVolume2(r) => r.body_length(),
+            Self::GaussianPlume(r) => r.body_length(),
+            Self::GaussianPuff(r) => r.body_length(),
+            Self::Unknown {
+                record_specific_fields,
+                ..
+            } => record_specific_fields.len(),
+        }
+    }
+
+    pub fn serialize(&self, buf: &mut BytesMut) {
+        match self {
+            Self::CombicState(r) => r.serialize(buf),
+            Self::FlareState(r) => r.serialize(buf),
+            Self::BoundingSphere(r) => r.serialize(buf),
+            Self::UniformGeometry(r) => r.serialize(buf),
+            Self::Point1(r) => r.serialize(buf),
+            Self::Line1(r) => r.serialize(buf),
+            Self::Sphere1(r) => r.serialize(buf),
+            Self::Ellipsoid1(r) => r.serialize(buf),
+            Self::Cone1(r) => r.serialize(buf),
+            Self::RectangularVolume1(r) => r.serialize(buf),
+            Self::RectangularVolume3(r) => r.serialize(buf),
+            Self::Point2(r) => r.serialize(buf),
+            Self::Line2(r) => r.serialize(buf),
+            Self::Sphere2(r) => r.serialize(buf),
+            Self::Ellipsoid2(r) => r.serialize(buf),
+            Self::Cone2(r) => r.serialize(buf),
+            Self::RectangularVolume2(r) => r.serialize(buf),
+            Self::GaussianPlume(r) => r.serialize(buf),
+            Self::GaussianPuff(r) => r.serialize(buf),
+            Self::Unknown {
+                record_specific_fields,
+                ..
+            } => buf.put_slice(record_specific_fields),
+        }
+    }
+
+    /// Deserializes the record-specific fields for the given environment type. `length` is the
+    /// number of octets available for the record-specific fields and padding; unknown records,
+    /// and known records too short for their fields, consume all of it.
+    pub fn deserialize<B: Buf>(buf: &mut B, environment_type: u32, length: usize) -> Self {
+        let available = length.min(buf.remaining());
+        let record = match EnvironmentalProcessRecordType::from_u32(environment_type) {
+            Some(EnvironmentalProcessRecordType::COMBICState) => {
+                CombicStateRecord::deserialize_within(buf, available).map(Self::CombicState)
+            }
+            Some(EnvironmentalProcessRecordType::FlareState) => {
+                FlareStateRecord::deserialize_within(buf, available).map(Self::FlareState)
+            }
+            Some(EnvironmentalProcessRecordType::BoundingSphereRecord) => {
+                BoundingSphereRecord::deserialize_within(buf, available).map(Self::BoundingSphere)
+            }
+            Some(EnvironmentalProcessRecordType::UniformGeometryRecord) => {
+                Some(Self::UniformGeometry(UniformGeometryRecord))
+            }
+            Some(EnvironmentalProcessRecordType::PointRecord1) => {
+                PointRecord1::deserialize_within(buf, available).map(Self::Point1)
+            }
+            Some(EnvironmentalProcessRecordType::LineRecord1) => {
+                LineRecord1::deserialize_within(buf, available).map(Self::Line1)
+            }
+            Some(EnvironmentalProcessRecordType::SphereRecord1) => {
+                SphereRecord1::deserialize_within(buf, available).map(Self::Sphere1)
+            }
+            Some(EnvironmentalProcessRecordType::EllipsoidRecord1) => {
+                EllipsoidRecord1::deserialize_within(buf, available).map(Self::Ellipsoid1)
+            }
+            Some(EnvironmentalProcessRecordType::ConeRecord1) => {
+                ConeRecord1::deserialize_within(buf, available).map(Self::Cone1)
+            }
+            Some(EnvironmentalProcessRecordType::RectangularVolumeRecord1) => {
+                RectangularVolumeRecord1::deserialize_within(buf, available)
+                    .map(Self::RectangularVolume1)
+            }
+            Some(EnvironmentalProcessRecordType::RectangularVolumeRecord3) => {
+                RectangularVolumeRecord3::deserialize_within(buf, available)
+                    .map(Self::RectangularVolume3)
+            }
+            Some(EnvironmentalProcessRecordType::PointRecord2) => {
+                PointRecord2::deserialize_within(buf, available).map(Self::Point2)
+            }
+            Some(EnvironmentalProcessRecordType::LineRecord2) => {
+                LineRecord2::deserialize_within(buf, available).map(Self::Line2)
+            }
+            Some(EnvironmentalProcessRecordType::SphereRecord2) => {
+                SphereRecord2::deserialize_within(buf, available).map(Self::Sphere2)
+            }
+            Some(EnvironmentalProcessRecordType::EllipsoidRecord2) => {
+                EllipsoidRecord2::deserialize_within(buf, available).map(Self::Ellipsoid2)
+            }
+            Some(EnvironmentalProcessRecordType::ConeRecord2) => {
+                ConeRecord2::deserialize_within(buf, available).map(Self::Cone2)
+            }
+            Some(EnvironmentalProcessRecordType::RectangularVolumeRecord2) => {
+                RectangularVolumeRecord2::deserialize_within(buf, available)
+                    .map(Self::RectangularVolume2)
+            }
+            Some(EnvironmentalProcessRecordType::GaussianPlumeRecord) => {
+                GaussianPlumeRecord::deserialize_within(buf, available).map(Self::GaussianPlume)
+            }
+            Some(EnvironmentalProcessRecordType::GaussianPuffRecord) => {
+                GaussianPuffRecord::deserialize_within(buf, available).map(Self::GaussianPuff)
+            }
+            None => None,
+        };
+        record.unwrap_or_else(|| {
+            let mut record_specific_fields = vec![0u8; available];
+            buf.copy_to_slice(&mut record_specific_fields);
+            Self::Unknown {
+                environment_type,
+                record_specific_fields,
+            }
+        })
+    }
+}
+
+/// Implemented according to IEEE 1278.1-2012 §6.2.31
+///
+/// The record type and length are derived from the typed record, and the record-specific
+/// fields are padded to a 64-bit boundary on serialization.
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct Environment {
-    pub environment_type: u32,
-    pub length: u16,
+    /// Sequential index used to associate state records with geometry records
     pub index: u8,
-    pub padding: u8,
+    pub record: EnvironmentRecord,
 }
 
 impl Environment {
     #[must_use]
-    pub const fn new(environment_type: u32, length: u16, index: u8, padding: u8) -> Self {
-        Self {
-            environment_type,
-            length,
-            index,
-            padding,
-        }
+    pub const fn new(index: u8, record: EnvironmentRecord) -> Self {
+        Self { index, record }
+    }
+
+    /// The raw environment record type, as written on the wire
+    #[must_use]
+    pub fn environment_type(&self) -> u32 {
+        self.record.environment_type()
+    }
+
+    /// Number of padding octets needed to align the record to a 64-bit boundary
+    fn padding_length(&self) -> usize {
+        padding_to_64_bits(ENVIRONMENT_HEADER_LENGTH + self.record.body_length())
+    }
+
+    /// The length of the whole record in bits, including padding
+    #[must_use]
+    pub fn length(&self) -> u16 {
+        u16::try_from(self.field_len() * 8).unwrap_or(u16::MAX)
     }
 
     pub fn serialize(&self, buf: &mut BytesMut) {
-        buf.put_u32(self.environment_type);
-        buf.put_u16(self.length);
+        buf.put_u32(self.environment_type());
+        buf.put_u16(self.length());
         buf.put_u8(self.index);
-        buf.put_u8(self.padding);
+        buf.put_u8(0u8);
+        self.record.serialize(buf);
+        buf.put_bytes(0u8, self.padding_length());
     }
 
     pub fn deserialize<B: Buf>(buf: &mut B) -> Self {
-        Self {
-            environment_type: buf.get_u32(),
-            length: buf.get_u16(),
-            index: buf.get_u8(),
-            padding: buf.get_u8(),
-        }
+        let environment_type = buf.get_u32();
+        let length = usize::from(buf.get_u16()) / 8;
+        let index = buf.get_u8();
+        let _padding = buf.get_u8();
+
+        let available = length.saturating_sub(ENVIRONMENT_HEADER_LENGTH);
+        let remaining_before = buf.remaining();
+        let record = EnvironmentRecord::deserialize(buf, environment_type, available);
+
+        // Skip any padding or trailing fields this crate does not model
+        let consumed = remaining_before - buf.remaining();
+        buf.advance(available.saturating_sub(consumed).min(buf.remaining()));
+
+        Self { index, record }
     }
 }
 
@@ -57,9 +606,10 @@ impl FieldDeserialize for Environment {
 
 impl FieldLen for Environment {
     fn field_len(&self) -> usize {
-        self.environment_type.field_len()
-            + self.length.field_len()
-            + self.index.field_len()
-            + self.padding.field_len()
+        ENVIRONMENT_HEADER_LENGTH + self.record.body_length() + self.padding_length()
     }
 }
+
+impl FieldMinLen for Environment {
+    const MIN_LEN: usize = ENVIRONMENT_HEADER_LENGTH;
+}
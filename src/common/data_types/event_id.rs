@@ -12,7 +12,7 @@ use super::simulation_address::SimulationAddress;
 
 use bytes::{Buf, BufMut, BytesMut};
 
-#[derive(Copy, Clone, Debug, Default)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
 pub struct EventId {
     pub simulation_address: SimulationAddress,
     pub event_identifier: u16,
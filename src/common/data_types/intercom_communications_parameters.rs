@@ -3,14 +3,188 @@
 //
 //     Licensed under the BSD-2-Clause License
 
-use crate::pdu_macro::{FieldDeserialize, FieldLen, FieldSerialize};
 use bytes::{Buf, BufMut, BytesMut};
+use num_traits::FromPrimitive;
 
-#[derive(Clone, Debug, Default)]
-pub struct IntercomCommunicationsParameters {
-    pub record_type: u16,
-    pub record_length: u16,
-    pub record_specific_field: u32,
+use crate::{
+    common::{
+        SerializedLength,
+        data_types::entity_id::EntityId,
+        enums::{IntercomControlDestinationLineStateCommand, IntercomControlRecordType},
+    },
+    pdu_macro::{FieldDeserialize, FieldLen, FieldMinLen, FieldSerialize},
+};
+
+/// Length in octets of the record type and record length fields
+const ICP_RECORD_HEADER_LENGTH: usize = 4;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+/// Names a single intercom line to connect to, disconnect from or command
+pub struct SpecificDestination {
+    pub destination_intercom_reference_id: EntityId,
+    pub destination_intercom_number: u16,
+    pub destination_line_id: u8,
+    pub destination_priority: u8,
+    pub destination_line_state_command: IntercomControlDestinationLineStateCommand,
+    padding: u8,
+}
+
+impl SpecificDestination {
+    #[must_use]
+    pub const fn new(
+        destination_intercom_reference_id: EntityId,
+        destination_intercom_number: u16,
+        destination_line_id: u8,
+        destination_priority: u8,
+        destination_line_state_command: IntercomControlDestinationLineStateCommand,
+    ) -> Self {
+        Self {
+            destination_intercom_reference_id,
+            destination_intercom_number,
+            destination_line_id,
+            destination_priority,
+            destination_line_state_command,
+            padding: 0,
+        }
+    }
+
+    pub fn serialize(&self, buf: &mut BytesMut) {
+        self.destination_intercom_reference_id.serialize(buf);
+        buf.put_u16(self.destination_intercom_number);
+        buf.put_u8(self.destination_line_id);
+        buf.put_u8(self.destination_priority);
+        buf.put_u8(self.destination_line_state_command as u8);
+        buf.put_u8(self.padding);
+    }
+
+    pub fn deserialize<B: Buf>(buf: &mut B) -> Self {
+        Self {
+            destination_intercom_reference_id: EntityId::deserialize(buf),
+            destination_intercom_number: buf.get_u16(),
+            destination_line_id: buf.get_u8(),
+            destination_priority: buf.get_u8(),
+            destination_line_state_command: IntercomControlDestinationLineStateCommand::deserialize(
+                buf,
+            ),
+            padding: buf.get_u8(),
+        }
+    }
+}
+
+impl SerializedLength for SpecificDestination {
+    /// Length of the record-specific field, excluding the record header
+    const LENGTH: usize = 12;
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+/// Addresses every intercom assigned to one of the groups set in `group_bit_field`
+pub struct GroupDestination {
+    pub group_bit_field: u32,
+    pub destination_priority: u8,
+    pub destination_line_state_command: IntercomControlDestinationLineStateCommand,
+    padding: u16,
+}
+
+impl GroupDestination {
+    #[must_use]
+    pub const fn new(
+        group_bit_field: u32,
+        destination_priority: u8,
+        destination_line_state_command: IntercomControlDestinationLineStateCommand,
+    ) -> Self {
+        Self {
+            group_bit_field,
+            destination_priority,
+            destination_line_state_command,
+            padding: 0,
+        }
+    }
+
+    pub fn serialize(&self, buf: &mut BytesMut) {
+        buf.put_u32(self.group_bit_field);
+        buf.put_u8(self.destination_priority);
+        buf.put_u8(self.destination_line_state_command as u8);
+        buf.put_u16(self.padding);
+    }
+
+    pub fn deserialize<B: Buf>(buf: &mut B) -> Self {
+        Self {
+            group_bit_field: buf.get_u32(),
+            destination_priority: buf.get_u8(),
+            destination_line_state_command: IntercomControlDestinationLineStateCommand::deserialize(
+                buf,
+            ),
+            padding: buf.get_u16(),
+        }
+    }
+}
+
+impl SerializedLength for GroupDestination {
+    /// Length of the record-specific field, excluding the record header
+    const LENGTH: usize = 8;
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+/// Assigns an intercom to the groups set in `group_bit_field`
+pub struct GroupAssignment {
+    pub group_bit_field: u32,
+    pub intercom_reference_id: EntityId,
+    pub intercom_number: u16,
+}
+
+impl GroupAssignment {
+    #[must_use]
+    pub const fn new(
+        group_bit_field: u32,
+        intercom_reference_id: EntityId,
+        intercom_number: u16,
+    ) -> Self {
+        Self {
+            group_bit_field,
+            intercom_reference_id,
+            intercom_number,
+        }
+    }
+
+    pub fn serialize(&self, buf: &mut BytesMut) {
+        buf.put_u32(self.group_bit_field);
+        self.intercom_reference_id.serialize(buf);
+        buf.put_u16(self.intercom_number);
+    }
+
+    pub fn deserialize<B: Buf>(buf: &mut B) -> Self {
+        Self {
+            group_bit_field: buf.get_u32(),
+            intercom_reference_id: EntityId::deserialize(buf),
+            intercom_number: buf.get_u16(),
+        }
+    }
+}
+
+impl SerializedLength for GroupAssignment {
+    /// Length of the record-specific field, excluding the record header
+    const LENGTH: usize = 12;
+}
+
+/// An Intercom Communications Parameters record, keyed by its record type
+///
+/// The record length (in octets) covers the record-specific field only.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IntercomCommunicationsParameters {
+    SpecificDestination(SpecificDestination),
+    GroupDestination(GroupDestination),
+    GroupAssignment(GroupAssignment),
+    /// A record type this crate does not model; the record-specific field is kept verbatim
+    Unknown {
+        record_type: u16,
+        record_specific_field: Vec<u8>,
+    },
+}
+
+impl Default for IntercomCommunicationsParameters {
+    fn default() -> Self {
+        Self::SpecificDestination(SpecificDestination::default())
+    }
 }
 
 impl FieldSerialize for IntercomCommunicationsParameters {
@@ -27,31 +201,106 @@ impl FieldDeserialize for IntercomCommunicationsParameters {
 
 impl FieldLen for IntercomCommunicationsParameters {
     fn field_len(&self) -> usize {
-        2 + 2 + 4
+        ICP_RECORD_HEADER_LENGTH + self.body_length()
     }
 }
 
+impl FieldMinLen for IntercomCommunicationsParameters {
+    const MIN_LEN: usize = ICP_RECORD_HEADER_LENGTH;
+}
+
 impl IntercomCommunicationsParameters {
     #[must_use]
-    pub const fn new(record_type: u16, record_length: u16, record_specific_field: u32) -> Self {
-        Self {
-            record_type,
-            record_length,
-            record_specific_field,
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The raw record type, as written on the wire
+    #[must_use]
+    pub const fn record_type(&self) -> u16 {
+        match self {
+            Self::SpecificDestination(_) => {
+                IntercomControlRecordType::SpecificDestinationrecord as u16
+            }
+            Self::GroupDestination(_) => IntercomControlRecordType::GroupDestinationrecord as u16,
+            Self::GroupAssignment(_) => IntercomControlRecordType::GroupAssignmentrecord as u16,
+            Self::Unknown { record_type, .. } => *record_type,
+        }
+    }
+
+    /// The record type, if it is a known Intercom Control record type
+    #[must_use]
+    pub fn known_record_type(&self) -> Option<IntercomControlRecordType> {
+        IntercomControlRecordType::from_u16(self.record_type())
+    }
+
+    const fn body_length(&self) -> usize {
+        match self {
+            Self::SpecificDestination(_) => SpecificDestination::LENGTH,
+            Self::GroupDestination(_) => GroupDestination::LENGTH,
+            Self::GroupAssignment(_) => GroupAssignment::LENGTH,
+            Self::Unknown {
+                record_specific_field,
+                ..
+            } => record_specific_field.len(),
         }
     }
 
+    /// The length of the record-specific field in octets
+    #[must_use]
+    pub fn record_length(&self) -> u16 {
+        u16::try_from(self.body_length()).unwrap_or(u16::MAX)
+    }
+
     pub fn serialize(&self, buf: &mut BytesMut) {
-        buf.put_u16(self.record_type);
-        buf.put_u16(self.record_length);
-        buf.put_u32(self.record_specific_field);
+        buf.put_u16(self.record_type());
+        buf.put_u16(self.record_length());
+        match self {
+            Self::SpecificDestination(record) => record.serialize(buf),
+            Self::GroupDestination(record) => record.serialize(buf),
+            Self::GroupAssignment(record) => record.serialize(buf),
+            Self::Unknown {
+                record_specific_field,
+                ..
+            } => buf.put_slice(record_specific_field),
+        }
     }
 
     pub fn deserialize<B: Buf>(buf: &mut B) -> Self {
-        Self {
-            record_type: buf.get_u16(),
-            record_length: buf.get_u16(),
-            record_specific_field: buf.get_u32(),
-        }
+        let record_type = buf.get_u16();
+        let available = usize::from(buf.get_u16()).min(buf.remaining());
+
+        let remaining_before = buf.remaining();
+        let record = match IntercomControlRecordType::from_u16(record_type) {
+            Some(IntercomControlRecordType::SpecificDestinationrecord)
+                if available >= SpecificDestination::LENGTH =>
+            {
+                Self::SpecificDestination(SpecificDestination::deserialize(buf))
+            }
+            Some(IntercomControlRecordType::GroupDestinationrecord)
+                if available >= GroupDestination::LENGTH =>
+            {
+                Self::GroupDestination(GroupDestination::deserialize(buf))
+            }
+            Some(IntercomControlRecordType::GroupAssignmentrecord)
+                if available >= GroupAssignment::LENGTH =>
+            {
+                Self::GroupAssignment(GroupAssignment::deserialize(buf))
+            }
+            _ => {
+                let mut record_specific_field = vec![0u8; available];
+                buf.copy_to_slice(&mut record_specific_field);
+                Self::Unknown {
+                    record_type,
+                    record_specific_field,
+                }
+            }
+        };
+
+        // Skip anything a longer record carries beyond the fields this crate knows
+        let consumed = remaining_before - buf.remaining();
+        buf.advance(available.saturating_sub(consumed).min(buf.remaining()));
+
+        record
     }
 }
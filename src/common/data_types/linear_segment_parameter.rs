@@ -6,16 +6,22 @@
 use bytes::{Buf, BufMut, BytesMut};
 
 use crate::{
-    common::data_types::{WorldCoordinate, euler_angles::EulerAngles},
+    common::{
+        data_types::{
+            WorldCoordinate, euler_angles::EulerAngles,
+            object_appearance::SpecificObjectAppearance, object_type::ObjectType,
+        },
+        enums::ObjectStateModificationLinearObject,
+    },
     pdu_macro::{FieldDeserialize, FieldLen, FieldSerialize},
 };
 
 #[derive(Clone, Debug, Default)]
 pub struct LinearSegmentParameter {
     pub segment_number: u8,
-    pub segment_modification: u8,
+    pub segment_modification: ObjectStateModificationLinearObject,
     pub general_segment_appearance: u32,
-    pub specific_segment_appearance: u32, // TODO(@anyone) Implement Specific Object Appearance
+    pub specific_segment_appearance: u32,
     pub segment_location: WorldCoordinate,
     pub segment_orientation: EulerAngles,
     pub segment_length: f32,
@@ -30,7 +36,7 @@ impl LinearSegmentParameter {
     #[allow(clippy::too_many_arguments)]
     pub const fn new(
         segment_number: u8,
-        segment_modification: u8,
+        segment_modification: ObjectStateModificationLinearObject,
         general_segment_appearance: u32,
         specific_segment_appearance: u32,
         segment_location: WorldCoordinate,
@@ -55,9 +61,19 @@ impl LinearSegmentParameter {
         }
     }
 
+    /// Interprets the specific segment appearance according to the type of the linear object
+    #[must_use]
+    pub fn specific_appearance(&self, object_type: &ObjectType) -> SpecificObjectAppearance {
+        SpecificObjectAppearance::for_linear_object(object_type, self.specific_segment_appearance)
+    }
+
+    pub const fn set_specific_appearance(&mut self, appearance: SpecificObjectAppearance) {
+        self.specific_segment_appearance = appearance.to_u32();
+    }
+
     pub fn serialize(&self, buf: &mut BytesMut) {
         buf.put_u8(self.segment_number);
-        buf.put_u8(self.segment_modification);
+        buf.put_u8(self.segment_modification.as_u8());
         buf.put_u32(self.general_segment_appearance);
         buf.put_u32(self.specific_segment_appearance);
         self.segment_location.serialize(buf);
@@ -72,7 +88,9 @@ impl LinearSegmentParameter {
     pub fn deserialize<B: Buf>(buf: &mut B) -> Self {
         Self {
             segment_number: buf.get_u8(),
-            segment_modification: buf.get_u8(),
+            segment_modification: ObjectStateModificationLinearObject::from_bits_truncate(
+                buf.get_u8(),
+            ),
             general_segment_appearance: buf.get_u32(),
             specific_segment_appearance: buf.get_u32(),
             segment_location: WorldCoordinate::deserialize(buf),
@@ -14,14 +14,17 @@ pub mod antenna_pattern;
 pub mod apa_data;
 pub mod attribute_record;
 pub mod attribute_record_set;
+pub mod beam_antenna_pattern;
 pub mod beam_data;
 pub mod clock_time;
 pub mod datum_records;
 pub mod dead_reckoning_parameters;
 pub mod directed_energy_damage;
+pub mod directed_energy_fire_record;
 pub mod electromagnetic_emission_beam_data;
 pub mod electromagnetic_emission_system_data;
 pub mod emitter_system;
+pub mod encoding_scheme;
 pub mod entity_coordinate_vector;
 pub mod entity_id;
 pub mod entity_marking;
@@ -44,6 +47,7 @@ pub mod modulation_parameters;
 pub mod modulation_type;
 pub mod munition_descriptor;
 pub mod named_location;
+pub mod object_appearance;
 pub mod object_identifier;
 pub mod object_type;
 pub mod point;
@@ -4,10 +4,28 @@
 //     Licensed under the BSD-2-Clause License
 
 use bytes::{Buf, BufMut, BytesMut};
+use num_traits::FromPrimitive;
 
-use crate::pdu_macro::{FieldDeserialize, FieldDeserializeWithLen, FieldLen, FieldSerialize};
+use crate::{
+    common::{
+        SerializedLength,
+        enums::{
+            CCTTSINCGARSClearChannel, CCTTSINCGARSStartofMessage,
+            JTIDSMIDSModulationParametersSynchronizationState,
+            JTIDSMIDSModulationParametersTransmittingTerminalPrimaryMode,
+            JTIDSMIDSModulationParametersTransmittingTerminalSecondaryMode,
+            NETIDRecordFrequencyTable, NETIDRecordMode, TimeSlotAllocationLevel,
+            TransmitterModulationTypeSystem,
+        },
+    },
+    pdu_macro::{FieldDeserialize, FieldDeserializeWithLen, FieldLen, FieldSerialize},
+};
 
-#[derive(Clone, Debug, Default)]
+/// The modulation parameters carried in the Transmitter PDU, kept as the raw octets on the wire
+///
+/// Their meaning depends on the radio system of the accompanying `ModulationType`; use
+/// [`ModulationParameters::decode`] to interpret them.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct ModulationParameters {
     record_specific_fields: Vec<u8>,
 }
@@ -20,6 +38,24 @@ impl ModulationParameters {
         }
     }
 
+    #[must_use]
+    pub const fn from_bytes(record_specific_fields: Vec<u8>) -> Self {
+        Self {
+            record_specific_fields,
+        }
+    }
+
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.record_specific_fields
+    }
+
+    /// Interprets the parameters according to the radio system they were sent for
+    #[must_use]
+    pub fn decode(&self, system: TransmitterModulationTypeSystem) -> ModulationParameterRecord {
+        ModulationParameterRecord::decode(system, &self.record_specific_fields)
+    }
+
     pub fn serialize(&self, buf: &mut BytesMut) {
         for i in 0..self.record_specific_fields.len() {
             buf.put_u8(self.record_specific_fields[i]);
@@ -42,6 +78,14 @@ impl ModulationParameters {
     }
 }
 
+impl From<&ModulationParameterRecord> for ModulationParameters {
+    fn from(record: &ModulationParameterRecord) -> Self {
+        let mut buf = BytesMut::new();
+        record.serialize(&mut buf);
+        Self::from_bytes(buf.to_vec())
+    }
+}
+
 impl FieldSerialize for ModulationParameters {
     fn serialize_field(&self, buf: &mut BytesMut) {
         self.serialize(buf);
@@ -57,8 +101,8 @@ impl FieldDeserialize for ModulationParameters {
 
 impl FieldDeserializeWithLen for ModulationParameters {
     fn deserialize_with_len<B: Buf>(buf: &mut B, len: usize) -> Self {
-        // Underlying API expects u8; clamp/cast from usize safely.
-        let len_u8 = u8::try_from(len).expect("Should not be larger than u8");
+        // The length field on the wire is a single octet
+        let len_u8 = u8::try_from(len).unwrap_or(u8::MAX);
         Self::deserialize(buf, len_u8).unwrap_or_default()
     }
 }
@@ -68,3 +112,222 @@ impl FieldLen for ModulationParameters {
         self.record_specific_fields.field_len()
     }
 }
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+/// Modulation parameters of a CCTT SINCGARS radio
+pub struct CcttSincgarsModulationParameters {
+    /// Frequency hopping network identifier
+    pub fh_net_id: u16,
+    pub hop_set_id: u16,
+    pub lockout_set_id: u16,
+    pub start_of_message: CCTTSINCGARSStartofMessage,
+    padding: u8,
+    /// Offset of the frequency hopping synchronization time
+    pub fh_sync_time_offset: u32,
+    pub transmission_security_key: u16,
+    pub clear_channel: CCTTSINCGARSClearChannel,
+    padding2: u8,
+}
+
+impl CcttSincgarsModulationParameters {
+    #[must_use]
+    pub const fn new(
+        fh_net_id: u16,
+        hop_set_id: u16,
+        lockout_set_id: u16,
+        start_of_message: CCTTSINCGARSStartofMessage,
+        fh_sync_time_offset: u32,
+    ) -> Self {
+        Self {
+            fh_net_id,
+            hop_set_id,
+            lockout_set_id,
+            start_of_message,
+            padding: 0,
+            fh_sync_time_offset,
+            transmission_security_key: 0,
+            clear_channel: CCTTSINCGARSClearChannel::Notclearchannel,
+            padding2: 0,
+        }
+    }
+
+    pub fn serialize(&self, buf: &mut BytesMut) {
+        buf.put_u16(self.fh_net_id);
+        buf.put_u16(self.hop_set_id);
+        buf.put_u16(self.lockout_set_id);
+        buf.put_u8(self.start_of_message as u8);
+        buf.put_u8(self.padding);
+        buf.put_u32(self.fh_sync_time_offset);
+        buf.put_u16(self.transmission_security_key);
+        buf.put_u8(self.clear_channel as u8);
+        buf.put_u8(self.padding2);
+    }
+
+    pub fn deserialize<B: Buf>(buf: &mut B) -> Self {
+        Self {
+            fh_net_id: buf.get_u16(),
+            hop_set_id: buf.get_u16(),
+            lockout_set_id: buf.get_u16(),
+            start_of_message: CCTTSINCGARSStartofMessage::deserialize(buf),
+            padding: buf.get_u8(),
+            fh_sync_time_offset: buf.get_u32(),
+            transmission_security_key: buf.get_u16(),
+            clear_channel: CCTTSINCGARSClearChannel::deserialize(buf),
+            padding2: buf.get_u8(),
+        }
+    }
+}
+
+impl SerializedLength for CcttSincgarsModulationParameters {
+    const LENGTH: usize = 16;
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+/// Modulation parameters of a JTIDS/MIDS terminal
+pub struct JtidsMidsModulationParameters {
+    pub time_slot_allocation_mode: TimeSlotAllocationLevel,
+    pub transmitting_terminal_primary_mode:
+        JTIDSMIDSModulationParametersTransmittingTerminalPrimaryMode,
+    pub transmitting_terminal_secondary_mode:
+        JTIDSMIDSModulationParametersTransmittingTerminalSecondaryMode,
+    pub synchronization_state: JTIDSMIDSModulationParametersSynchronizationState,
+    pub network_sync_id: u32,
+}
+
+impl JtidsMidsModulationParameters {
+    pub fn serialize(&self, buf: &mut BytesMut) {
+        buf.put_u8(self.time_slot_allocation_mode as u8);
+        buf.put_u8(self.transmitting_terminal_primary_mode as u8);
+        buf.put_u8(self.transmitting_terminal_secondary_mode as u8);
+        buf.put_u8(self.synchronization_state as u8);
+        buf.put_u32(self.network_sync_id);
+    }
+
+    pub fn deserialize<B: Buf>(buf: &mut B) -> Self {
+        Self {
+            time_slot_allocation_mode: TimeSlotAllocationLevel::deserialize(buf),
+            transmitting_terminal_primary_mode:
+                JTIDSMIDSModulationParametersTransmittingTerminalPrimaryMode::deserialize(buf),
+            transmitting_terminal_secondary_mode:
+                JTIDSMIDSModulationParametersTransmittingTerminalSecondaryMode::deserialize(buf),
+            synchronization_state: JTIDSMIDSModulationParametersSynchronizationState::deserialize(
+                buf,
+            ),
+            network_sync_id: buf.get_u32(),
+        }
+    }
+}
+
+impl SerializedLength for JtidsMidsModulationParameters {
+    const LENGTH: usize = 8;
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+/// Implemented according to IEEE 1278.1-2012 §6.2.58
+///
+/// The net number occupies bits 0-9, the frequency table bits 10-11 and the mode bits 12-13.
+pub struct NetId {
+    /// Net number, limited to 10 bits
+    pub net_number: u16,
+    pub frequency_table: NETIDRecordFrequencyTable,
+    pub mode: NETIDRecordMode,
+}
+
+impl NetId {
+    #[must_use]
+    pub const fn to_u16(&self) -> u16 {
+        (self.net_number & 0x03FF)
+            | (self.frequency_table as u16 & 0x03) << 10
+            | (self.mode as u16 & 0x03) << 12
+    }
+
+    #[must_use]
+    pub fn from_u16(value: u16) -> Self {
+        Self {
+            net_number: value & 0x03FF,
+            frequency_table: NETIDRecordFrequencyTable::from_u16((value >> 10) & 0x03)
+                .unwrap_or_default(),
+            mode: NETIDRecordMode::from_u16((value >> 12) & 0x03).unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+/// Modulation parameters of a HAVE QUICK I/II radio: the Net ID record padded to 64 bits
+pub struct HaveQuickModulationParameters {
+    pub net_id: NetId,
+}
+
+impl HaveQuickModulationParameters {
+    pub fn serialize(&self, buf: &mut BytesMut) {
+        buf.put_u16(self.net_id.to_u16());
+        buf.put_bytes(0u8, Self::LENGTH - 2);
+    }
+
+    pub fn deserialize<B: Buf>(buf: &mut B) -> Self {
+        let net_id = NetId::from_u16(buf.get_u16());
+        buf.advance((Self::LENGTH - 2).min(buf.remaining()));
+        Self { net_id }
+    }
+}
+
+impl SerializedLength for HaveQuickModulationParameters {
+    const LENGTH: usize = 8;
+}
+
+/// The modulation parameters of a Transmitter PDU, interpreted by radio system
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ModulationParameterRecord {
+    CcttSincgars(CcttSincgarsModulationParameters),
+    JtidsMids(JtidsMidsModulationParameters),
+    HaveQuick(HaveQuickModulationParameters),
+    /// Parameters of a system this crate does not model, or that were too short to decode
+    Other(Vec<u8>),
+}
+
+impl ModulationParameterRecord {
+    /// Interprets `bytes` as the modulation parameters of `system`
+    #[must_use]
+    pub fn decode(system: TransmitterModulationTypeSystem, bytes: &[u8]) -> Self {
+        let mut buf = bytes;
+        match system {
+            TransmitterModulationTypeSystem::CCTTSINCGARS
+                if bytes.len() >= CcttSincgarsModulationParameters::LENGTH =>
+            {
+                Self::CcttSincgars(CcttSincgarsModulationParameters::deserialize(&mut buf))
+            }
+            TransmitterModulationTypeSystem::JTIDSMIDS
+                if bytes.len() >= JtidsMidsModulationParameters::LENGTH =>
+            {
+                Self::JtidsMids(JtidsMidsModulationParameters::deserialize(&mut buf))
+            }
+            TransmitterModulationTypeSystem::HAVEQUICKI
+            | TransmitterModulationTypeSystem::HAVEQUICKII
+                if bytes.len() >= 2 =>
+            {
+                Self::HaveQuick(HaveQuickModulationParameters::deserialize(&mut buf))
+            }
+            _ => Self::Other(bytes.to_vec()),
+        }
+    }
+
+    /// The radio system these parameters belong to, or `None` for raw parameters
+    #[must_use]
+    pub const fn system(&self) -> Option<TransmitterModulationTypeSystem> {
+        match self {
+            Self::CcttSincgars(_) => Some(TransmitterModulationTypeSystem::CCTTSINCGARS),
+            Self::JtidsMids(_) => Some(TransmitterModulationTypeSystem::JTIDSMIDS),
+            Self::HaveQuick(_) => Some(TransmitterModulationTypeSystem::HAVEQUICKII),
+            Self::Other(_) => None,
+        }
+    }
+
+    pub fn serialize(&self, buf: &mut BytesMut) {
+        match self {
+            Self::CcttSincgars(record) => record.serialize(buf),
+            Self::JtidsMids(record) => record.serialize(buf),
+            Self::HaveQuick(record) => record.serialize(buf),
+            Self::Other(bytes) => buf.put_slice(bytes),
+        }
+    }
+}
@@ -5,9 +5,14 @@
 
 use bytes::{Buf, BufMut, BytesMut};
 
-use crate::pdu_macro::{FieldDeserialize, FieldLen, FieldSerialize};
+use num_traits::FromPrimitive;
 
-#[derive(Clone, Debug, Default)]
+use crate::{
+    common::enums::TransmitterModulationTypeSystem,
+    pdu_macro::{FieldDeserialize, FieldLen, FieldSerialize},
+};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct ModulationType {
     pub spread_spectrum: u16,
     pub major: u16,
@@ -26,6 +31,12 @@ impl ModulationType {
         }
     }
 
+    /// The radio system, falling back to `Other` for values this crate does not know
+    #[must_use]
+    pub fn radio_system(&self) -> TransmitterModulationTypeSystem {
+        TransmitterModulationTypeSystem::from_u16(self.system).unwrap_or_default()
+    }
+
     pub fn serialize(&self, buf: &mut BytesMut) {
         buf.put_u16(self.spread_spectrum);
         buf.put_u16(self.major);
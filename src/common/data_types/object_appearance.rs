@@ -0,0 +1,291 @@
+//     open-dis-rust - Rust implementation of the IEEE-1278.1 Distributed Interactive Simulation
+//     Copyright (C) 2025 Cameron Howell
+//
+//     Licensed under the BSD-2-Clause License
+
+//! Object-type-specific appearance records carried in the 32-bit specific appearance field of
+//! the point, linear and areal object state PDUs
+
+use num_traits::FromPrimitive;
+
+use crate::common::{
+    data_types::object_type::ObjectType,
+    enums::{
+        AppearanceLinearObjectLaneMarkerVisible, AppearanceLinearObjectTankDitchBreach,
+        AppearanceObjectSpecificBreachState, AppearanceObjectSpecificChemicalType, ObjectKind,
+    },
+};
+
+/// Obstacle category of a crater point object
+const POINT_OBSTACLE_CRATER: u8 = 3;
+/// Passageway category of a ribbon bridge point object
+const POINT_PASSAGEWAY_RIBBON_BRIDGE: u8 = 1;
+/// Obstacle category of a tank ditch linear object
+const LINEAR_OBSTACLE_TANK_DITCH: u8 = 1;
+
+/// Extracts `width` bits starting at bit `offset`
+const fn bits(value: u32, offset: u32, width: u32) -> u32 {
+    (value >> offset) & ((1 << width) - 1)
+}
+
+/// Extracts an 8-bit field starting at bit `offset`
+#[allow(clippy::cast_possible_truncation)]
+const fn byte(value: u32, offset: u32) -> u8 {
+    bits(value, offset, 8) as u8
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+/// Specific appearance of a crater point object
+pub struct CraterAppearance {
+    /// Diameter of the crater in meters
+    pub size: u8,
+}
+
+impl CraterAppearance {
+    #[must_use]
+    pub const fn from_u32(value: u32) -> Self {
+        Self {
+            size: byte(value, 0),
+        }
+    }
+
+    #[must_use]
+    pub const fn to_u32(&self) -> u32 {
+        self.size as u32
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+/// Specific appearance of a ribbon bridge point object
+pub struct RibbonBridgeAppearance {
+    pub number_of_segments: u8,
+}
+
+impl RibbonBridgeAppearance {
+    #[must_use]
+    pub const fn from_u32(value: u32) -> Self {
+        Self {
+            number_of_segments: byte(value, 0),
+        }
+    }
+
+    #[must_use]
+    pub const fn to_u32(&self) -> u32 {
+        self.number_of_segments as u32
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+/// Specific appearance of an air burst or ground burst point object
+pub struct BurstAppearance {
+    /// Opacity of the burst in percent
+    pub opacity: u8,
+    /// Size of the burst in meters
+    pub size: u8,
+    /// Height of the burst in meters
+    pub height: u8,
+    /// Number of bursts, limited to 6 bits
+    pub number_of_bursts: u8,
+    pub chemical_type: AppearanceObjectSpecificChemicalType,
+}
+
+impl BurstAppearance {
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn from_u32(value: u32) -> Self {
+        Self {
+            opacity: byte(value, 0),
+            size: byte(value, 8),
+            height: byte(value, 16),
+            number_of_bursts: bits(value, 24, 6) as u8,
+            chemical_type: AppearanceObjectSpecificChemicalType::from_u32(bits(value, 30, 2))
+                .unwrap_or_default(),
+        }
+    }
+
+    #[must_use]
+    pub const fn to_u32(&self) -> u32 {
+        self.opacity as u32
+            | (self.size as u32) << 8
+            | (self.height as u32) << 16
+            | (self.number_of_bursts as u32 & 0x3F) << 24
+            | (self.chemical_type as u32 & 0x03) << 30
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+/// Specific appearance of obstacles and prepared positions that can be breached, such as log
+/// cribs, abatis, vehicle defilades, infantry fighting positions, wire and minefields
+pub struct BreachAppearance {
+    pub breach_state: AppearanceObjectSpecificBreachState,
+}
+
+impl BreachAppearance {
+    #[must_use]
+    pub fn from_u32(value: u32) -> Self {
+        Self {
+            breach_state: AppearanceObjectSpecificBreachState::from_u32(bits(value, 0, 2))
+                .unwrap_or_default(),
+        }
+    }
+
+    #[must_use]
+    pub const fn to_u32(&self) -> u32 {
+        self.breach_state as u32 & 0x03
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+/// Specific appearance of a tank ditch linear object
+pub struct TankDitchAppearance {
+    pub breach: AppearanceLinearObjectTankDitchBreach,
+}
+
+impl TankDitchAppearance {
+    #[must_use]
+    pub fn from_u32(value: u32) -> Self {
+        Self {
+            breach: AppearanceLinearObjectTankDitchBreach::from_u32(bits(value, 0, 2))
+                .unwrap_or_default(),
+        }
+    }
+
+    #[must_use]
+    pub const fn to_u32(&self) -> u32 {
+        self.breach as u32 & 0x03
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+/// Specific appearance of an exhaust smoke linear object
+pub struct SmokeAppearance {
+    /// Opacity of the smoke in percent
+    pub opacity: u8,
+    /// Whether the smoke is attached to the entity producing it
+    pub attached: bool,
+    pub chemical_type: AppearanceObjectSpecificChemicalType,
+}
+
+impl SmokeAppearance {
+    #[must_use]
+    pub fn from_u32(value: u32) -> Self {
+        Self {
+            opacity: byte(value, 0),
+            attached: bits(value, 8, 1) == 1,
+            chemical_type: AppearanceObjectSpecificChemicalType::from_u32(bits(value, 9, 2))
+                .unwrap_or_default(),
+        }
+    }
+
+    #[must_use]
+    pub const fn to_u32(&self) -> u32 {
+        self.opacity as u32 | (self.attached as u32) << 8 | (self.chemical_type as u32 & 0x03) << 9
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+/// Specific appearance of a minefield lane marker linear object
+pub struct LaneMarkerAppearance {
+    pub visible_side: AppearanceLinearObjectLaneMarkerVisible,
+}
+
+impl LaneMarkerAppearance {
+    #[must_use]
+    pub fn from_u32(value: u32) -> Self {
+        Self {
+            visible_side: AppearanceLinearObjectLaneMarkerVisible::from_u32(bits(value, 0, 2))
+                .unwrap_or_default(),
+        }
+    }
+
+    #[must_use]
+    pub const fn to_u32(&self) -> u32 {
+        self.visible_side as u32 & 0x03
+    }
+}
+
+/// The specific appearance of an object, interpreted according to its `ObjectType`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SpecificObjectAppearance {
+    Crater(CraterAppearance),
+    RibbonBridge(RibbonBridgeAppearance),
+    Burst(BurstAppearance),
+    Breach(BreachAppearance),
+    TankDitch(TankDitchAppearance),
+    Smoke(SmokeAppearance),
+    LaneMarker(LaneMarkerAppearance),
+    /// An object type without a specific appearance record; the raw bits are kept verbatim
+    Other(u32),
+}
+
+impl Default for SpecificObjectAppearance {
+    fn default() -> Self {
+        Self::Other(0)
+    }
+}
+
+impl SpecificObjectAppearance {
+    /// Interprets the specific appearance of a point object
+    #[must_use]
+    pub fn for_point_object(object_type: &ObjectType, value: u32) -> Self {
+        match (
+            ObjectKind::from_u8(object_type.object_kind),
+            object_type.category,
+        ) {
+            (Some(ObjectKind::Obstacle), POINT_OBSTACLE_CRATER) => {
+                Self::Crater(CraterAppearance::from_u32(value))
+            }
+            (Some(ObjectKind::Obstacle | ObjectKind::PreparedPosition), _) => {
+                Self::Breach(BreachAppearance::from_u32(value))
+            }
+            (Some(ObjectKind::Passageway), POINT_PASSAGEWAY_RIBBON_BRIDGE) => {
+                Self::RibbonBridge(RibbonBridgeAppearance::from_u32(value))
+            }
+            (Some(ObjectKind::TacticalSmoke), _) => Self::Burst(BurstAppearance::from_u32(value)),
+            _ => Self::Other(value),
+        }
+    }
+
+    /// Interprets the specific appearance of a linear object segment
+    #[must_use]
+    pub fn for_linear_object(object_type: &ObjectType, value: u32) -> Self {
+        match (
+            ObjectKind::from_u8(object_type.object_kind),
+            object_type.category,
+        ) {
+            (Some(ObjectKind::Obstacle), LINEAR_OBSTACLE_TANK_DITCH) => {
+                Self::TankDitch(TankDitchAppearance::from_u32(value))
+            }
+            (Some(ObjectKind::Obstacle), _) => Self::Breach(BreachAppearance::from_u32(value)),
+            (Some(ObjectKind::TacticalSmoke), _) => Self::Smoke(SmokeAppearance::from_u32(value)),
+            (Some(ObjectKind::ObstacleMarker), _) => {
+                Self::LaneMarker(LaneMarkerAppearance::from_u32(value))
+            }
+            _ => Self::Other(value),
+        }
+    }
+
+    /// Interprets the specific appearance of an areal object
+    #[must_use]
+    pub fn for_areal_object(object_type: &ObjectType, value: u32) -> Self {
+        match ObjectKind::from_u8(object_type.object_kind) {
+            Some(ObjectKind::Obstacle) => Self::Breach(BreachAppearance::from_u32(value)),
+            _ => Self::Other(value),
+        }
+    }
+
+    /// The raw 32-bit value written in the specific appearance field
+    #[must_use]
+    pub const fn to_u32(&self) -> u32 {
+        match self {
+            Self::Crater(appearance) => appearance.to_u32(),
+            Self::RibbonBridge(appearance) => appearance.to_u32(),
+            Self::Burst(appearance) => appearance.to_u32(),
+            Self::Breach(appearance) => appearance.to_u32(),
+            Self::TankDitch(appearance) => appearance.to_u32(),
+            Self::Smoke(appearance) => appearance.to_u32(),
+            Self::LaneMarker(appearance) => appearance.to_u32(),
+            Self::Other(value) => *value,
+        }
+    }
+}
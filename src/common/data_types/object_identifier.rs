@@ -6,11 +6,11 @@
 use bytes::{Buf, BufMut, BytesMut};
 
 use crate::{
-    common::data_types::SimulationAddress,
+    common::data_types::{EntityId, SimulationAddress},
     pdu_macro::{FieldDeserialize, FieldLen, FieldSerialize},
 };
 
-#[derive(Clone, Debug, Default)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
 pub struct ObjectIdentifier {
     pub simulation_address: SimulationAddress,
     pub object_number: u16,
@@ -22,6 +22,14 @@ impl ObjectIdentifier {
         Self::default()
     }
 
+    #[must_use]
+    pub const fn from_parts(simulation_address: SimulationAddress, object_number: u16) -> Self {
+        Self {
+            simulation_address,
+            object_number,
+        }
+    }
+
     pub fn serialize(&self, buf: &mut BytesMut) {
         self.simulation_address.serialize(buf);
         buf.put_u16(self.object_number);
@@ -35,6 +43,12 @@ impl ObjectIdentifier {
     }
 }
 
+impl From<EntityId> for ObjectIdentifier {
+    fn from(entity_id: EntityId) -> Self {
+        Self::from_parts(entity_id.simulation_address, entity_id.entity_id)
+    }
+}
+
 impl FieldSerialize for ObjectIdentifier {
     fn serialize_field(&self, buf: &mut BytesMut) {
         self.serialize(buf);
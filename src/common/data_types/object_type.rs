@@ -7,7 +7,7 @@ use bytes::{Buf, BufMut, BytesMut};
 
 use crate::pdu_macro::{FieldDeserialize, FieldLen, FieldSerialize};
 
-#[derive(Clone, Debug, Default)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
 pub struct ObjectType {
     pub domain: u8,
     pub object_kind: u8,
@@ -5,7 +5,10 @@
 
 use bytes::{Buf, BufMut, BytesMut};
 
-use crate::pdu_macro::{FieldDeserialize, FieldLen, FieldSerialize};
+use crate::{
+    common::{data_types::datum_records::VariableDatumValue, enums::VariableRecordTypes},
+    pdu_macro::{FieldDeserialize, FieldLen, FieldSerialize},
+};
 
 use super::record_specification_element::RecordSpecificationElement;
 
@@ -27,6 +30,52 @@ impl RecordSpecification {
         }
     }
 
+    /// A record specification holding `record_sets`, with the number of record sets set to match
+    #[must_use]
+    pub fn from_record_sets(record_sets: Vec<RecordSpecificationElement>) -> Self {
+        let mut specification = Self::default();
+        for record_set in record_sets {
+            specification.push(record_set);
+        }
+        specification
+    }
+
+    /// Appends a record set and counts it in the number of record sets
+    pub fn push(&mut self, record_set: RecordSpecificationElement) {
+        self.record_sets.push(record_set);
+        self.number_of_record_sets = self.number_of_record_sets.saturating_add(1);
+    }
+
+    #[must_use]
+    pub fn with_record_set(mut self, record_set: RecordSpecificationElement) -> Self {
+        self.push(record_set);
+        self
+    }
+
+    /// The first record set with the record ID `record_id`
+    #[must_use]
+    pub fn record_set(
+        &self,
+        record_id: VariableRecordTypes,
+    ) -> Option<&RecordSpecificationElement> {
+        self.record_sets
+            .iter()
+            .find(|record_set| record_set.record_id == record_id as u32)
+    }
+
+    /// The records of the first record set with the record ID `record_id`, interpreted as `T`
+    #[must_use]
+    pub fn values<T: VariableDatumValue>(&self, record_id: VariableRecordTypes) -> Option<Vec<T>> {
+        self.record_set(record_id)?.values()
+    }
+
+    /// The first record of the first record set with the record ID `record_id`, interpreted as
+    /// `T`
+    #[must_use]
+    pub fn value<T: VariableDatumValue>(&self, record_id: VariableRecordTypes) -> Option<T> {
+        self.record_set(record_id)?.value()
+    }
+
     pub fn serialize(&self, buf: &mut BytesMut) {
         buf.put_u32(self.number_of_record_sets);
         for i in 0..self.record_sets.len() {
@@ -4,19 +4,35 @@
 //     Licensed under the BSD-2-Clause License
 
 use bytes::{Buf, BufMut, BytesMut};
+use num_traits::FromPrimitive;
 
-use crate::pdu_macro::{FieldDeserialize, FieldLen, FieldSerialize};
+use crate::{
+    common::{
+        constants::padding_to_64_bits, data_types::datum_records::VariableDatumValue,
+        dis_error::DISError, enums::VariableRecordTypes,
+    },
+    pdu_macro::{FieldDeserialize, FieldLen, FieldSerialize},
+};
 
 #[derive(Clone, Debug)]
+/// A record set of a record specification, IEEE 1278.1-2012 §6.2.73
+///
+/// The set holds `record_count` records of `record_length` bits each, followed on the wire by
+/// padding to a 64-bit boundary. Typed values are read and written with the same
+/// [`VariableDatumValue`] conversions as variable datums.
 pub struct RecordSpecificationElement {
     pub record_id: u32,
     pub record_set_serial_number: u32,
+    /// Length of each record in bits
     pub record_length: u16,
     pub record_count: u16,
     pub record_values: Vec<u8>,
 }
 
 impl RecordSpecificationElement {
+    /// Octets of the record ID, serial number, length and count fields
+    const HEADER_OCTETS: usize = 12;
+
     #[must_use]
     pub const fn new(
         record_id: u32,
@@ -34,35 +50,132 @@ impl RecordSpecificationElement {
         }
     }
 
-    pub fn serialize(&self, buf: &mut BytesMut) {
-        buf.put_u32(self.record_id);
-        buf.put_u32(self.record_set_serial_number);
-        buf.put_u16(self.record_length);
-        buf.put_u16(self.record_count);
-        let num_record_values: usize = (self.record_length * self.record_count).into();
-        for i in 0..num_record_values {
-            buf.put_u8(self.record_values[i]);
-        }
+    /// A record set holding one record
+    ///
+    /// # Errors
+    /// See [`Self::from_values`].
+    pub fn from_value<T: VariableDatumValue>(
+        record_id: VariableRecordTypes,
+        record_set_serial_number: u32,
+        value: &T,
+    ) -> Result<Self, DISError> {
+        Self::from_values(
+            record_id,
+            record_set_serial_number,
+            std::slice::from_ref(value),
+        )
     }
 
-    pub fn deserialize<B: Buf>(buf: &mut B) -> Self {
-        let record_id = buf.get_u32();
-        let record_set_serial_number = buf.get_u32();
-        let record_length = buf.get_u16();
-        let record_count = buf.get_u16();
-        let num_record_values = record_length * record_count;
-        let mut record_values: Vec<u8> = vec![];
-        for _ in 0..num_record_values {
-            record_values.push(buf.get_u8());
+    /// A record set holding one record per value, with the length and count computed from them
+    ///
+    /// # Errors
+    /// Returns `DISError::InvalidFieldValue` if the values differ in length, or if the length
+    /// in bits or the number of values does not fit 16 bits.
+    pub fn from_values<T: VariableDatumValue>(
+        record_id: VariableRecordTypes,
+        record_set_serial_number: u32,
+        values: &[T],
+    ) -> Result<Self, DISError> {
+        let record_count = u16::try_from(values.len()).map_err(|_| {
+            DISError::invalid_field(
+                "record_count".to_string(),
+                values.len().to_string(),
+                "more than 65535 records".to_string(),
+            )
+        })?;
+        let mut record_length = None;
+        let mut record_values = Vec::new();
+        for value in values {
+            let (octets, length_bits) = value.to_variable_datum();
+            if record_length.is_some_and(|length| length != length_bits) {
+                return Err(DISError::invalid_field(
+                    "record_length".to_string(),
+                    length_bits.to_string(),
+                    "records of one set must have the same length".to_string(),
+                ));
+            }
+            record_length = Some(length_bits);
+            record_values.extend(octets);
         }
-
-        Self {
-            record_id,
+        let length_bits = record_length.unwrap_or(0);
+        let record_length = u16::try_from(length_bits).map_err(|_| {
+            DISError::invalid_field(
+                "record_length".to_string(),
+                length_bits.to_string(),
+                "records longer than 65535 bits".to_string(),
+            )
+        })?;
+        Ok(Self {
+            record_id: record_id as u32,
             record_set_serial_number,
             record_length,
             record_count,
             record_values,
+        })
+    }
+
+    /// The record ID, if it is a known variable record type
+    #[must_use]
+    pub fn record_type(&self) -> Option<VariableRecordTypes> {
+        VariableRecordTypes::from_u32(self.record_id)
+    }
+
+    /// The records interpreted as `T`, or `None` if they do not hold values of that type
+    ///
+    /// Records that are not a whole number of octets long are not decoded.
+    #[must_use]
+    pub fn values<T: VariableDatumValue>(&self) -> Option<Vec<T>> {
+        if !self.record_length.is_multiple_of(8) {
+            return None;
         }
+        let record_octets = usize::from(self.record_length / 8);
+        (0..usize::from(self.record_count))
+            .map(|index| {
+                let octets = self
+                    .record_values
+                    .get(index * record_octets..(index + 1) * record_octets)?;
+                T::from_variable_datum(octets, u32::from(self.record_length))
+            })
+            .collect()
+    }
+
+    /// The first record interpreted as `T`
+    #[must_use]
+    pub fn value<T: VariableDatumValue>(&self) -> Option<T> {
+        self.values().and_then(|values| values.into_iter().next())
+    }
+
+    /// Octets taken by the records on the wire, before padding
+    fn values_octets(&self) -> usize {
+        (usize::from(self.record_length) * usize::from(self.record_count)).div_ceil(8)
+    }
+
+    pub fn serialize(&self, buf: &mut BytesMut) {
+        buf.put_u32(self.record_id);
+        buf.put_u32(self.record_set_serial_number);
+        buf.put_u16(self.record_length);
+        buf.put_u16(self.record_count);
+        let values_octets = self.values_octets();
+        let present = values_octets.min(self.record_values.len());
+        buf.put_slice(&self.record_values[..present]);
+        buf.put_bytes(0, values_octets - present);
+        buf.put_bytes(0, padding_to_64_bits(Self::HEADER_OCTETS + values_octets));
+    }
+
+    pub fn deserialize<B: Buf>(buf: &mut B) -> Self {
+        let mut record_set = Self::new(
+            buf.get_u32(),
+            buf.get_u32(),
+            buf.get_u16(),
+            buf.get_u16(),
+            Vec::new(),
+        );
+        let values_octets = record_set.values_octets();
+        record_set.record_values = vec![0; values_octets.min(buf.remaining())];
+        buf.copy_to_slice(&mut record_set.record_values);
+        let padding = padding_to_64_bits(Self::HEADER_OCTETS + values_octets);
+        buf.advance(padding.min(buf.remaining()));
+        record_set
     }
 }
 
@@ -80,10 +193,95 @@ impl FieldDeserialize for RecordSpecificationElement {
 
 impl FieldLen for RecordSpecificationElement {
     fn field_len(&self) -> usize {
-        self.record_id.field_len()
-            + self.record_set_serial_number.field_len()
-            + self.record_length.field_len()
-            + self.record_count.field_len()
-            + self.record_values.field_len()
+        let values_octets = self.values_octets();
+        Self::HEADER_OCTETS
+            + values_octets
+            + padding_to_64_bits(Self::HEADER_OCTETS + values_octets)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::data_types::{
+        record_specification::RecordSpecification, world_coordinate::WorldCoordinate,
+    };
+
+    #[test]
+    fn typed_record_sets_are_padded_to_64_bits() {
+        let record_set =
+            RecordSpecificationElement::from_values(VariableRecordTypes::Fuel, 3, &[5u16, 6, 7])
+                .expect("fits a record set");
+        assert_eq!(record_set.record_length, 16);
+        assert_eq!(record_set.record_count, 3);
+        assert_eq!(record_set.field_len(), 24);
+
+        let mut buf = BytesMut::new();
+        record_set.serialize(&mut buf);
+        assert_eq!(buf.len(), 24);
+        assert_eq!(&buf[12..], &[0, 5, 0, 6, 0, 7, 0, 0, 0, 0, 0, 0]);
+
+        let mut bytes = buf.freeze();
+        let decoded = RecordSpecificationElement::deserialize(&mut bytes);
+        assert!(bytes.is_empty());
+        assert_eq!(decoded.record_type(), Some(VariableRecordTypes::Fuel));
+        assert_eq!(decoded.record_set_serial_number, 3);
+        assert_eq!(decoded.values::<u16>(), Some(vec![5, 6, 7]));
+        assert_eq!(decoded.values::<u32>(), None);
+    }
+
+    #[test]
+    fn truncated_record_sets_keep_the_octets_that_remain() {
+        let mut buf = BytesMut::new();
+        buf.put_u32(240_000);
+        buf.put_u32(1);
+        buf.put_u16(32);
+        buf.put_u16(4);
+        buf.put_slice(&[1, 2, 3, 4, 5, 6]);
+
+        let mut bytes = buf.freeze();
+        let decoded = RecordSpecificationElement::deserialize(&mut bytes);
+        assert!(bytes.is_empty());
+        assert_eq!(decoded.record_count, 4);
+        assert_eq!(decoded.record_values, vec![1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn record_specifications_count_and_find_their_record_sets() {
+        let location = WorldCoordinate::new(1.0, 2.0, 3.0);
+        let specification = RecordSpecification::from_record_sets(vec![
+            RecordSpecificationElement::from_value(
+                VariableRecordTypes::EntityLocation,
+                1,
+                &location,
+            )
+            .expect("fits a record set"),
+        ])
+        .with_record_set(
+            RecordSpecificationElement::from_value(VariableRecordTypes::Fuel, 1, &9u8)
+                .expect("fits a record set"),
+        );
+        assert_eq!(specification.number_of_record_sets, 2);
+        assert_eq!(specification.field_len(), 4 + 40 + 16);
+
+        let mut buf = BytesMut::new();
+        specification.serialize(&mut buf);
+        assert_eq!(buf.len(), specification.field_len());
+        let decoded = RecordSpecification::deserialize(&mut buf.freeze());
+        assert_eq!(
+            decoded.value(VariableRecordTypes::EntityLocation),
+            Some(location)
+        );
+        assert_eq!(decoded.value::<u8>(VariableRecordTypes::Fuel), Some(9));
+        assert_eq!(
+            decoded.value::<u8>(VariableRecordTypes::EntityOrientation),
+            None
+        );
+
+        let mixed = [String::from("a"), String::from("ab")];
+        assert!(
+            RecordSpecificationElement::from_values(VariableRecordTypes::EntityMarking, 1, &mixed)
+                .is_err()
+        );
     }
 }
@@ -7,11 +7,14 @@
 use bytes::{Buf, BufMut, BytesMut};
 
 use crate::{
-    common::SerializedLength,
+    common::{
+        SerializedLength,
+        constants::{ALL_APPLIC, ALL_SITES},
+    },
     pdu_macro::{FieldDeserialize, FieldLen, FieldSerialize},
 };
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 /// Implemented according to IEEE 1278.1-2012 §6.2.80
 pub struct SimulationAddress {
     /// Identification number representing the site, which may be a facility,
@@ -42,6 +45,22 @@ impl SimulationAddress {
         }
     }
 
+    /// Whether the site or application is a wildcard
+    #[must_use]
+    pub const fn is_wildcard(self) -> bool {
+        self.site_id == ALL_SITES || self.application_id == ALL_APPLIC
+    }
+
+    /// Whether this address, as a receiving address, addresses the simulation at `address`
+    ///
+    /// The site and application each match if equal or if this one is `ALL_SITES` or
+    /// `ALL_APPLIC`.
+    #[must_use]
+    pub const fn addresses(self, address: Self) -> bool {
+        (self.site_id == ALL_SITES || self.site_id == address.site_id)
+            && (self.application_id == ALL_APPLIC || self.application_id == address.application_id)
+    }
+
     pub fn serialize(&self, buf: &mut BytesMut) {
         buf.put_u16(self.site_id);
         buf.put_u16(self.application_id);
@@ -11,7 +11,7 @@ use crate::{
     pdu_macro::{FieldDeserialize, FieldLen, FieldSerialize},
 };
 
-#[derive(Copy, Clone, Debug, Default)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
 pub struct SimulationIdentifier {
     pub simulation_address: SimulationAddress,
     pub reference_number: u16,
@@ -26,9 +26,19 @@ impl SimulationIdentifier {
         }
     }
 
+    /// Whether this identifier, as a receiving identifier, addresses the simulation `id`
+    ///
+    /// The simulation address may use the `ALL_SITES` and `ALL_APPLIC` wildcards; the
+    /// reference numbers must be equal.
+    #[must_use]
+    pub const fn addresses(self, id: Self) -> bool {
+        self.simulation_address.addresses(id.simulation_address)
+            && self.reference_number == id.reference_number
+    }
+
     pub fn serialize(&self, buf: &mut BytesMut) {
         self.simulation_address.serialize(buf);
-        buf.put_u16(0u16);
+        buf.put_u16(self.reference_number);
     }
 
     pub fn deserialize<B: Buf>(buf: &mut B) -> Self {
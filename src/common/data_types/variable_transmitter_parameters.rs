@@ -3,15 +3,143 @@
 //
 //     Licensed under the BSD-2-Clause License
 use bytes::{Buf, BufMut, BytesMut};
+use num_traits::FromPrimitive;
 
-use crate::common::enums::VariableParameterRecordType;
-use crate::pdu_macro::{FieldDeserialize, FieldLen, FieldSerialize};
+use crate::{
+    common::{
+        SerializedLength,
+        constants::padding_to_64_bits,
+        data_types::{EntityCoordinateVector, WorldCoordinate, modulation_parameters::NetId},
+        enums::{
+            HighFidelityHAVEQUICKTODTransmitIndicator, VariableTransmitterParametersRecordType,
+        },
+    },
+    pdu_macro::{FieldDeserialize, FieldLen, FieldMinLen, FieldSerialize},
+};
 
-#[derive(Clone, Debug, Default)]
-pub struct VariableTransmitterParameters {
-    pub record_type: VariableParameterRecordType,
-    pub record_length: u16,
-    pub record_specific_fields: Vec<u8>,
+/// Length in octets of the record type and record length fields
+const VTP_RECORD_HEADER_LENGTH: usize = 6;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+/// High fidelity HAVE QUICK/SATURN transmitter parameters
+pub struct HighFidelityHaveQuickSaturn {
+    padding: u16,
+    pub net_id: NetId,
+    pub tod_transmit_indicator: HighFidelityHAVEQUICKTODTransmitIndicator,
+    padding2: u8,
+    /// Offset of the transmitter's time of day from the simulation time, in milliseconds
+    pub tod_delta: u32,
+    /// The six segments of the word of day
+    pub word_of_day: [u32; 6],
+}
+
+impl HighFidelityHaveQuickSaturn {
+    #[must_use]
+    pub const fn new(
+        net_id: NetId,
+        tod_transmit_indicator: HighFidelityHAVEQUICKTODTransmitIndicator,
+        tod_delta: u32,
+        word_of_day: [u32; 6],
+    ) -> Self {
+        Self {
+            padding: 0,
+            net_id,
+            tod_transmit_indicator,
+            padding2: 0,
+            tod_delta,
+            word_of_day,
+        }
+    }
+
+    pub fn serialize(&self, buf: &mut BytesMut) {
+        buf.put_u16(self.padding);
+        buf.put_u16(self.net_id.to_u16());
+        buf.put_u8(self.tod_transmit_indicator as u8);
+        buf.put_u8(self.padding2);
+        buf.put_u32(self.tod_delta);
+        for word in self.word_of_day {
+            buf.put_u32(word);
+        }
+    }
+
+    pub fn deserialize<B: Buf>(buf: &mut B) -> Self {
+        Self {
+            padding: buf.get_u16(),
+            net_id: NetId::from_u16(buf.get_u16()),
+            tod_transmit_indicator: HighFidelityHAVEQUICKTODTransmitIndicator::deserialize(buf),
+            padding2: buf.get_u8(),
+            tod_delta: buf.get_u32(),
+            word_of_day: std::array::from_fn(|_| buf.get_u32()),
+        }
+    }
+}
+
+impl SerializedLength for HighFidelityHaveQuickSaturn {
+    /// Length of the record-specific fields, excluding the record header
+    const LENGTH: usize = 34;
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+/// Location of the transmitting antenna, in world coordinates and relative to the entity
+pub struct AntennaLocation {
+    pub antenna_location: WorldCoordinate,
+    pub relative_antenna_location: EntityCoordinateVector,
+}
+
+impl AntennaLocation {
+    #[must_use]
+    pub const fn new(
+        antenna_location: WorldCoordinate,
+        relative_antenna_location: EntityCoordinateVector,
+    ) -> Self {
+        Self {
+            antenna_location,
+            relative_antenna_location,
+        }
+    }
+
+    pub fn serialize(&self, buf: &mut BytesMut) {
+        self.antenna_location.serialize(buf);
+        self.relative_antenna_location.serialize(buf);
+    }
+
+    pub fn deserialize<B: Buf>(buf: &mut B) -> Self {
+        Self {
+            antenna_location: WorldCoordinate::deserialize(buf),
+            relative_antenna_location: EntityCoordinateVector::deserialize(buf),
+        }
+    }
+}
+
+impl SerializedLength for AntennaLocation {
+    /// Length of the record-specific fields, excluding the record header
+    const LENGTH: usize = 36;
+}
+
+/// A Variable Transmitter Parameters record, keyed by its record type
+///
+/// Records are padded to a 64-bit boundary, and the record length (in octets) covers the
+/// record type, the record length itself and the padding.
+///
+/// Only the high fidelity HAVE QUICK/SATURN and antenna location records are typed. Crypto
+/// Control records are read as [`Self::Unknown`], and SISO-REF-010-2023 assigns no record type
+/// to a propagation model record, so none is modelled.
+#[derive(Clone, Debug, PartialEq)]
+pub enum VariableTransmitterParameters {
+    HighFidelityHaveQuickSaturn(HighFidelityHaveQuickSaturn),
+    AntennaLocation(AntennaLocation),
+    /// A record type this crate does not model, such as Crypto Control; the record-specific
+    /// fields are kept verbatim
+    Unknown {
+        record_type: u32,
+        record_specific_fields: Vec<u8>,
+    },
+}
+
+impl Default for VariableTransmitterParameters {
+    fn default() -> Self {
+        Self::HighFidelityHaveQuickSaturn(HighFidelityHaveQuickSaturn::default())
+    }
 }
 
 impl FieldSerialize for VariableTransmitterParameters {
@@ -28,36 +156,110 @@ impl FieldDeserialize for VariableTransmitterParameters {
 
 impl FieldLen for VariableTransmitterParameters {
     fn field_len(&self) -> usize {
-        4 /* record_type u32 */ + 2 /* record_length u16 */ + self.record_specific_fields.len()
+        VTP_RECORD_HEADER_LENGTH + self.body_length() + self.padding_length()
     }
 }
 
+impl FieldMinLen for VariableTransmitterParameters {
+    const MIN_LEN: usize = VTP_RECORD_HEADER_LENGTH;
+}
+
 impl VariableTransmitterParameters {
     #[must_use]
     pub fn new() -> Self {
         Self::default()
     }
 
-    pub fn serialize(&self, buf: &mut BytesMut) {
-        buf.put_u32(self.record_type as u32);
-        buf.put_u16(self.record_length);
-        for i in 0..self.record_specific_fields.len() {
-            buf.put_u8(self.record_specific_fields[i]);
+    /// The raw record type, as written on the wire
+    #[must_use]
+    pub const fn record_type(&self) -> u32 {
+        match self {
+            Self::HighFidelityHaveQuickSaturn(_) => {
+                VariableTransmitterParametersRecordType::HighFidelityHAVEQUICKSATURNRadio as u32
+            }
+            Self::AntennaLocation(_) => {
+                VariableTransmitterParametersRecordType::AntennaLocation as u32
+            }
+            Self::Unknown { record_type, .. } => *record_type,
         }
     }
 
-    pub fn deserialize<B: Buf>(buf: &mut B) -> Self {
-        let record_type = VariableParameterRecordType::deserialize(buf);
-        let record_length = buf.get_u16();
-        let mut record_specific_fields: Vec<u8> = vec![];
-        for _ in 0..record_length {
-            record_specific_fields.push(buf.get_u8());
+    /// The record type, if it is a known Variable Transmitter Parameters record type
+    #[must_use]
+    pub fn known_record_type(&self) -> Option<VariableTransmitterParametersRecordType> {
+        VariableTransmitterParametersRecordType::from_u32(self.record_type())
+    }
+
+    const fn body_length(&self) -> usize {
+        match self {
+            Self::HighFidelityHaveQuickSaturn(_) => HighFidelityHaveQuickSaturn::LENGTH,
+            Self::AntennaLocation(_) => AntennaLocation::LENGTH,
+            Self::Unknown {
+                record_specific_fields,
+                ..
+            } => record_specific_fields.len(),
         }
+    }
 
-        Self {
-            record_type,
-            record_length,
-            record_specific_fields,
+    const fn padding_length(&self) -> usize {
+        padding_to_64_bits(VTP_RECORD_HEADER_LENGTH + self.body_length())
+    }
+
+    /// The length of the whole record in octets, including padding
+    #[must_use]
+    pub fn record_length(&self) -> u16 {
+        u16::try_from(self.field_len()).unwrap_or(u16::MAX)
+    }
+
+    pub fn serialize(&self, buf: &mut BytesMut) {
+        buf.put_u32(self.record_type());
+        buf.put_u16(self.record_length());
+        match self {
+            Self::HighFidelityHaveQuickSaturn(record) => record.serialize(buf),
+            Self::AntennaLocation(record) => record.serialize(buf),
+            Self::Unknown {
+                record_specific_fields,
+                ..
+            } => buf.put_slice(record_specific_fields),
         }
+        buf.put_bytes(0u8, self.padding_length());
+    }
+
+    pub fn deserialize<B: Buf>(buf: &mut B) -> Self {
+        let record_type = buf.get_u32();
+        let record_length = usize::from(buf.get_u16());
+
+        let available = record_length
+            .saturating_sub(VTP_RECORD_HEADER_LENGTH)
+            .min(buf.remaining());
+        let remaining_before = buf.remaining();
+        let record = match VariableTransmitterParametersRecordType::from_u32(record_type) {
+            Some(VariableTransmitterParametersRecordType::HighFidelityHAVEQUICKSATURNRadio)
+                if available >= HighFidelityHaveQuickSaturn::LENGTH =>
+            {
+                Self::HighFidelityHaveQuickSaturn(HighFidelityHaveQuickSaturn::deserialize(buf))
+            }
+            Some(VariableTransmitterParametersRecordType::AntennaLocation)
+                if available >= AntennaLocation::LENGTH =>
+            {
+                Self::AntennaLocation(AntennaLocation::deserialize(buf))
+            }
+            _ => {
+                let mut record_specific_fields = vec![0u8; available];
+                buf.copy_to_slice(&mut record_specific_fields);
+                // Padding cannot be told apart from the fields of an unknown record, so it is
+                // kept and the record re-serializes to the same length
+                Self::Unknown {
+                    record_type,
+                    record_specific_fields,
+                }
+            }
+        };
+
+        // Skip the padding that aligns the record to a 64-bit boundary
+        let consumed = remaining_before - buf.remaining();
+        buf.advance(available.saturating_sub(consumed).min(buf.remaining()));
+
+        record
     }
 }
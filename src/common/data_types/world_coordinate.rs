@@ -11,7 +11,7 @@ use crate::{
     pdu_macro::{FieldDeserialize, FieldLen, FieldSerialize},
 };
 
-#[derive(Copy, Clone, Debug, Default)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
 /// Implemented according to IEEE 1278.1-2012 §6.2.98
 pub struct WorldCoordinate {
     /// The coordinate value along the X-axis which passes through the prime meridian at the equator
@@ -38,6 +38,17 @@ impl WorldCoordinate {
         Self { x, y, z }
     }
 
+    /// Straight-line distance to `other` in meters
+    #[must_use]
+    pub fn distance_to(&self, other: &Self) -> f64 {
+        (other.z - self.z)
+            .mul_add(
+                other.z - self.z,
+                (other.y - self.y).mul_add(other.y - self.y, (other.x - self.x).powi(2)),
+            )
+            .sqrt()
+    }
+
     pub fn serialize(&self, buf: &mut BytesMut) {
         buf.put_f64(self.x);
         buf.put_f64(self.y);
@@ -68,6 +68,12 @@ pub enum DISError {
 
     #[error("PDU size exceeds maximum allowed: {size} > {max_size}")]
     PduSizeExceeded { size: usize, max_size: usize },
+
+    #[error("No response to request {request_id} before the timeout")]
+    RequestTimeout { request_id: u32 },
+
+    #[error("Request {request_id} was cancelled")]
+    RequestCancelled { request_id: u32 },
 }
 
 impl DISError {
@@ -127,6 +133,18 @@ impl DISError {
     pub const fn pdu_size_exceeded(size: usize, max_size: usize) -> Self {
         Self::PduSizeExceeded { size, max_size }
     }
+
+    #[must_use]
+    /// Create a new `RequestTimeout` error
+    pub const fn request_timeout(request_id: u32) -> Self {
+        Self::RequestTimeout { request_id }
+    }
+
+    #[must_use]
+    /// Create a new `RequestCancelled` error
+    pub const fn request_cancelled(request_id: u32) -> Self {
+        Self::RequestCancelled { request_id }
+    }
 }
 
 /// Result type for DIS operations
@@ -12,7 +12,7 @@ use modular_bitfield::Specifier;
 use num_derive::FromPrimitive;
 use num_traits::FromPrimitive;
 
-use crate::pdu_macro::{FieldDeserialize, FieldLen, FieldSerialize};
+use crate::pdu_macro::{FieldDeserialize, FieldLen, FieldMinLen, FieldSerialize};
 
 // SISO-REF-010-2023 Protocol Version [UID 3]
 #[derive(Copy, Clone, Debug, Default, FromPrimitive, PartialEq, Eq)]
@@ -3605,6 +3605,44 @@ impl FieldLen for VariableRecordTypes {
     }
 }
 
+impl FieldMinLen for VariableRecordTypes {
+    const MIN_LEN: usize = 4;
+}
+
+// SISO-REF-010-2023 VariableRecordTypes [UID 66], Variable Transmitter Parameters subset
+#[derive(Copy, Clone, Debug, Default, FromPrimitive, PartialEq, Eq)]
+pub enum VariableTransmitterParametersRecordType {
+    #[default]
+    HighFidelityHAVEQUICKSATURNRadio = 3000,
+    CryptoControl = 5000,
+    AntennaLocation = 5009,
+}
+
+impl VariableTransmitterParametersRecordType {
+    #[must_use]
+    pub fn deserialize<B: Buf>(buf: &mut B) -> Self {
+        Self::from_u32(buf.get_u32()).unwrap_or_else(Self::default)
+    }
+}
+
+impl FieldSerialize for VariableTransmitterParametersRecordType {
+    fn serialize_field(&self, buf: &mut BytesMut) {
+        buf.put_u32(*self as u32);
+    }
+}
+
+impl FieldDeserialize for VariableTransmitterParametersRecordType {
+    fn deserialize_field<B: Buf>(buf: &mut B) -> Self {
+        Self::deserialize(buf)
+    }
+}
+
+impl FieldLen for VariableTransmitterParametersRecordType {
+    fn field_len(&self) -> usize {
+        4
+    }
+}
+
 // SISO-REF-010-2023 Reason [UID 67]
 #[derive(Copy, Clone, Debug, Default, FromPrimitive, PartialEq, Eq)]
 pub enum Reason {
@@ -9461,6 +9499,140 @@ impl FieldLen for ObjectStateAppearanceGeneral {
     }
 }
 
+// SISO-REF-010-2023 ObjectStateModificationPointObject [UID 240]
+bitflags! {
+    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+    pub struct ObjectStateModificationPointObject: u8 {
+        const IsLocationModified = 1 << 0;
+        const IsOrientationModified = 1 << 1;
+    }
+}
+
+impl Default for ObjectStateModificationPointObject {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl ObjectStateModificationPointObject {
+    #[must_use]
+    pub const fn as_u8(&self) -> u8 {
+        self.bits()
+    }
+
+    #[must_use]
+    pub const fn from_u8(bits: u8) -> Option<Self> {
+        Self::from_bits(bits)
+    }
+}
+
+impl FieldSerialize for ObjectStateModificationPointObject {
+    fn serialize_field(&self, buf: &mut BytesMut) {
+        buf.put_u8(self.as_u8());
+    }
+}
+
+impl FieldDeserialize for ObjectStateModificationPointObject {
+    fn deserialize_field<B: Buf>(buf: &mut B) -> Self {
+        Self::from_bits_truncate(buf.get_u8())
+    }
+}
+
+impl FieldLen for ObjectStateModificationPointObject {
+    fn field_len(&self) -> usize {
+        1
+    }
+}
+
+// SISO-REF-010-2023 ObjectStateModificationLinearObject [UID 241]
+bitflags! {
+    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+    pub struct ObjectStateModificationLinearObject: u8 {
+        const IsLocationModified = 1 << 0;
+        const IsOrientationModified = 1 << 1;
+    }
+}
+
+impl Default for ObjectStateModificationLinearObject {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl ObjectStateModificationLinearObject {
+    #[must_use]
+    pub const fn as_u8(&self) -> u8 {
+        self.bits()
+    }
+
+    #[must_use]
+    pub const fn from_u8(bits: u8) -> Option<Self> {
+        Self::from_bits(bits)
+    }
+}
+
+impl FieldSerialize for ObjectStateModificationLinearObject {
+    fn serialize_field(&self, buf: &mut BytesMut) {
+        buf.put_u8(self.as_u8());
+    }
+}
+
+impl FieldDeserialize for ObjectStateModificationLinearObject {
+    fn deserialize_field<B: Buf>(buf: &mut B) -> Self {
+        Self::from_bits_truncate(buf.get_u8())
+    }
+}
+
+impl FieldLen for ObjectStateModificationLinearObject {
+    fn field_len(&self) -> usize {
+        1
+    }
+}
+
+// SISO-REF-010-2023 ObjectStateModificationArealObject [UID 242]
+bitflags! {
+    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+    pub struct ObjectStateModificationArealObject: u8 {
+        const IsLocationModified = 1 << 0;
+    }
+}
+
+impl Default for ObjectStateModificationArealObject {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl ObjectStateModificationArealObject {
+    #[must_use]
+    pub const fn as_u8(&self) -> u8 {
+        self.bits()
+    }
+
+    #[must_use]
+    pub const fn from_u8(bits: u8) -> Option<Self> {
+        Self::from_bits(bits)
+    }
+}
+
+impl FieldSerialize for ObjectStateModificationArealObject {
+    fn serialize_field(&self, buf: &mut BytesMut) {
+        buf.put_u8(self.as_u8());
+    }
+}
+
+impl FieldDeserialize for ObjectStateModificationArealObject {
+    fn deserialize_field<B: Buf>(buf: &mut B) -> Self {
+        Self::from_bits_truncate(buf.get_u8())
+    }
+}
+
+impl FieldLen for ObjectStateModificationArealObject {
+    fn field_len(&self) -> usize {
+        1
+    }
+}
+
 // SISO-REF-010-2023 GriddedDataFieldNumber [UID 243]
 #[derive(Copy, Clone, Debug, FromPrimitive, PartialEq, Eq)]
 pub enum GriddedDataFieldNumber {}
@@ -11114,7 +11286,25 @@ pub enum RecordREventType {
 impl RecordREventType {
     #[must_use]
     pub fn deserialize<B: Buf>(buf: &mut B) -> Self {
-        Self::from_u8(buf.get_u8()).unwrap_or_else(Self::default)
+        Self::from_u16(buf.get_u16()).unwrap_or_else(Self::default)
+    }
+}
+
+impl FieldSerialize for RecordREventType {
+    fn serialize_field(&self, buf: &mut BytesMut) {
+        buf.put_u16(*self as u16);
+    }
+}
+
+impl FieldDeserialize for RecordREventType {
+    fn deserialize_field<B: Buf>(buf: &mut B) -> Self {
+        Self::deserialize(buf)
+    }
+}
+
+impl FieldLen for RecordREventType {
+    fn field_len(&self) -> usize {
+        2
     }
 }
 
@@ -11833,7 +12023,8 @@ impl DEFireFlagsStateUpdateFlag {
 }
 
 // SISO-REF-010-2023 ComponentVisualDamageStatusSmoke [UID 375]
-#[derive(Copy, Clone, Debug, Default, FromPrimitive, PartialEq, Eq)]
+#[derive(Specifier, Copy, Clone, Debug, Default, FromPrimitive, PartialEq, Eq)]
+#[bits = 2]
 pub enum ComponentVisualDamageStatusSmoke {
     #[default]
     NoSmoke = 0,
@@ -11850,7 +12041,8 @@ impl ComponentVisualDamageStatusSmoke {
 }
 
 // SISO-REF-010-2023 ComponentVisualDamageStatusSurfaceDamage [UID 376]
-#[derive(Copy, Clone, Debug, Default, FromPrimitive, PartialEq, Eq)]
+#[derive(Specifier, Copy, Clone, Debug, Default, FromPrimitive, PartialEq, Eq)]
+#[bits = 2]
 pub enum ComponentVisualDamageStatusSurfaceDamage {
     #[default]
     NormalAppearance = 0,
@@ -0,0 +1,207 @@
+//     open-dis-rust - Rust implementation of the IEEE 1278.1-2012 Distributed Interactive
+//                     Simulation (DIS) application protocol
+//     Copyright (C) 2025 Cameron Howell
+//
+//     Licensed under the BSD 2-Clause License
+
+//! Allocation of entity and event identifiers within one simulation, IEEE 1278.1-2012 §6.2.28
+//! and §6.2.33
+//!
+//! An [`EntityIdAllocator`] hands out the entity numbers of a [`SimulationAddress`], never the
+//! reserved `NO_ENTITY`, `ALL_ENTITIES` or `RQST_ASSIGN_ID` values, and holds a released number
+//! back for a reuse delay so other simulations have timed the old entity out before the number
+//! returns. An [`EventIdGenerator`] numbers the events of Fire, Detonation, Collision and
+//! Directed Energy Fire PDUs. Neither does I/O of its own; time is passed in by the caller.
+
+use std::{
+    collections::{HashSet, VecDeque},
+    time::{Duration, Instant},
+};
+
+use crate::common::{
+    constants::{ALL_ENTITIES, NO_ENTITY, RQST_ASSIGN_ID},
+    data_types::{entity_id::EntityId, event_id::EventId, simulation_address::SimulationAddress},
+};
+
+/// Default time a released entity number is held back before it is handed out again
+pub const DEFAULT_ENTITY_ID_REUSE_DELAY: Duration = Duration::from_mins(1);
+
+/// Whether an entity number is reserved and never identifies an entity
+#[must_use]
+pub const fn is_reserved_entity_number(entity_number: u16) -> bool {
+    matches!(entity_number, NO_ENTITY | ALL_ENTITIES | RQST_ASSIGN_ID)
+}
+
+/// Hands out unique entity IDs within one simulation
+#[derive(Clone, Debug)]
+pub struct EntityIdAllocator {
+    address: SimulationAddress,
+    reuse_delay: Duration,
+    /// Lowest entity number never handed out; a reserved number once all have been
+    next_unused: u32,
+    in_use: HashSet<u16>,
+    /// Released numbers in the order they were released
+    released: VecDeque<(u16, Instant)>,
+}
+
+impl EntityIdAllocator {
+    #[must_use]
+    pub fn new(address: SimulationAddress) -> Self {
+        Self {
+            address,
+            reuse_delay: DEFAULT_ENTITY_ID_REUSE_DELAY,
+            next_unused: 1,
+            in_use: HashSet::new(),
+            released: VecDeque::new(),
+        }
+    }
+
+    #[must_use]
+    pub const fn with_reuse_delay(mut self, reuse_delay: Duration) -> Self {
+        self.reuse_delay = reuse_delay;
+        self
+    }
+
+    #[must_use]
+    pub const fn address(&self) -> SimulationAddress {
+        self.address
+    }
+
+    #[must_use]
+    pub const fn reuse_delay(&self) -> Duration {
+        self.reuse_delay
+    }
+
+    /// Number of entity IDs handed out and not released
+    #[must_use]
+    pub fn in_use_len(&self) -> usize {
+        self.in_use.len()
+    }
+
+    #[must_use]
+    pub fn is_in_use(&self, id: EntityId) -> bool {
+        id.simulation_address == self.address && self.in_use.contains(&id.entity_id)
+    }
+
+    /// Hands out an entity ID
+    ///
+    /// Numbers never handed out before come first, then released numbers whose reuse delay
+    /// has passed, oldest first. Returns `None` if every number is in use or still held back.
+    pub fn allocate(&mut self, now: Instant) -> Option<EntityId> {
+        let entity_number = if let Ok(number) = u16::try_from(self.next_unused)
+            && !is_reserved_entity_number(number)
+        {
+            self.next_unused += 1;
+            number
+        } else {
+            let (number, released_at) = *self.released.front()?;
+            if now.saturating_duration_since(released_at) < self.reuse_delay {
+                return None;
+            }
+            self.released.pop_front();
+            number
+        };
+        self.in_use.insert(entity_number);
+        Some(EntityId {
+            simulation_address: self.address,
+            entity_id: entity_number,
+        })
+    }
+
+    /// Returns an entity ID for reuse once the reuse delay has passed; returns whether it was
+    /// in use
+    pub fn release(&mut self, id: EntityId, now: Instant) -> bool {
+        if !self.is_in_use(id) {
+            return false;
+        }
+        self.in_use.remove(&id.entity_id);
+        self.released.push_back((id.entity_id, now));
+        true
+    }
+}
+
+/// Numbers the events of one simulation
+///
+/// Event numbers increase by one with each event and wrap from 65535 back to 1.
+#[derive(Clone, Debug)]
+pub struct EventIdGenerator {
+    address: SimulationAddress,
+    next_event_number: u16,
+}
+
+impl EventIdGenerator {
+    #[must_use]
+    pub const fn new(address: SimulationAddress) -> Self {
+        Self {
+            address,
+            next_event_number: 1,
+        }
+    }
+
+    #[must_use]
+    pub const fn address(&self) -> SimulationAddress {
+        self.address
+    }
+
+    /// The ID of the next event
+    pub const fn next_event_id(&mut self) -> EventId {
+        let event_identifier = self.next_event_number;
+        self.next_event_number = match self.next_event_number.wrapping_add(1) {
+            0 => 1,
+            next => next,
+        };
+        EventId {
+            simulation_address: self.address,
+            event_identifier,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn released_entity_ids_wait_out_the_reuse_delay() {
+        let now = Instant::now();
+        let delay = Duration::from_secs(30);
+        let mut allocator =
+            EntityIdAllocator::new(SimulationAddress::new(2, 5)).with_reuse_delay(delay);
+        allocator.next_unused = u32::from(RQST_ASSIGN_ID) - 2;
+
+        let first = allocator.allocate(now).expect("numbers left");
+        let last = allocator.allocate(now).expect("numbers left");
+        assert_eq!(first, EntityId::new(2, 5, 0xFFFC));
+        assert_eq!(last, EntityId::new(2, 5, 0xFFFD));
+        assert!(allocator.allocate(now).is_none());
+
+        assert!(allocator.release(first, now));
+        assert!(!allocator.release(first, now));
+        assert!(!allocator.is_in_use(first));
+        assert!(allocator.allocate(now + delay / 2).is_none());
+        assert_eq!(allocator.allocate(now + delay), Some(first));
+        assert_eq!(allocator.in_use_len(), 2);
+    }
+
+    #[test]
+    fn entity_ids_skip_reserved_numbers() {
+        let mut allocator = EntityIdAllocator::new(SimulationAddress::new(2, 5));
+        let id = allocator.allocate(Instant::now()).expect("numbers left");
+        assert_eq!(id.entity_id, 1);
+        assert!(is_reserved_entity_number(NO_ENTITY));
+        assert!(is_reserved_entity_number(ALL_ENTITIES));
+        assert!(is_reserved_entity_number(RQST_ASSIGN_ID));
+        assert!(!allocator.release(EntityId::new(2, 6, 1), Instant::now()));
+    }
+
+    #[test]
+    fn event_ids_increase_and_wrap_past_zero() {
+        let mut events = EventIdGenerator::new(SimulationAddress::new(2, 5));
+        assert_eq!(events.next_event_id(), EventId::new(2, 5, 1));
+        assert_eq!(events.next_event_id().event_identifier, 2);
+
+        events.next_event_number = u16::MAX;
+        assert_eq!(events.next_event_id().event_identifier, u16::MAX);
+        assert_eq!(events.next_event_id().event_identifier, 1);
+    }
+}
@@ -6,16 +6,19 @@
 
 //! All commonly used non-PDU data types
 
+pub mod addressing;
 pub mod constants;
 pub mod data_types;
 pub mod dis_error;
 pub mod enums;
 pub mod generic_header;
+pub mod id_allocator;
 pub mod live_entity_pdu_header;
 pub mod pdu;
 pub mod pdu_body;
 pub mod pdu_header;
 pub mod serialized_length;
+pub(crate) mod timestamp;
 
 pub use dis_error::DISError;
 pub use generic_header::GenericHeader;
@@ -0,0 +1,96 @@
+//     open-dis-rust - Rust implementation of the IEEE 1278.1-2012 Distributed Interactive
+//                     Simulation (DIS) application protocol
+//     Copyright (C) 2025 Cameron Howell
+//
+//     Licensed under the BSD 2-Clause License
+
+//! DIS timestamps, implemented according to IEEE 1278.1-2012 §6.2.88
+//!
+//! The upper 31 bits of a timestamp count units of 3600 s / 2^31 past the hour and the least
+//! significant bit is set for absolute time. Arithmetic on timestamps wraps at the hour and
+//! keeps the absolute bit.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Number of timestamp units in an hour
+pub const UNITS_PER_HOUR: u32 = 1 << 31;
+
+const NANOS_PER_HOUR: u128 = 3_600_000_000_000;
+
+/// The timestamp `units` past the hour
+pub const fn from_units(units: u32, absolute: bool) -> u32 {
+    (units % UNITS_PER_HOUR) << 1 | absolute as u32
+}
+
+/// The units past the hour of `timestamp`
+pub const fn units(timestamp: u32) -> u32 {
+    timestamp >> 1
+}
+
+/// Whether `timestamp` is absolute, i.e. synchronized with UTC
+pub const fn is_absolute(timestamp: u32) -> bool {
+    timestamp & 1 == 1
+}
+
+/// Number of timestamp units in `duration`, rounded to the nearest unit
+pub fn units_in(duration: Duration) -> u64 {
+    let units =
+        (duration.as_nanos() * u128::from(UNITS_PER_HOUR) + NANOS_PER_HOUR / 2) / NANOS_PER_HOUR;
+    u64::try_from(units).unwrap_or(u64::MAX)
+}
+
+/// Number of timestamp units spanned by `samples` samples at `sample_rate` samples per second
+pub fn units_in_samples(samples: usize, sample_rate: u32) -> u64 {
+    let hour_samples = 3_600 * u128::from(sample_rate.max(1));
+    let units = (samples as u128 * u128::from(UNITS_PER_HOUR) + hour_samples / 2) / hour_samples;
+    u64::try_from(units).unwrap_or(u64::MAX)
+}
+
+/// The timestamp `units` after `timestamp`
+#[allow(clippy::cast_possible_truncation)]
+pub const fn advance(timestamp: u32, units: u64) -> u32 {
+    let past_hour =
+        (self::units(timestamp) as u64 + units % UNITS_PER_HOUR as u64) % UNITS_PER_HOUR as u64;
+    from_units(past_hour as u32, is_absolute(timestamp))
+}
+
+/// Number of units from `earlier` forward to `later`
+pub const fn units_between(earlier: u32, later: u32) -> u32 {
+    units(later).wrapping_sub(units(earlier)) % UNITS_PER_HOUR
+}
+
+/// Whether `a` comes before `b`, taking the shorter way round the hour
+pub const fn precedes(a: u32, b: u32) -> bool {
+    let ahead = units_between(a, b);
+    ahead != 0 && ahead < UNITS_PER_HOUR / 2
+}
+
+/// A relative timestamp of the time past the hour on the system clock
+pub fn relative_now() -> u32 {
+    let elapsed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    advance(from_units(0, false), units_in(elapsed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic_wraps_at_the_hour_and_keeps_the_absolute_bit() {
+        let before_wrap = from_units(UNITS_PER_HOUR - 10, true);
+        assert_eq!(before_wrap, u32::MAX - 18);
+        let after_wrap = advance(before_wrap, 25);
+        assert_eq!(after_wrap, from_units(15, true));
+        assert!(is_absolute(after_wrap));
+        assert_eq!(units_between(before_wrap, after_wrap), 25);
+        assert!(precedes(before_wrap, after_wrap));
+        assert!(!precedes(after_wrap, before_wrap));
+        assert!(!precedes(after_wrap, after_wrap));
+
+        assert_eq!(units_in(Duration::from_hours(1)), u64::from(UNITS_PER_HOUR));
+        assert_eq!(units_in_samples(8_000 * 1_800, 8_000), 1 << 30);
+        assert!(!is_absolute(relative_now()));
+    }
+}
@@ -6,6 +6,8 @@
 
 //! The Entity Management protocol family
 
+pub mod ownership;
+
 use crate::{
     common::{
         GenericHeader, SerializedLength,
@@ -80,7 +82,7 @@ define_pdu! {
 }
 
 define_pdu! {
-    #[derive(Debug)]
+    #[derive(Clone, Debug)]
     /// Implemented according to IEEE 1278.1-2012 §7.8.4
     pub struct TransferOwnershipPdu {
         header: PduHeader,
@@ -0,0 +1,863 @@
+//     open-dis-rust - Rust implementation of the IEEE 1278.1-2012 Distributed Interactive
+//                     Simulation (DIS) application protocol
+//     Copyright (C) 2025 Cameron Howell
+//
+//     Licensed under the BSD 2-Clause License
+
+//! Transfer of entity ownership between simulations over Transfer Ownership PDUs,
+//! IEEE 1278.1-2012 §5.9.4
+//!
+//! An [`OwnershipRegistry`] records the entities a simulation owns and therefore publishes. A
+//! [`TransferControl`] moves entities in and out of it:
+//!
+//! - In a push, the owner sends a Transfer Ownership PDU carrying the entity's records. The
+//!   receiver takes the entity over if its [`TransferHandler`] accepts and acknowledges; the
+//!   owner gives the entity up once the acknowledgement says it is able to comply.
+//! - In a pull, the requester sends a Transfer Ownership PDU to the owner. An owner that lets
+//!   the entity go answers with a Transfer Ownership PDU carrying the records, and otherwise
+//!   with an acknowledgement refusing. The requester takes the entity over on receiving the
+//!   records and acknowledges, upon which the owner gives it up.
+//!
+//! The new owner takes the entity over before the old owner learns of it, so a lost final
+//! acknowledgement would leave both owning it. With the acknowledged reliability service, the
+//! default, a step that goes unanswered is therefore sent again, and a simulation answers a
+//! retransmitted step with the reply it already sent instead of acting on it twice. A transfer
+//! is given up after the retry limit, or at the first timeout with the unacknowledged service,
+//! and the side that gives up keeps what it owned. The initiator may cancel a transfer in
+//! progress. Environmental process transfers are not handled. The control does no I/O of its
+//! own; time is passed in by the caller.
+
+use std::{
+    collections::{HashMap, HashSet},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    common::{
+        constants::NO_ENTITY,
+        data_types::{
+            SimulationIdentifier, entity_id::EntityId, record_specification::RecordSpecification,
+            record_specification_element::RecordSpecificationElement,
+        },
+        dis_error::DISError,
+        enums::{
+            AcknowledgeFlag, AcknowledgeResponseFlag, RequiredReliabilityService,
+            TransferControlTransferType,
+        },
+    },
+    entity_management::TransferOwnershipPdu,
+    simulation_management::AcknowledgePdu,
+};
+
+/// Default time to wait for each step of a transfer
+pub const DEFAULT_TRANSFER_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Default number of times an unanswered step is sent again before the transfer is given up
+pub const DEFAULT_TRANSFER_RETRIES: u32 = 3;
+
+/// The entities a simulation owns and publishes
+#[derive(Clone, Debug, Default)]
+pub struct OwnershipRegistry {
+    owned: HashSet<EntityId>,
+}
+
+impl OwnershipRegistry {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records that the simulation owns `entity`; returns whether it did not already
+    pub fn claim(&mut self, entity: EntityId) -> bool {
+        self.owned.insert(entity)
+    }
+
+    /// Records that the simulation no longer owns `entity`; returns whether it did
+    pub fn release(&mut self, entity: EntityId) -> bool {
+        self.owned.remove(&entity)
+    }
+
+    #[must_use]
+    pub fn owns(&self, entity: EntityId) -> bool {
+        self.owned.contains(&entity)
+    }
+
+    pub fn owned(&self) -> impl Iterator<Item = EntityId> + '_ {
+        self.owned.iter().copied()
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.owned.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.owned.is_empty()
+    }
+}
+
+/// The application's side of entity transfers
+///
+/// Unless overridden, pushed entities and automatic pulls are accepted, manual pulls are
+/// refused, and no records are exchanged.
+pub trait TransferHandler {
+    /// Whether to take over the entity pushed by a Transfer Ownership PDU
+    fn accept_push(&mut self, pdu: &TransferOwnershipPdu) -> AcknowledgeResponseFlag {
+        let _ = pdu;
+        AcknowledgeResponseFlag::AbleToComply
+    }
+
+    /// Whether to give up the entity a Transfer Ownership PDU pulls
+    fn release_pull(&mut self, pdu: &TransferOwnershipPdu) -> AcknowledgeResponseFlag {
+        if pdu.transfer_type == TransferControlTransferType::AutomaticPullTransferEntity {
+            AcknowledgeResponseFlag::AbleToComply
+        } else {
+            AcknowledgeResponseFlag::UnableToComply
+        }
+    }
+
+    /// The records another simulation needs to take over `entity`
+    fn entity_records(&mut self, entity: EntityId) -> Vec<RecordSpecificationElement> {
+        let _ = entity;
+        Vec::new()
+    }
+
+    /// Called once the simulation has taken over `entity`, with the records handed over
+    fn acquired(&mut self, entity: EntityId, records: &[RecordSpecificationElement]) {
+        let _ = (entity, records);
+    }
+}
+
+/// What to send back in answer to a Transfer Ownership PDU
+#[derive(Clone, Debug)]
+pub enum TransferReply {
+    Acknowledge(AcknowledgePdu),
+    /// The records handing an entity over in answer to a pull
+    Records(TransferOwnershipPdu),
+}
+
+/// How a transfer ended for the simulation that initiated it or gave the entity up
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TransferResult {
+    /// The other simulation now owns the entity
+    Released,
+    /// The other simulation refused with the given response
+    Refused(AcknowledgeResponseFlag),
+    /// The next step did not arrive in time, however often it was asked for; ownership is
+    /// unchanged
+    TimedOut,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TransferOutcome {
+    pub request_id: u32,
+    pub entity: EntityId,
+    pub peer: SimulationIdentifier,
+    pub result: TransferResult,
+}
+
+/// What the control did with a transfer step whose timeout expired
+#[derive(Clone, Debug)]
+pub enum TransferRetry {
+    /// The step is due to be sent again
+    Retransmit(TransferOwnershipPdu),
+    /// The transfer was given up; it is no longer tracked
+    GaveUp(TransferOutcome),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Stage {
+    /// We pushed the entity and wait for the receiver to accept
+    Pushed,
+    /// We pulled the entity and wait for the owner's records
+    Pulled,
+    /// We sent the records for a pull and wait for the requester to confirm
+    RecordsSent,
+}
+
+#[derive(Debug)]
+struct PendingTransfer {
+    initiator: SimulationIdentifier,
+    request_id: u32,
+    peer: SimulationIdentifier,
+    entity: EntityId,
+    stage: Stage,
+    /// The PDU of this step, sent again while it goes unanswered
+    pdu: TransferOwnershipPdu,
+    retries: u32,
+    deadline: Instant,
+}
+
+impl PendingTransfer {
+    const fn outcome(&self, result: TransferResult) -> TransferOutcome {
+        TransferOutcome {
+            request_id: self.request_id,
+            entity: self.entity,
+            peer: self.peer,
+            result,
+        }
+    }
+}
+
+/// The reply sent to a step of a transfer, kept to answer its retransmissions
+#[derive(Debug)]
+struct Answered {
+    peer: SimulationIdentifier,
+    transfer_type: TransferControlTransferType,
+    reply: TransferReply,
+    expires: Instant,
+}
+
+const fn simulation_entity_id(id: SimulationIdentifier) -> EntityId {
+    EntityId::new(
+        id.simulation_address.site_id,
+        id.simulation_address.application_id,
+        NO_ENTITY,
+    )
+}
+
+const fn is_pull(transfer_type: TransferControlTransferType) -> bool {
+    matches!(
+        transfer_type,
+        TransferControlTransferType::AutomaticPullTransferEntity
+            | TransferControlTransferType::ManualPullTransferEntity
+    )
+}
+
+/// Pushes and pulls entities to and from other simulations, keeping an
+/// [`OwnershipRegistry`] up to date
+#[derive(Debug)]
+pub struct TransferControl<H> {
+    id: SimulationIdentifier,
+    handler: H,
+    registry: OwnershipRegistry,
+    timeout: Duration,
+    max_retries: u32,
+    reliability: RequiredReliabilityService,
+    next_request_id: u32,
+    pending: Vec<PendingTransfer>,
+    /// Replies by initiating simulation and request ID
+    answered: HashMap<(SimulationIdentifier, u32), Answered>,
+}
+
+impl<H: TransferHandler> TransferControl<H> {
+    #[must_use]
+    pub fn new(id: SimulationIdentifier, handler: H) -> Self {
+        Self {
+            id,
+            handler,
+            registry: OwnershipRegistry::new(),
+            timeout: DEFAULT_TRANSFER_TIMEOUT,
+            max_retries: DEFAULT_TRANSFER_RETRIES,
+            reliability: RequiredReliabilityService::Acknowledged,
+            next_request_id: 1,
+            pending: Vec::new(),
+            answered: HashMap::new(),
+        }
+    }
+
+    #[must_use]
+    pub const fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// The reliability service requested by the transfers this simulation initiates
+    #[must_use]
+    pub const fn with_reliability(mut self, reliability: RequiredReliabilityService) -> Self {
+        self.reliability = reliability;
+        self
+    }
+
+    #[must_use]
+    pub const fn id(&self) -> SimulationIdentifier {
+        self.id
+    }
+
+    #[must_use]
+    pub const fn handler(&self) -> &H {
+        &self.handler
+    }
+
+    pub const fn handler_mut(&mut self) -> &mut H {
+        &mut self.handler
+    }
+
+    #[must_use]
+    pub const fn registry(&self) -> &OwnershipRegistry {
+        &self.registry
+    }
+
+    /// The registry, for recording the entities the simulation creates and removes itself
+    pub const fn registry_mut(&mut self) -> &mut OwnershipRegistry {
+        &mut self.registry
+    }
+
+    /// Number of transfers in progress
+    #[must_use]
+    pub const fn pending_len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Whether an entity is being transferred to or from this simulation
+    #[must_use]
+    pub fn is_transferring(&self, entity: EntityId) -> bool {
+        self.pending.iter().any(|pending| pending.entity == entity)
+    }
+
+    /// Earliest time a transfer step times out
+    #[must_use]
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.pending.iter().map(|pending| pending.deadline).min()
+    }
+
+    /// Builds the Transfer Ownership PDU pushing an owned entity to the simulation `to`
+    ///
+    /// # Errors
+    /// Returns `DISError::InvalidFieldValue` if the simulation does not own the entity or is
+    /// already transferring it.
+    pub fn push(
+        &mut self,
+        entity: EntityId,
+        to: SimulationIdentifier,
+        now: Instant,
+    ) -> Result<TransferOwnershipPdu, DISError> {
+        if !self.registry.owns(entity) {
+            return Err(transfer_error(entity, "not owned by this simulation"));
+        }
+        let records = self.handler.entity_records(entity);
+        self.initiate(
+            entity,
+            to,
+            TransferControlTransferType::PushTransferEntity,
+            records,
+            Stage::Pushed,
+            now,
+        )
+    }
+
+    /// Builds the Transfer Ownership PDU asking the simulation `from` for one of its entities
+    ///
+    /// A manual pull leaves the decision to the owner's operator.
+    ///
+    /// # Errors
+    /// Returns `DISError::InvalidFieldValue` if the simulation already owns the entity or is
+    /// already transferring it.
+    pub fn pull(
+        &mut self,
+        entity: EntityId,
+        from: SimulationIdentifier,
+        manual: bool,
+        now: Instant,
+    ) -> Result<TransferOwnershipPdu, DISError> {
+        if self.registry.owns(entity) {
+            return Err(transfer_error(entity, "already owned by this simulation"));
+        }
+        let transfer_type = if manual {
+            TransferControlTransferType::ManualPullTransferEntity
+        } else {
+            TransferControlTransferType::AutomaticPullTransferEntity
+        };
+        self.initiate(entity, from, transfer_type, Vec::new(), Stage::Pulled, now)
+    }
+
+    /// Abandons a transfer this simulation initiated and builds the Transfer Ownership PDU
+    /// that tells the other simulation; ownership is unchanged
+    pub fn cancel(&mut self, request_id: u32) -> Option<TransferOwnershipPdu> {
+        let index = self
+            .pending
+            .iter()
+            .position(|pending| pending.initiator == self.id && pending.request_id == request_id)?;
+        let pending = self.pending.remove(index);
+        Some(self.transfer_pdu(
+            pending.peer,
+            request_id,
+            TransferControlTransferType::CancelTransfer,
+            pending.entity,
+            Vec::new(),
+        ))
+    }
+
+    /// Handles a Transfer Ownership PDU and returns the answer to send back, if any
+    ///
+    /// A retransmitted PDU is answered with the reply already sent. PDUs not addressed to this
+    /// simulation, and transfer types other than entity push, pull and cancel, are ignored.
+    pub fn handle_transfer_ownership(
+        &mut self,
+        pdu: &TransferOwnershipPdu,
+        now: Instant,
+    ) -> Option<TransferReply> {
+        if !pdu.receiving_id.addresses(self.id) {
+            return None;
+        }
+        match pdu.transfer_type {
+            TransferControlTransferType::CancelTransfer => {
+                self.pending.retain(|pending| {
+                    (pending.initiator, pending.request_id) != (pdu.originating_id, pdu.request_id)
+                });
+                None
+            }
+            TransferControlTransferType::PushTransferEntity => self
+                .replay(pdu)
+                .or_else(|| Some(self.receive_push(pdu, now))),
+            transfer_type if is_pull(transfer_type) => {
+                if let Some(index) = self.pending.iter().position(|pending| {
+                    pending.stage == Stage::Pulled
+                        && pending.request_id == pdu.request_id
+                        && pending.peer == pdu.originating_id
+                        && pending.entity == pdu.transfer_entity_id
+                }) {
+                    self.pending.remove(index);
+                    return Some(self.take_over(pdu, now));
+                }
+                self.replay(pdu).or_else(|| self.receive_pull(pdu, now))
+            }
+            _ => None,
+        }
+    }
+
+    /// Handles an Acknowledge PDU answering a push, refusing a pull, or confirming that a
+    /// pulled entity was taken over; returns how the transfer ended
+    pub fn handle_acknowledge(&mut self, ack: &AcknowledgePdu) -> Option<TransferOutcome> {
+        if ack.acknowledge_flag != AcknowledgeFlag::TransferOwnership
+            || !ack
+                .receiving_entity_id
+                .addresses_simulation(self.id.simulation_address)
+        {
+            return None;
+        }
+        let sender = ack.originating_entity_id.simulation_address;
+        let index = self.pending.iter().position(|pending| {
+            pending.request_id == ack.request_id && pending.peer.simulation_address == sender
+        })?;
+        let pending = &self.pending[index];
+        let able = ack.response_flag == AcknowledgeResponseFlag::AbleToComply;
+        let result = match pending.stage {
+            Stage::Pushed | Stage::RecordsSent if able => {
+                self.registry.release(pending.entity);
+                TransferResult::Released
+            }
+            // A pull is only accepted with the records, never with an acknowledgement
+            Stage::Pulled if able => return None,
+            _ => TransferResult::Refused(ack.response_flag),
+        };
+        Some(self.pending.remove(index).outcome(result))
+    }
+
+    /// Retransmits or gives up the transfer steps whose timeout has expired at `now`, and
+    /// forgets replies no retransmission can ask for any more
+    pub fn poll(&mut self, now: Instant) -> Vec<TransferRetry> {
+        self.answered.retain(|_, answered| answered.expires > now);
+
+        let (timeout, max_retries) = (self.timeout, self.max_retries);
+        let mut retries = Vec::new();
+        self.pending.retain_mut(|pending| {
+            if pending.deadline > now {
+                return true;
+            }
+            let acknowledged = pending.pdu.required_reliability_service
+                == RequiredReliabilityService::Acknowledged;
+            if acknowledged && pending.retries < max_retries {
+                pending.retries += 1;
+                pending.deadline = now + timeout;
+                retries.push(TransferRetry::Retransmit(pending.pdu.clone()));
+                true
+            } else {
+                retries.push(TransferRetry::GaveUp(
+                    pending.outcome(TransferResult::TimedOut),
+                ));
+                false
+            }
+        });
+        retries
+    }
+
+    fn initiate(
+        &mut self,
+        entity: EntityId,
+        peer: SimulationIdentifier,
+        transfer_type: TransferControlTransferType,
+        records: Vec<RecordSpecificationElement>,
+        stage: Stage,
+        now: Instant,
+    ) -> Result<TransferOwnershipPdu, DISError> {
+        if self.is_transferring(entity) {
+            return Err(transfer_error(entity, "already being transferred"));
+        }
+        let request_id = self.next_request_id;
+        self.next_request_id = self.next_request_id.wrapping_add(1).max(1);
+        let mut pdu = self.transfer_pdu(peer, request_id, transfer_type, entity, records);
+        pdu.required_reliability_service = self.reliability;
+        self.pending.push(PendingTransfer {
+            initiator: self.id,
+            request_id,
+            peer,
+            entity,
+            stage,
+            pdu: pdu.clone(),
+            retries: 0,
+            deadline: now + self.timeout,
+        });
+        Ok(pdu)
+    }
+
+    /// The reply already sent to a retransmitted push or pull, or to the records of a pull
+    /// this simulation initiated
+    fn replay(&self, pdu: &TransferOwnershipPdu) -> Option<TransferReply> {
+        let sender = pdu.originating_id;
+        [(sender, pdu.request_id), (self.id, pdu.request_id)]
+            .iter()
+            .filter_map(|key| self.answered.get(key))
+            .find(|answered| answered.peer == sender && answered.transfer_type == pdu.transfer_type)
+            .map(|answered| answered.reply.clone())
+    }
+
+    /// Keeps the reply to a step of the transfer `(initiator, request_id)` for its
+    /// retransmissions, and returns it
+    fn answer(
+        &mut self,
+        initiator: SimulationIdentifier,
+        pdu: &TransferOwnershipPdu,
+        reply: TransferReply,
+        now: Instant,
+    ) -> TransferReply {
+        let window = self
+            .timeout
+            .saturating_mul(self.max_retries.saturating_add(1));
+        self.answered.insert(
+            (initiator, pdu.request_id),
+            Answered {
+                peer: pdu.originating_id,
+                transfer_type: pdu.transfer_type,
+                reply: reply.clone(),
+                expires: now + window,
+            },
+        );
+        reply
+    }
+
+    fn receive_push(&mut self, pdu: &TransferOwnershipPdu, now: Instant) -> TransferReply {
+        let response_flag = if self.is_transferring(pdu.transfer_entity_id) {
+            AcknowledgeResponseFlag::UnableToComply
+        } else {
+            self.handler.accept_push(pdu)
+        };
+        if response_flag == AcknowledgeResponseFlag::AbleToComply {
+            self.registry.claim(pdu.transfer_entity_id);
+            self.handler
+                .acquired(pdu.transfer_entity_id, &pdu.record_information.record_sets);
+        }
+        let reply = TransferReply::Acknowledge(self.acknowledge(pdu, response_flag));
+        self.answer(pdu.originating_id, pdu, reply, now)
+    }
+
+    fn receive_pull(&mut self, pdu: &TransferOwnershipPdu, now: Instant) -> Option<TransferReply> {
+        let entity = pdu.transfer_entity_id;
+        if !self.registry.owns(entity) {
+            return None;
+        }
+        let response_flag = if self.is_transferring(entity) {
+            AcknowledgeResponseFlag::UnableToComply
+        } else {
+            self.handler.release_pull(pdu)
+        };
+        if response_flag != AcknowledgeResponseFlag::AbleToComply {
+            let reply = TransferReply::Acknowledge(self.acknowledge(pdu, response_flag));
+            return Some(self.answer(pdu.originating_id, pdu, reply, now));
+        }
+
+        // Ownership moves once the requester confirms it has taken the entity over
+        let records = self.handler.entity_records(entity);
+        let mut records = self.transfer_pdu(
+            pdu.originating_id,
+            pdu.request_id,
+            pdu.transfer_type,
+            entity,
+            records,
+        );
+        records.required_reliability_service = pdu.required_reliability_service;
+        self.pending.push(PendingTransfer {
+            initiator: pdu.originating_id,
+            request_id: pdu.request_id,
+            peer: pdu.originating_id,
+            entity,
+            stage: Stage::RecordsSent,
+            pdu: records.clone(),
+            retries: 0,
+            deadline: now + self.timeout,
+        });
+        Some(self.answer(
+            pdu.originating_id,
+            pdu,
+            TransferReply::Records(records),
+            now,
+        ))
+    }
+
+    fn take_over(&mut self, pdu: &TransferOwnershipPdu, now: Instant) -> TransferReply {
+        self.registry.claim(pdu.transfer_entity_id);
+        self.handler
+            .acquired(pdu.transfer_entity_id, &pdu.record_information.record_sets);
+        let reply = TransferReply::Acknowledge(
+            self.acknowledge(pdu, AcknowledgeResponseFlag::AbleToComply),
+        );
+        self.answer(self.id, pdu, reply, now)
+    }
+
+    fn acknowledge(
+        &self,
+        pdu: &TransferOwnershipPdu,
+        response_flag: AcknowledgeResponseFlag,
+    ) -> AcknowledgePdu {
+        let mut ack = AcknowledgePdu::new();
+        ack.originating_entity_id = simulation_entity_id(self.id);
+        ack.receiving_entity_id = simulation_entity_id(pdu.originating_id);
+        ack.acknowledge_flag = AcknowledgeFlag::TransferOwnership;
+        ack.response_flag = response_flag;
+        ack.request_id = pdu.request_id;
+        ack
+    }
+
+    fn transfer_pdu(
+        &self,
+        receiving_id: SimulationIdentifier,
+        request_id: u32,
+        transfer_type: TransferControlTransferType,
+        entity: EntityId,
+        records: Vec<RecordSpecificationElement>,
+    ) -> TransferOwnershipPdu {
+        let mut pdu = TransferOwnershipPdu::new();
+        pdu.originating_id = self.id;
+        pdu.receiving_id = receiving_id;
+        pdu.request_id = request_id;
+        pdu.transfer_type = transfer_type;
+        pdu.transfer_entity_id = entity;
+        pdu.record_information = RecordSpecification::from_record_sets(records);
+        pdu
+    }
+}
+
+fn transfer_error(entity: EntityId, reason: &str) -> DISError {
+    DISError::invalid_field(
+        "transfer_entity_id".to_string(),
+        format!("{entity:?}"),
+        reason.to_string(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{
+        data_types::{simulation_address::SimulationAddress, world_coordinate::WorldCoordinate},
+        enums::VariableRecordTypes,
+    };
+
+    const TANK: EntityId = EntityId::new(1, 1, 7);
+    const TANK_LOCATION: WorldCoordinate = WorldCoordinate::new(4_000_000.0, 500.0, -20.5);
+
+    #[derive(Default)]
+    struct Recorder {
+        acquired: Vec<(EntityId, Option<WorldCoordinate>)>,
+    }
+
+    impl TransferHandler for Recorder {
+        fn entity_records(&mut self, _entity: EntityId) -> Vec<RecordSpecificationElement> {
+            vec![
+                RecordSpecificationElement::from_value(
+                    VariableRecordTypes::EntityLocation,
+                    1,
+                    &TANK_LOCATION,
+                )
+                .expect("fits a record set"),
+            ]
+        }
+
+        fn acquired(&mut self, entity: EntityId, records: &[RecordSpecificationElement]) {
+            let location = RecordSpecification::from_record_sets(records.to_vec())
+                .value(VariableRecordTypes::EntityLocation);
+            self.acquired.push((entity, location));
+        }
+    }
+
+    fn simulation(application_id: u16) -> SimulationIdentifier {
+        SimulationIdentifier {
+            simulation_address: SimulationAddress::new(1, application_id),
+            reference_number: 0,
+        }
+    }
+
+    fn controls() -> (TransferControl<Recorder>, TransferControl<Recorder>) {
+        let mut owner = TransferControl::new(simulation(1), Recorder::default());
+        owner.registry_mut().claim(TANK);
+        let other = TransferControl::new(simulation(2), Recorder::default());
+        (owner, other)
+    }
+
+    fn acknowledgement(reply: Option<TransferReply>) -> AcknowledgePdu {
+        match reply {
+            Some(TransferReply::Acknowledge(ack)) => ack,
+            other => panic!("expected an acknowledgement, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn push_hands_the_entity_over_once_acknowledged() {
+        let now = Instant::now();
+        let (mut owner, mut receiver) = controls();
+
+        let push = owner.push(TANK, receiver.id(), now).expect("owned");
+        assert_eq!(push.record_information.number_of_record_sets, 1);
+        assert!(owner.push(TANK, receiver.id(), now).is_err());
+
+        let ack = acknowledgement(receiver.handle_transfer_ownership(&push, now));
+        assert_eq!(ack.response_flag, AcknowledgeResponseFlag::AbleToComply);
+        assert!(receiver.registry().owns(TANK));
+        assert_eq!(
+            receiver.handler().acquired,
+            vec![(TANK, Some(TANK_LOCATION))]
+        );
+
+        let outcome = owner.handle_acknowledge(&ack).expect("answers the push");
+        assert_eq!(outcome.result, TransferResult::Released);
+        assert_eq!(outcome.peer, receiver.id());
+        assert!(!owner.registry().owns(TANK));
+        assert_eq!(owner.pending_len(), 0);
+    }
+
+    #[test]
+    fn pull_exchanges_records_before_ownership_moves() {
+        let now = Instant::now();
+        let (mut owner, mut requester) = controls();
+
+        let pull = requester
+            .pull(TANK, owner.id(), false, now)
+            .expect("not owned");
+        let Some(TransferReply::Records(records)) = owner.handle_transfer_ownership(&pull, now)
+        else {
+            panic!("the owner answers an automatic pull with records");
+        };
+        assert_eq!(records.request_id, pull.request_id);
+        assert!(owner.registry().owns(TANK));
+
+        let confirmation = acknowledgement(requester.handle_transfer_ownership(&records, now));
+        assert!(requester.registry().owns(TANK));
+        assert_eq!(
+            requester.handler().acquired,
+            vec![(TANK, Some(TANK_LOCATION))]
+        );
+        assert_eq!(requester.pending_len(), 0);
+
+        let outcome = owner
+            .handle_acknowledge(&confirmation)
+            .expect("confirms the pull");
+        assert_eq!(outcome.result, TransferResult::Released);
+        assert!(!owner.registry().owns(TANK));
+    }
+
+    #[test]
+    fn refused_and_unanswered_transfers_keep_ownership() {
+        let now = Instant::now();
+        let (mut owner, mut requester) = controls();
+
+        let pull = requester
+            .pull(TANK, owner.id(), true, now)
+            .expect("not owned");
+        let refusal = acknowledgement(owner.handle_transfer_ownership(&pull, now));
+        let outcome = requester
+            .handle_acknowledge(&refusal)
+            .expect("answers the pull");
+        assert_eq!(
+            outcome.result,
+            TransferResult::Refused(AcknowledgeResponseFlag::UnableToComply)
+        );
+        assert!(owner.registry().owns(TANK));
+
+        let push = owner.push(TANK, requester.id(), now).expect("owned");
+        assert!(owner.poll(now + Duration::from_secs(4)).is_empty());
+        let mut deadline = now;
+        for _ in 0..DEFAULT_TRANSFER_RETRIES {
+            deadline += DEFAULT_TRANSFER_TIMEOUT;
+            let retries = owner.poll(deadline);
+            assert!(
+                matches!(&retries[..], [TransferRetry::Retransmit(pdu)] if pdu.request_id == push.request_id)
+            );
+        }
+        let retries = owner.poll(deadline + DEFAULT_TRANSFER_TIMEOUT);
+        let [TransferRetry::GaveUp(outcome)] = &retries[..] else {
+            panic!("expected the push to be given up, got {retries:?}");
+        };
+        assert_eq!(outcome.request_id, push.request_id);
+        assert_eq!(outcome.result, TransferResult::TimedOut);
+        assert!(owner.registry().owns(TANK));
+
+        let mut owner = owner.with_reliability(RequiredReliabilityService::Unacknowledged);
+        owner.push(TANK, requester.id(), now).expect("owned");
+        assert!(matches!(
+            &owner.poll(now + DEFAULT_TRANSFER_TIMEOUT)[..],
+            [TransferRetry::GaveUp(_)]
+        ));
+
+        let push = owner.push(TANK, requester.id(), now).expect("owned");
+        let cancel = owner.cancel(push.request_id).expect("in progress");
+        assert_eq!(
+            cancel.transfer_type,
+            TransferControlTransferType::CancelTransfer
+        );
+        assert!(requester.handle_transfer_ownership(&cancel, now).is_none());
+        assert_eq!(owner.pending_len(), 0);
+    }
+
+    #[test]
+    fn lost_final_acknowledgements_are_replayed_to_retransmissions() {
+        let now = Instant::now();
+        let (mut owner, mut receiver) = controls();
+
+        let push = owner.push(TANK, receiver.id(), now).expect("owned");
+        let lost = acknowledgement(receiver.handle_transfer_ownership(&push, now));
+        let later = now + DEFAULT_TRANSFER_TIMEOUT;
+        let [TransferRetry::Retransmit(retransmitted)] = &owner.poll(later)[..] else {
+            panic!("the unanswered push is sent again");
+        };
+        let ack = acknowledgement(receiver.handle_transfer_ownership(retransmitted, later));
+        assert_eq!(ack.request_id, lost.request_id);
+        assert_eq!(receiver.handler().acquired.len(), 1);
+        assert_eq!(
+            owner.handle_acknowledge(&ack).map(|outcome| outcome.result),
+            Some(TransferResult::Released)
+        );
+        assert!(!owner.registry().owns(TANK) && receiver.registry().owns(TANK));
+
+        // Pull the entity back, losing the confirmation of the records
+        let (mut owner, mut requester) = (receiver, owner);
+        let pull = requester
+            .pull(TANK, owner.id(), false, now)
+            .expect("not owned");
+        let Some(TransferReply::Records(records)) = owner.handle_transfer_ownership(&pull, now)
+        else {
+            panic!("the owner answers an automatic pull with records");
+        };
+        acknowledgement(requester.handle_transfer_ownership(&records, now));
+        let [TransferRetry::Retransmit(retransmitted)] = &owner.poll(later)[..] else {
+            panic!("the unconfirmed records are sent again");
+        };
+        let confirmation =
+            acknowledgement(requester.handle_transfer_ownership(retransmitted, later));
+        assert_eq!(requester.handler().acquired.len(), 1);
+        assert_eq!(
+            owner
+                .handle_acknowledge(&confirmation)
+                .map(|outcome| outcome.result),
+            Some(TransferResult::Released)
+        );
+        assert!(!owner.registry().owns(TANK) && requester.registry().owns(TANK));
+        assert_eq!(owner.pending_len() + requester.pending_len(), 0);
+    }
+}
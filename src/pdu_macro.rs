@@ -10,9 +10,19 @@
 
 use bytes::{Buf, BufMut, BytesMut};
 
+use crate::common::dis_error::DISError;
+
 /// Serialize a single field into the buffer.
 pub trait FieldSerialize {
     fn serialize_field(&self, buf: &mut BytesMut);
+
+    /// Checks that the field can be written as it is; PDUs check every field before writing any.
+    ///
+    /// # Errors
+    /// Returns an error describing the first inconsistency found.
+    fn validate_field(&self) -> Result<(), DISError> {
+        Ok(())
+    }
 }
 
 /// Deserialize a single field from the buffer.
@@ -31,6 +41,50 @@ pub trait FieldDeserializeWithLen: Sized {
     fn deserialize_with_len<B: Buf>(buf: &mut B, len: usize) -> Self;
 }
 
+/// Trait for list types that can be deserialized given an externally-provided element count.
+/// Used by the macro when a field is annotated with `#[count = count_field_name]`.
+pub trait FieldDeserializeWithCount: Sized {
+    /// # Errors
+    /// Returns `DISError::BufferUnderflow` if the buffer runs out before `count` elements
+    fn deserialize_with_count<B: Buf>(buf: &mut B, count: usize) -> Result<Self, DISError>;
+}
+
+/// The fewest octets an element of a counted list occupies on the wire. A count read from the
+/// wire is only trusted while this many octets remain for each element.
+pub trait FieldMinLen {
+    const MIN_LEN: usize;
+}
+
+impl<T> FieldDeserializeWithCount for Vec<T>
+where
+    T: FieldDeserialize + FieldMinLen,
+{
+    fn deserialize_with_count<B: Buf>(buf: &mut B, count: usize) -> Result<Self, DISError> {
+        (0..count)
+            .map(|_| {
+                if buf.remaining() < T::MIN_LEN {
+                    return Err(DISError::buffer_underflow(T::MIN_LEN, buf.remaining()));
+                }
+                Ok(T::deserialize_field(buf))
+            })
+            .collect()
+    }
+}
+
+impl FieldDeserializeWithLen for Vec<u8> {
+    fn deserialize_with_len<B: Buf>(buf: &mut B, len: usize) -> Self {
+        let mut bytes = vec![0u8; len.min(buf.remaining())];
+        buf.copy_to_slice(&mut bytes);
+        bytes
+    }
+}
+
+/// Number of octets occupied on the wire by `bits` bits of data padded to a 32-bit boundary
+#[must_use]
+pub const fn padded_octets_for_bits(bits: usize) -> usize {
+    bits.div_ceil(32) * 4
+}
+
 // Blanket impl so `Option<T>` can be deserialized with an externally-provided length
 impl<T> FieldDeserializeWithLen for Option<T>
 where
@@ -53,25 +107,82 @@ where
 macro_rules! __pdu_prep_serialize_field {
     // When the field has a length attribute, set the length field before length calculation.
     ( len = $len_field:ident ; $self:ident, $field:ident, Option<$inner:ty> ) => {
-        $self.$len_field = $self.$field.as_ref().map_or(0u8, |v| {
-            <$inner as $crate::pdu_macro::FieldLen>::field_len(v) as u8
+        $self.$len_field = $self.$field.as_ref().map_or(0, |v| {
+            ::core::convert::TryFrom::try_from(<$inner as $crate::pdu_macro::FieldLen>::field_len(
+                v,
+            ))
+            .unwrap_or_default()
         });
     };
 
     ( len = $len_field:ident ; $self:ident, $field:ident, $t:ty ) => {
         // For non-option fields with length attribute, set the length from the inner value.
-        $self.$len_field = u8::try_from(<$t as $crate::pdu_macro::FieldLen>::field_len(
-            &$self.$field,
-        ))
+        $self.$len_field = ::core::convert::TryFrom::try_from(
+            <$t as $crate::pdu_macro::FieldLen>::field_len(&$self.$field),
+        )
         .unwrap_or_default();
     };
 
+    // When the field has a bit length attribute, set the bit length to the whole field unless
+    // it already ends within the field's last octet. The field itself is left untouched; its
+    // padding to a 32-bit boundary is written by `__pdu_serialize_field!`.
+    ( bits = $bits_field:ident ; $self:ident, $field:ident, $t:ty ) => {
+        let available_bits = $self.$field.len() * 8;
+        if usize::from($self.$bits_field).div_ceil(8) != $self.$field.len() {
+            $self.$bits_field =
+                ::core::convert::TryFrom::try_from(available_bits).map_err(|_| {
+                    $crate::common::dis_error::DISError::invalid_field(
+                        stringify!($bits_field).to_string(),
+                        available_bits.to_string(),
+                        "data longer than 65535 bits".to_string(),
+                    )
+                })?;
+        }
+    };
+
+    // When the field has a count attribute, set the count field to the number of elements.
+    ( count = $count_field:ident ; $self:ident, $field:ident, $t:ty ) => {
+        $self.$count_field =
+            ::core::convert::TryFrom::try_from($self.$field.len()).unwrap_or_default();
+    };
+
     // Default: no-op
     ( ; $self:ident, $field:ident, $t:ty ) => {
         // nothing to do
     };
 }
 
+#[macro_export]
+macro_rules! __pdu_field_len {
+    // Octets with a bit length attribute take the octets holding that many bits, padded to a
+    // 32-bit boundary
+    ( bits = $bits_field:ident ; $self:ident, $field:ident, $t:ty ) => {
+        $crate::pdu_macro::padded_octets_for_bits($self.$bits_field as usize)
+    };
+
+    // Default: plain FieldLen
+    ( $( $attr:ident = $attr_field:ident )? ; $self:ident, $field:ident, $t:ty ) => {
+        <$t as $crate::pdu_macro::FieldLen>::field_len(&$self.$field)
+    };
+}
+
+#[macro_export]
+macro_rules! __pdu_serialize_field {
+    // Octets with a bit length attribute -> write the octets holding that many bits, then zeros
+    // up to the next 32-bit boundary
+    ( bits = $bits_field:ident ; $self:ident, $field:ident, $t:ty, $buf:ident ) => {
+        let padded_len = $crate::pdu_macro::padded_octets_for_bits($self.$bits_field as usize);
+        let data_len = padded_len.min($self.$field.len());
+        bytes::BufMut::put_slice($buf, &$self.$field[..data_len]);
+        bytes::BufMut::put_bytes($buf, 0, padded_len - data_len);
+    };
+
+    // Default: plain FieldSerialize
+    ( $( $attr:ident = $attr_field:ident )? ; $self:ident, $field:ident, $t:ty, $buf:ident ) => {
+        <$t as $crate::pdu_macro::FieldSerialize>::serialize_field(&$self.$field, $buf);
+    };
+}
+
 #[macro_export]
 macro_rules! __pdu_deserialize_field {
     // Option<T> with a length attribute -> read using FieldDeserializeWithLen
@@ -98,6 +209,24 @@ macro_rules! __pdu_deserialize_field {
         );
     };
 
+    // Octets with a bit length attribute -> read the octets holding that many bits and the
+    // padding to the next 32-bit boundary
+    ( bits = $bits_field:ident ; $field:ident, $t:ty, $buf:ident ) => {
+        let $field: $t = <$t as $crate::pdu_macro::FieldDeserializeWithLen>::deserialize_with_len(
+            $buf,
+            $crate::pdu_macro::padded_octets_for_bits($bits_field as usize),
+        );
+    };
+
+    // List with count attribute -> read that many elements, failing if the buffer runs out
+    ( count = $count_field:ident ; $field:ident, $t:ty, $buf:ident ) => {
+        let $field: $t =
+            <$t as $crate::pdu_macro::FieldDeserializeWithCount>::deserialize_with_count(
+                $buf,
+                $count_field as usize,
+            )?;
+    };
+
     // Default: plain FieldDeserialize
     ( ; $field:ident, $t:ty, $buf:ident ) => {
         let $field: $t = <$t as $crate::pdu_macro::FieldDeserialize>::deserialize_field($buf);
@@ -125,6 +254,10 @@ macro_rules! impl_primitive {
                 $len
             }
         }
+
+        impl FieldMinLen for $ty {
+            const MIN_LEN: usize = $len;
+        }
     };
 }
 
@@ -166,6 +299,10 @@ where
             item.serialize_field(buf);
         }
     }
+
+    fn validate_field(&self) -> Result<(), DISError> {
+        self.iter().try_for_each(FieldSerialize::validate_field)
+    }
 }
 impl<T> FieldDeserialize for Vec<T>
 where
@@ -194,6 +331,10 @@ where
             v.serialize_field(buf);
         }
     }
+
+    fn validate_field(&self) -> Result<(), DISError> {
+        self.as_ref().map_or(Ok(()), FieldSerialize::validate_field)
+    }
 }
 impl<T> FieldDeserialize for Option<T>
 where
@@ -222,7 +363,7 @@ macro_rules! define_pdu {
             protocol_family: $protocol_family:expr,
             fields: {
                 $(
-                    $(#[len = $len_field:ident])? $fvis:vis $field:ident : $ftype:ty,
+                    $(#[$attr:ident = $attr_field:ident])? $fvis:vis $field:ident : $ftype:ty,
                 )*
             }
 
@@ -254,21 +395,24 @@ macro_rules! define_pdu {
             /// Note: for variable-length arrays/strings the generated code will call
             /// `FieldDeserialize::deserialize_field()`, but for real variable-length fields
             /// you should write custom code in the manual body impl below or adapt the macro.
-            fn deserialize_body<B: bytes::Buf>(buf: &mut B) -> Self {
+            fn deserialize_body<B: bytes::Buf>(
+                buf: &mut B,
+            ) -> Result<Self, $crate::common::dis_error::DISError> {
                 $(
-                    // Each field can optionally be annotated with `#[len = name]`.
-                    // The helper macro below will either call the plain `FieldDeserialize`
-                    // or the length-aware `FieldDeserializeWithLen` depending on the
+                    // Each field can optionally be annotated with `#[len = name]`,
+                    // `#[bits = name]` or `#[count = name]`. The helper macro below will call
+                    // the plain `FieldDeserialize`, the length-aware `FieldDeserializeWithLen`
+                    // or the count-aware `FieldDeserializeWithCount` depending on the
                     // annotation.
-                    $crate::__pdu_deserialize_field!( $( len = $len_field )? ; $field, $ftype, buf );
+                    $crate::__pdu_deserialize_field!( $( $attr = $attr_field )? ; $field, $ftype, buf );
                 )*
 
-                Self {
+                Ok(Self {
                     header: <$header>::default(),
                     $(
                         $field,
                     )*
-                }
+                })
             }
         }
 
@@ -281,7 +425,7 @@ macro_rules! define_pdu {
                 let mut len: usize = <$header>::LENGTH;
 
                 $(
-                    len += <$ftype as $crate::pdu_macro::FieldLen>::field_len(&self.$field);
+                    len += $crate::__pdu_field_len!( $( $attr = $attr_field )? ; self, $field, $ftype );
                 )*
 
                 u16::try_from(len).map_err(|_| $crate::common::dis_error::DISError::PduSizeExceeded {
@@ -299,14 +443,18 @@ macro_rules! define_pdu {
             }
 
             fn serialize(&mut self, buf: &mut bytes::BytesMut) -> Result<(), $crate::common::dis_error::DISError> {
+                // refuse fields that cannot be written as they are, before writing anything
+                $( <$ftype as $crate::pdu_macro::FieldSerialize>::validate_field(&self.$field)?; )*
+
                 // set header fields
                 self.header.set_pdu_type($pdu_type);
                 self.header.set_protocol_family($protocol_family);
 
                 // Allow annotated fields to update their associated "length" fields
                 // before we compute the overall PDU length. If a field is annotated
-                // `#[len = foo]` the prep macro will set `self.foo` appropriately.
-                $( $crate::__pdu_prep_serialize_field!( $( len = $len_field )? ; self, $field, $ftype ); )*
+                // `#[len = foo]`, `#[bits = foo]` or `#[count = foo]` the prep macro will set
+                // `self.foo` appropriately.
+                $( $crate::__pdu_prep_serialize_field!( $( $attr = $attr_field )? ; self, $field, $ftype ); )*
 
                 // compute length the correct way and set it
                 let len = self.calculate_length()?;
@@ -317,7 +465,7 @@ macro_rules! define_pdu {
 
                 // serialize each field
                 $(
-                    <$ftype as $crate::pdu_macro::FieldSerialize>::serialize_field(&self.$field, buf);
+                    $crate::__pdu_serialize_field!( $( $attr = $attr_field )? ; self, $field, $ftype, buf );
                 )*
 
                 Ok(())
@@ -337,14 +485,14 @@ macro_rules! define_pdu {
                 }
 
                 // read body fields with the generated deserializer
-                let mut body = Self::deserialize_body(buf);
+                let mut body = Self::deserialize_body(buf)?;
                 body.header = header;
                 Ok(body)
             }
 
             fn deserialize_without_header<B: bytes::Buf>(buf: &mut B, header: Self::Header) -> Result<Self, $crate::common::dis_error::DISError>
             where Self: Sized {
-                let mut body = Self::deserialize_body(buf);
+                let mut body = Self::deserialize_body(buf)?;
                 body.header = header;
                 Ok(body)
             }
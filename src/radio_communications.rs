@@ -6,22 +6,40 @@
 
 //! The Radio Communications protocol family
 
+pub mod audio;
+pub mod intercom;
+pub mod link16;
+pub mod playout;
+pub mod publisher;
+pub mod reception;
+pub mod sender;
+
+use num_traits::FromPrimitive;
+
 use crate::{
     common::{
         GenericHeader, SerializedLength,
         data_types::{
-            EntityCoordinateVector, WorldCoordinate, antenna_pattern::AntennaPattern,
+            EntityCoordinateVector, WorldCoordinate,
+            antenna_pattern::{AntennaPattern, AntennaPatternRecord},
+            encoding_scheme::EncodingScheme,
             entity_id::EntityId,
-            intercom_communications_parameters::IntercomCommunicationsParameters,
-            modulation_parameters::ModulationParameters, modulation_type::ModulationType,
+            intercom_communications_parameters::{
+                IntercomCommunicationsParameters, SpecificDestination,
+            },
+            modulation_parameters::{
+                JtidsMidsModulationParameters, ModulationParameterRecord, ModulationParameters,
+            },
+            modulation_type::ModulationType,
             radio_entity_type::RadioEntityType,
             variable_transmitter_parameters::VariableTransmitterParameters,
         },
+        dis_error::DISError,
         enums::{
-            IntercomControlCommand, IntercomControlControlType, IntercomControlTransmitLineState,
-            PduType, ProtocolFamily, ReceiverReceiverState, SignalTDLType,
-            TransmitterAntennaPatternType, TransmitterCryptoSystem, TransmitterInputSource,
-            TransmitterTransmitState,
+            IntercomControlCommand, IntercomControlCommunicationsType, IntercomControlControlType,
+            IntercomControlTransmitLineState, PduType, ProtocolFamily, ReceiverReceiverState,
+            SignalTDLType, TransmitterAntennaPatternType, TransmitterCryptoSystem,
+            TransmitterInputSource, TransmitterModulationTypeSystem, TransmitterTransmitState,
         },
         pdu::Pdu,
         pdu_header::PduHeader,
@@ -29,8 +47,11 @@ use crate::{
     define_pdu,
 };
 
+use audio::AudioCodec;
+use link16::Link16Message;
+
 define_pdu! {
-    #[derive(Debug)]
+    #[derive(Clone, Debug)]
     /// Implemented according to IEEE 1278.1-2012 §7.7.2
     pub struct TransmitterPdu {
         header: PduHeader,
@@ -58,14 +79,71 @@ define_pdu! {
             padding2: u16,
             #[len = modulation_parameter_length]
             pub modulation_parameters: Option<ModulationParameters>,
+            #[len = antenna_pattern_length]
             pub antenna_pattern: Option<AntennaPattern>,
+            #[count = number_of_variable_transmitter_parameters_records]
             pub variable_transmitter_parameters: Vec<VariableTransmitterParameters>,
         }
     }
 }
 
+impl TransmitterPdu {
+    /// The modulation parameters interpreted according to `modulation_type.system`
+    #[must_use]
+    pub fn modulation_parameter_record(&self) -> Option<ModulationParameterRecord> {
+        self.modulation_parameters
+            .as_ref()
+            .map(|parameters| parameters.decode(self.modulation_type.radio_system()))
+    }
+
+    /// Sets the modulation parameters and, unless they are raw, the matching radio system
+    pub fn set_modulation_parameter_record(&mut self, record: &ModulationParameterRecord) {
+        if let Some(system) = record.system() {
+            let current = self.modulation_type.radio_system();
+            let same_family = matches!(
+                (current, system),
+                (
+                    TransmitterModulationTypeSystem::HAVEQUICKI,
+                    TransmitterModulationTypeSystem::HAVEQUICKII
+                )
+            );
+            if !same_family {
+                self.modulation_type.system = system as u16;
+            }
+        }
+        self.modulation_parameters = Some(ModulationParameters::from(record));
+    }
+
+    /// The JTIDS/MIDS terminal parameters, if the radio is a JTIDS/MIDS terminal
+    #[must_use]
+    pub fn jtids_mids_parameters(&self) -> Option<JtidsMidsModulationParameters> {
+        match self.modulation_parameter_record()? {
+            ModulationParameterRecord::JtidsMids(parameters) => Some(parameters),
+            _ => None,
+        }
+    }
+
+    /// The antenna pattern interpreted according to `antenna_pattern_type`
+    #[must_use]
+    pub fn antenna_pattern_record(&self) -> Option<AntennaPatternRecord> {
+        self.antenna_pattern
+            .as_ref()
+            .map(|pattern| pattern.decode(self.antenna_pattern_type))
+    }
+
+    /// Sets the antenna pattern and, unless it is raw, the matching antenna pattern type
+    ///
+    /// `antenna_pattern_length` is updated when the PDU is serialized.
+    pub fn set_antenna_pattern_record(&mut self, record: &AntennaPatternRecord) {
+        if let Some(pattern_type) = record.pattern_type() {
+            self.antenna_pattern_type = pattern_type;
+        }
+        self.antenna_pattern = Some(AntennaPattern::from(record));
+    }
+}
+
 define_pdu! {
-    #[derive(Debug)]
+    #[derive(Clone, Debug)]
     /// Implemented according to IEEE 1278.1-2012 §7.7.3
     pub struct SignalPdu {
         header: PduHeader,
@@ -79,11 +157,142 @@ define_pdu! {
             pub sample_rate: u32,
             pub data_length: u16,
             pub samples: u16,
+            #[bits = data_length]
             pub data: Vec<u8>,
         }
     }
 }
 
+impl SignalPdu {
+    /// The encoding scheme split into its encoding class and type or TDL message count
+    #[must_use]
+    pub fn encoding(&self) -> EncodingScheme {
+        EncodingScheme::from_u16(self.encoding_scheme)
+    }
+
+    pub const fn set_encoding(&mut self, encoding: EncodingScheme) {
+        self.encoding_scheme = encoding.to_u16();
+    }
+
+    /// The codec for the encoded audio carried by this PDU, if it is supported
+    #[must_use]
+    pub fn audio_codec(&self) -> Option<AudioCodec> {
+        self.encoding()
+            .encoding_type()
+            .and_then(AudioCodec::from_encoding_type)
+    }
+
+    /// Decodes the audio samples carried by this PDU
+    ///
+    /// # Errors
+    /// Returns `DISError::InvalidFieldValue` if the PDU does not carry encoded audio in a
+    /// supported encoding.
+    pub fn decode_audio(&self) -> Result<Vec<i16>, DISError> {
+        let codec = self.audio_codec().ok_or_else(|| {
+            DISError::invalid_field(
+                "encoding_scheme".to_string(),
+                self.encoding_scheme.to_string(),
+                "not encoded audio in a supported encoding".to_string(),
+            )
+        })?;
+        let octets = usize::from(self.data_length)
+            .div_ceil(8)
+            .min(self.data.len());
+        Ok(codec.decode(&self.data[..octets], usize::from(self.samples)))
+    }
+
+    /// Encodes `pcm` with `codec` and sets the encoding scheme, sample rate, sample count,
+    /// data and data length accordingly
+    ///
+    /// # Errors
+    /// Returns `DISError::InvalidFieldValue` if the codec has no Signal PDU encoding type
+    /// (A-law), or if the samples do not fit in the `samples` or `data_length` fields.
+    pub fn set_audio(
+        &mut self,
+        codec: AudioCodec,
+        sample_rate: u32,
+        pcm: &[i16],
+    ) -> Result<(), DISError> {
+        let encoding_type = codec.encoding_type().ok_or_else(|| {
+            DISError::invalid_field(
+                "encoding_scheme".to_string(),
+                format!("{codec:?}"),
+                "the codec has no Signal PDU encoding type".to_string(),
+            )
+        })?;
+        let samples = u16::try_from(pcm.len()).map_err(|_| {
+            DISError::invalid_field(
+                "samples".to_string(),
+                pcm.len().to_string(),
+                "more samples than fit in 16 bits".to_string(),
+            )
+        })?;
+        let bits = pcm.len() * codec.bits_per_sample();
+        let data_length = u16::try_from(bits).map_err(|_| {
+            DISError::invalid_field(
+                "data_length".to_string(),
+                bits.to_string(),
+                "more data bits than fit in 16 bits".to_string(),
+            )
+        })?;
+
+        self.set_encoding(EncodingScheme::audio(encoding_type));
+        self.sample_rate = sample_rate;
+        self.samples = samples;
+        self.data_length = data_length;
+        self.data = codec.encode(pcm);
+        Ok(())
+    }
+
+    /// Decodes the SISO-STD-002 Link 16 payload carried by this PDU
+    ///
+    /// # Errors
+    /// Returns `DISError::InvalidFieldValue` if the TDL type is not the Link 16 standardized
+    /// format or the PDU does not carry raw binary data, and any error of
+    /// [`Link16Message::decode`].
+    pub fn link16_message(&self) -> Result<Link16Message, DISError> {
+        if self.tdl_type != SignalTDLType::Link16StandardizedFormat {
+            return Err(DISError::invalid_field(
+                "tdl_type".to_string(),
+                format!("{:?}", self.tdl_type),
+                "not the Link 16 standardized format".to_string(),
+            ));
+        }
+        if self.encoding().tdl_message_count().is_none() {
+            return Err(DISError::invalid_field(
+                "encoding_scheme".to_string(),
+                self.encoding_scheme.to_string(),
+                "not raw binary data".to_string(),
+            ));
+        }
+        Link16Message::decode(&self.data, usize::from(self.data_length))
+    }
+
+    /// Sets the TDL type, encoding scheme, data and data length to carry `message`
+    ///
+    /// # Errors
+    /// Returns `DISError::InvalidFieldValue` if the message does not fit in the
+    /// `data_length` field.
+    pub fn set_link16_message(&mut self, message: &Link16Message) -> Result<(), DISError> {
+        let bits = message.bit_length();
+        let data_length = u16::try_from(bits).map_err(|_| {
+            DISError::invalid_field(
+                "data_length".to_string(),
+                bits.to_string(),
+                "more data bits than fit in 16 bits".to_string(),
+            )
+        })?;
+
+        self.tdl_type = SignalTDLType::Link16StandardizedFormat;
+        self.set_encoding(EncodingScheme::tdl_messages(1));
+        self.sample_rate = 0;
+        self.samples = 0;
+        self.data_length = data_length;
+        self.data = message.encode();
+        Ok(())
+    }
+}
+
 define_pdu! {
     #[derive(Debug)]
     /// Implemented according to IEEE 1278.1-2012 §7.7.4
@@ -104,7 +313,7 @@ define_pdu! {
 }
 
 define_pdu! {
-    #[derive(Debug)]
+    #[derive(Clone, Debug)]
     /// Implemented according to IEEE 1278.1-2012 §7.7.5
     pub struct IntercomSignalPdu {
         header: PduHeader,
@@ -118,13 +327,14 @@ define_pdu! {
             pub sample_rate: u32,
             pub data_length: u16,
             pub samples: u16,
+            #[bits = data_length]
             pub data: Vec<u8>,
         }
     }
 }
 
 define_pdu! {
-    #[derive(Debug)]
+    #[derive(Clone, Debug)]
     /// Implemented according to IEEE 1278.1-2012 §7.7.6
     pub struct IntercomControlPdu {
         header: PduHeader,
@@ -143,11 +353,42 @@ define_pdu! {
             pub master_intercom_number: u16,
             pub master_channel_id: u16,
             pub intercom_parameters_length: u32,
+            #[count = intercom_parameters_length]
             pub intercom_parameters: Vec<IntercomCommunicationsParameters>,
         }
     }
 }
 
+impl IntercomControlPdu {
+    /// The communications type held in the low seven bits of `communications_channel_type`
+    #[must_use]
+    pub fn communications_type(&self) -> IntercomControlCommunicationsType {
+        IntercomControlCommunicationsType::from_u8(self.communications_channel_type & 0x7F)
+            .unwrap_or_default()
+    }
+
+    /// Sets the communications type, keeping the channel class bit
+    pub const fn set_communications_type(
+        &mut self,
+        communications_type: IntercomControlCommunicationsType,
+    ) {
+        self.communications_channel_type =
+            (self.communications_channel_type & 0x80) | (communications_type as u8 & 0x7F);
+    }
+
+    /// The Specific Destination records among the intercom parameters
+    pub fn specific_destinations(&self) -> impl Iterator<Item = &SpecificDestination> {
+        self.intercom_parameters
+            .iter()
+            .filter_map(|record| match record {
+                IntercomCommunicationsParameters::SpecificDestination(destination) => {
+                    Some(destination)
+                }
+                _ => None,
+            })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -156,6 +397,23 @@ mod tests {
 
     mod transmitter_pdu_tests {
         use super::*;
+        use crate::common::{
+            data_types::{
+                EulerAngles,
+                antenna_pattern::SphericalHarmonicAntennaPattern,
+                beam_antenna_pattern::BeamAntennaPattern,
+                modulation_parameters::{
+                    CcttSincgarsModulationParameters, HaveQuickModulationParameters,
+                    JtidsMidsModulationParameters, NetId,
+                },
+                variable_transmitter_parameters::{AntennaLocation, HighFidelityHaveQuickSaturn},
+            },
+            enums::{
+                CCTTSINCGARSStartofMessage, HighFidelityHAVEQUICKTODTransmitIndicator,
+                JTIDSMIDSModulationParametersSynchronizationState, NETIDRecordFrequencyTable,
+                NETIDRecordMode, TimeSlotAllocationLevel, TransmitterAntennaPatternReferenceSystem,
+            },
+        };
 
         #[test]
         fn cast_to_any() {
@@ -182,10 +440,186 @@ mod tests {
             let pdu = TransmitterPdu::new();
             assert_eq!(pdu.header().length, DEFAULT_LENGTH);
         }
+
+        #[test]
+        fn serialize_then_deserialize_sincgars_parameters() {
+            let record =
+                ModulationParameterRecord::CcttSincgars(CcttSincgarsModulationParameters::new(
+                    1234,
+                    3,
+                    7,
+                    CCTTSINCGARSStartofMessage::StartofMessage,
+                    250,
+                ));
+            let mut pdu = TransmitterPdu::new();
+            pdu.set_modulation_parameter_record(&record);
+            assert_eq!(
+                pdu.modulation_type.radio_system(),
+                TransmitterModulationTypeSystem::CCTTSINCGARS
+            );
+
+            let mut serialize_buf = BytesMut::new();
+            let _ = pdu.serialize(&mut serialize_buf);
+            assert_eq!(pdu.modulation_parameter_length, 16);
+
+            let mut deserialize_buf = serialize_buf.freeze();
+            let new_pdu = TransmitterPdu::deserialize(&mut deserialize_buf).unwrap_or_default();
+            assert_eq!(new_pdu.modulation_parameter_record(), Some(record));
+        }
+
+        #[test]
+        fn jtids_parameters_are_selected_by_system() {
+            let record = ModulationParameterRecord::JtidsMids(JtidsMidsModulationParameters {
+                time_slot_allocation_mode: TimeSlotAllocationLevel::HighFidelityLevel4,
+                synchronization_state:
+                    JTIDSMIDSModulationParametersSynchronizationState::FineSynchronization,
+                network_sync_id: 42,
+                ..Default::default()
+            });
+            let mut pdu = TransmitterPdu::new();
+            pdu.set_modulation_parameter_record(&record);
+            assert_eq!(pdu.modulation_parameter_record(), Some(record.clone()));
+
+            pdu.modulation_type.system = TransmitterModulationTypeSystem::Link11 as u16;
+            assert!(matches!(
+                pdu.modulation_parameter_record(),
+                Some(ModulationParameterRecord::Other(bytes)) if bytes.len() == 8
+            ));
+        }
+
+        #[test]
+        fn have_quick_keeps_the_configured_system() {
+            let record = ModulationParameterRecord::HaveQuick(HaveQuickModulationParameters {
+                net_id: NetId {
+                    net_number: 301,
+                    frequency_table: NETIDRecordFrequencyTable::HQIINATOEuropeAreaOperations,
+                    mode: NETIDRecordMode::TTRAINING,
+                },
+            });
+            let mut buf = BytesMut::new();
+            record.serialize(&mut buf);
+            // Mode 3 in bits 12-13, frequency table 1 in bits 10-11, net number 301 in bits 0-9
+            assert_eq!(buf[..2], [0x35, 0x2D]);
+
+            let mut pdu = TransmitterPdu::new();
+            pdu.modulation_type.system = TransmitterModulationTypeSystem::HAVEQUICKI as u16;
+            pdu.set_modulation_parameter_record(&record);
+            assert_eq!(
+                pdu.modulation_type.radio_system(),
+                TransmitterModulationTypeSystem::HAVEQUICKI
+            );
+            assert_eq!(pdu.modulation_parameter_record(), Some(record));
+        }
+
+        #[test]
+        fn serialize_then_deserialize_beam_antenna_pattern() {
+            let record = AntennaPatternRecord::Beam(BeamAntennaPattern::new(
+                EulerAngles::new(0.1, 0.2, 0.3),
+                0.5,
+                0.25,
+                TransmitterAntennaPatternReferenceSystem::EntityCoordinates,
+                1.0,
+                0.5,
+                0.0,
+            ));
+            let mut pdu = TransmitterPdu::new();
+            pdu.set_antenna_pattern_record(&record);
+            assert_eq!(
+                pdu.antenna_pattern_type,
+                TransmitterAntennaPatternType::Beam
+            );
+
+            let mut serialize_buf = BytesMut::new();
+            let _ = pdu.serialize(&mut serialize_buf);
+            assert_eq!(pdu.antenna_pattern_length, 40);
+
+            let mut deserialize_buf = serialize_buf.freeze();
+            let new_pdu = TransmitterPdu::deserialize(&mut deserialize_buf).unwrap_or_default();
+            assert_eq!(new_pdu.antenna_pattern_record(), Some(record));
+        }
+
+        #[test]
+        fn serialize_then_deserialize_variable_transmitter_parameters() {
+            let mut pdu = TransmitterPdu::new();
+            pdu.variable_transmitter_parameters = vec![
+                VariableTransmitterParameters::HighFidelityHaveQuickSaturn(
+                    HighFidelityHaveQuickSaturn::new(
+                        NetId {
+                            net_number: 12,
+                            frequency_table: NETIDRecordFrequencyTable::SATURNOperations,
+                            mode: NETIDRecordMode::BSATURNCOMBAT,
+                        },
+                        HighFidelityHAVEQUICKTODTransmitIndicator::TODTransmissioninProgress,
+                        250,
+                        [1, 2, 3, 4, 5, 6],
+                    ),
+                ),
+                VariableTransmitterParameters::AntennaLocation(AntennaLocation::new(
+                    WorldCoordinate::new(1.0, 2.0, 3.0),
+                    EntityCoordinateVector::new(0.0, 0.0, 2.5),
+                )),
+                VariableTransmitterParameters::Unknown {
+                    record_type: 1,
+                    record_specific_fields: vec![0xAB, 0xCD],
+                },
+            ];
+
+            let mut serialize_buf = BytesMut::new();
+            let _ = pdu.serialize(&mut serialize_buf);
+            assert_eq!(pdu.number_of_variable_transmitter_parameters_records, 3);
+            let record_lengths: Vec<u16> = pdu
+                .variable_transmitter_parameters
+                .iter()
+                .map(VariableTransmitterParameters::record_length)
+                .collect();
+            assert_eq!(record_lengths, vec![40, 48, 8]);
+
+            let mut record_buf = BytesMut::new();
+            pdu.variable_transmitter_parameters[0].serialize(&mut record_buf);
+            // Net ID after the record header and padding: mode 2 in bits 12-13, frequency
+            // table 3 in bits 10-11 and net number 12 in bits 0-9
+            assert_eq!(record_buf[8..10], [0x2C, 0x0C]);
+
+            let mut deserialize_buf = serialize_buf.freeze();
+            let new_pdu = TransmitterPdu::deserialize(&mut deserialize_buf).unwrap_or_default();
+            assert_eq!(
+                new_pdu.variable_transmitter_parameters[..2],
+                pdu.variable_transmitter_parameters[..2]
+            );
+            assert_eq!(
+                new_pdu.variable_transmitter_parameters[2],
+                VariableTransmitterParameters::Unknown {
+                    record_type: 1,
+                    record_specific_fields: vec![0xAB, 0xCD],
+                }
+            );
+        }
+
+        #[test]
+        fn serialize_then_deserialize_spherical_harmonic_pattern() {
+            let record =
+                AntennaPatternRecord::SphericalHarmonic(SphericalHarmonicAntennaPattern::new(
+                    1,
+                    vec![1.0, 0.5, 0.25, 0.125],
+                    TransmitterAntennaPatternReferenceSystem::WorldCoordinates,
+                ));
+            let mut pdu = TransmitterPdu::new();
+            pdu.set_antenna_pattern_record(&record);
+
+            let mut serialize_buf = BytesMut::new();
+            let _ = pdu.serialize(&mut serialize_buf);
+            assert_eq!(pdu.antenna_pattern_length, 24);
+            assert_eq!(pdu.header().length % 8, 0);
+
+            let mut deserialize_buf = serialize_buf.freeze();
+            let new_pdu = TransmitterPdu::deserialize(&mut deserialize_buf).unwrap_or_default();
+            assert_eq!(new_pdu.antenna_pattern_record(), Some(record));
+        }
     }
 
     mod signal_pdu_tests {
         use super::*;
+        use crate::common::enums::SignalEncodingType;
 
         #[test]
         fn cast_to_any() {
@@ -212,6 +646,124 @@ mod tests {
             let pdu = SignalPdu::new();
             assert_eq!(pdu.header().length, DEFAULT_LENGTH);
         }
+
+        #[test]
+        fn encoding_scheme_splits_class_and_type() {
+            let mut pdu = SignalPdu::new();
+            pdu.set_encoding(EncodingScheme::audio(SignalEncodingType::CVSD));
+            assert_eq!(pdu.encoding_scheme, 0x0002);
+            assert_eq!(pdu.audio_codec(), Some(AudioCodec::Cvsd));
+
+            pdu.set_encoding(EncodingScheme::tdl_messages(5));
+            assert_eq!(pdu.encoding_scheme, 0x4005);
+            assert_eq!(pdu.encoding().tdl_message_count(), Some(5));
+            assert_eq!(pdu.audio_codec(), None);
+            assert!(pdu.decode_audio().is_err());
+        }
+
+        #[test]
+        fn audio_round_trips_with_data_length_in_bits() {
+            let pcm: Vec<i16> = (0..21).map(|n| n * 1_000 - 10_000).collect();
+            let mut pdu = SignalPdu::new();
+            pdu.set_audio(AudioCodec::MuLaw, 8_000, &pcm)
+                .unwrap_or_default();
+            assert_eq!(pdu.data_length, 168);
+            assert_eq!(pdu.samples, 21);
+
+            let mut serialize_buf = BytesMut::new();
+            let _ = pdu.serialize(&mut serialize_buf);
+            // 21 octets of data padded to 24
+            assert_eq!(usize::from(pdu.header().length), 32 + 24);
+            assert_eq!(serialize_buf.len(), 32 + 24);
+
+            let mut deserialize_buf = serialize_buf.freeze();
+            let new_pdu = SignalPdu::deserialize(&mut deserialize_buf).unwrap_or_default();
+            assert_eq!(new_pdu.data_length, 168);
+            assert_eq!(new_pdu.data.len(), 24);
+            assert_eq!(
+                new_pdu.decode_audio().unwrap_or_default(),
+                AudioCodec::MuLaw.decode(&AudioCodec::MuLaw.encode(&pcm), pcm.len())
+            );
+        }
+
+        #[test]
+        fn cvsd_data_length_counts_bits() {
+            let pcm = vec![0i16; 100];
+            let mut pdu = SignalPdu::new();
+            pdu.set_audio(AudioCodec::Cvsd, 16_000, &pcm)
+                .unwrap_or_default();
+            assert_eq!(pdu.data_length, 100);
+            assert_eq!(pdu.data.len(), 13);
+            assert_eq!(pdu.decode_audio().unwrap_or_default().len(), 100);
+        }
+
+        #[test]
+        fn serialization_pads_the_wire_not_the_data() {
+            let mut pdu = SignalPdu::new();
+            pdu.data = vec![7; 21];
+            let mut first = BytesMut::new();
+            pdu.serialize(&mut first).expect("data fits");
+            let mut second = BytesMut::new();
+            pdu.serialize(&mut second).expect("data fits");
+            assert_eq!(first, second);
+            assert_eq!(first.len(), 32 + 24);
+            assert_eq!(pdu.data, vec![7; 21]);
+            assert_eq!(pdu.data_length, 168);
+
+            pdu.data = vec![7; 40];
+            let mut longer = BytesMut::new();
+            pdu.serialize(&mut longer).expect("data fits");
+            assert_eq!(pdu.data_length, 320);
+            let received = SignalPdu::deserialize(&mut longer.freeze()).expect("decodes");
+            assert_eq!(received.data, pdu.data);
+
+            pdu.data_length = 315;
+            pdu.serialize(&mut BytesMut::new()).expect("data fits");
+            assert_eq!(pdu.data_length, 315);
+
+            pdu.data = vec![0; 8_192];
+            assert!(pdu.serialize(&mut BytesMut::new()).is_err());
+        }
+
+        #[test]
+        fn a_law_has_no_encoding_type() {
+            let mut pdu = SignalPdu::new();
+            assert!(pdu.set_audio(AudioCodec::ALaw, 8_000, &[0, 1, 2]).is_err());
+            assert!(pdu.data.is_empty());
+        }
+
+        #[test]
+        fn link16_message_round_trips_through_the_pdu() {
+            use link16::{
+                JtidsHeader, Link16MessageData, Link16MessageType, Link16NetworkHeader, Link16Word,
+            };
+
+            let message = Link16Message::jtids(
+                Link16NetworkHeader::new(7, 1, Link16MessageType::JtidsHeaderMessages, 300),
+                JtidsHeader {
+                    source_track_number: 0o17_776,
+                    ..JtidsHeader::default()
+                },
+                vec![Link16Word::from_data(2 << 2 | 2 << 7, 0); 3],
+            );
+            let mut pdu = SignalPdu::new();
+            assert!(pdu.link16_message().is_err());
+            pdu.set_link16_message(&message)
+                .expect("message fits in the PDU");
+            assert_eq!(pdu.data_length, 160 + 35 + 3 * 75);
+            assert_eq!(pdu.encoding().tdl_message_count(), Some(1));
+
+            let mut serialize_buf = BytesMut::new();
+            let _ = pdu.serialize(&mut serialize_buf);
+            let mut deserialize_buf = serialize_buf.freeze();
+            let new_pdu = SignalPdu::deserialize(&mut deserialize_buf).unwrap_or_default();
+            let decoded = new_pdu.link16_message().expect("decodes the payload");
+            assert_eq!(decoded, message);
+            let Link16MessageData::Jtids { words, .. } = decoded.data else {
+                panic!("expected JTIDS header message data");
+            };
+            assert_eq!(words[0].j_series().as_deref(), Some("J2.2"));
+        }
     }
 
     mod receiver_pdu_tests {
@@ -302,5 +854,46 @@ mod tests {
             let pdu = IntercomControlPdu::new();
             assert_eq!(pdu.header().length, DEFAULT_LENGTH);
         }
+
+        #[test]
+        fn typed_parameters_round_trip() {
+            use crate::common::{
+                data_types::intercom_communications_parameters::{
+                    GroupAssignment, GroupDestination,
+                },
+                enums::IntercomControlDestinationLineStateCommand,
+            };
+
+            let mut pdu = IntercomControlPdu::new();
+            pdu.set_communications_type(IntercomControlCommunicationsType::ConnectionHDX);
+            pdu.intercom_parameters = vec![
+                IntercomCommunicationsParameters::GroupDestination(GroupDestination::new(
+                    0b101,
+                    3,
+                    IntercomControlDestinationLineStateCommand::SetLineStateTransmitting,
+                )),
+                IntercomCommunicationsParameters::GroupAssignment(GroupAssignment::new(
+                    0b100,
+                    EntityId::new(1, 2, 3),
+                    4,
+                )),
+                IntercomCommunicationsParameters::Unknown {
+                    record_type: 99,
+                    record_specific_field: vec![1, 2, 3, 4],
+                },
+            ];
+            let mut serialize_buf = BytesMut::new();
+            let _ = pdu.serialize(&mut serialize_buf);
+            assert_eq!(pdu.intercom_parameters_length, 3);
+            assert_eq!(usize::from(pdu.header().length), 40 + 12 + 16 + 8);
+
+            let mut deserialize_buf = serialize_buf.freeze();
+            let new_pdu = IntercomControlPdu::deserialize(&mut deserialize_buf).unwrap_or_default();
+            assert_eq!(
+                new_pdu.communications_type(),
+                IntercomControlCommunicationsType::ConnectionHDX
+            );
+            assert_eq!(new_pdu.intercom_parameters, pdu.intercom_parameters);
+        }
     }
 }
@@ -0,0 +1,441 @@
+//     open-dis-rust - Rust implementation of the IEEE 1278.1-2012 Distributed Interactive
+//                     Simulation (DIS) application protocol
+//     Copyright (C) 2025 Cameron Howell
+//
+//     Licensed under the BSD 2-Clause License
+
+//! Audio codecs for the encoded audio carried in Signal PDUs
+//!
+//! Every codec converts between 16-bit signed PCM samples and the octets placed in the
+//! `data` field of a [`SignalPdu`](crate::radio_communications::SignalPdu).
+
+use crate::common::enums::SignalEncodingType;
+
+/// Bias added to the magnitude before µ-law compression
+const MU_LAW_BIAS: i32 = 0x84;
+/// Largest magnitude µ-law can represent before the bias is added
+const MU_LAW_CLIP: i32 = 32_635;
+/// Upper bounds of the A-law segments, on 13-bit magnitudes
+const A_LAW_SEGMENT_END: [i32; 8] = [0x1F, 0x3F, 0x7F, 0xFF, 0x1FF, 0x3FF, 0x7FF, 0xFFF];
+
+/// Smallest CVSD step size
+const CVSD_MIN_STEP: i32 = 16;
+/// Largest CVSD step size
+const CVSD_MAX_STEP: i32 = 1_280;
+/// Number of equal consecutive bits that trigger a larger CVSD step
+const CVSD_RUN_LENGTH: u32 = 3;
+
+/// The audio codecs supported for Signal PDUs
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AudioCodec {
+    /// 8-bit µ-law, ITU-T G.711
+    MuLaw,
+    /// 8-bit A-law, ITU-T G.711
+    ///
+    /// SISO-REF-010 defines no Signal PDU encoding type for A-law, so it can only be used
+    /// between applications that agree on it out of band.
+    ALaw,
+    /// 16-bit linear PCM, two's complement, big endian
+    Pcm16BigEndian,
+    /// 16-bit linear PCM, two's complement, little endian
+    Pcm16LittleEndian,
+    /// 8-bit linear PCM, unsigned
+    Pcm8Unsigned,
+    /// Continuously variable slope delta modulation, one bit per sample
+    Cvsd,
+}
+
+impl AudioCodec {
+    /// The codec for a Signal PDU encoding type, if it is supported
+    #[must_use]
+    pub const fn from_encoding_type(encoding_type: SignalEncodingType) -> Option<Self> {
+        match encoding_type {
+            SignalEncodingType::_8bitmulaw => Some(Self::MuLaw),
+            SignalEncodingType::CVSD => Some(Self::Cvsd),
+            SignalEncodingType::_16bitLinearPCM2sComplementBigEndian => Some(Self::Pcm16BigEndian),
+            SignalEncodingType::_8bitLinearPCMUnsigned => Some(Self::Pcm8Unsigned),
+            SignalEncodingType::_16bitLinearPCM2sComplementLittleEndian => {
+                Some(Self::Pcm16LittleEndian)
+            }
+            _ => None,
+        }
+    }
+
+    /// The Signal PDU encoding type of the codec, or `None` for A-law
+    #[must_use]
+    pub const fn encoding_type(self) -> Option<SignalEncodingType> {
+        match self {
+            Self::MuLaw => Some(SignalEncodingType::_8bitmulaw),
+            Self::ALaw => None,
+            Self::Pcm16BigEndian => Some(SignalEncodingType::_16bitLinearPCM2sComplementBigEndian),
+            Self::Pcm16LittleEndian => {
+                Some(SignalEncodingType::_16bitLinearPCM2sComplementLittleEndian)
+            }
+            Self::Pcm8Unsigned => Some(SignalEncodingType::_8bitLinearPCMUnsigned),
+            Self::Cvsd => Some(SignalEncodingType::CVSD),
+        }
+    }
+
+    #[must_use]
+    pub const fn bits_per_sample(self) -> usize {
+        match self {
+            Self::MuLaw | Self::ALaw | Self::Pcm8Unsigned => 8,
+            Self::Pcm16BigEndian | Self::Pcm16LittleEndian => 16,
+            Self::Cvsd => 1,
+        }
+    }
+
+    /// Encodes `pcm` into the octets of a Signal PDU
+    ///
+    /// CVSD starts from a fresh encoder; use [`CvsdEncoder`] to keep its state across PDUs.
+    #[must_use]
+    pub fn encode(self, pcm: &[i16]) -> Vec<u8> {
+        match self {
+            Self::MuLaw => pcm.iter().map(|&sample| mu_law_encode(sample)).collect(),
+            Self::ALaw => pcm.iter().map(|&sample| a_law_encode(sample)).collect(),
+            Self::Pcm16BigEndian => pcm.iter().flat_map(|sample| sample.to_be_bytes()).collect(),
+            Self::Pcm16LittleEndian => pcm.iter().flat_map(|sample| sample.to_le_bytes()).collect(),
+            Self::Pcm8Unsigned => pcm.iter().map(|&sample| pcm8_encode(sample)).collect(),
+            Self::Cvsd => CvsdEncoder::new().encode(pcm),
+        }
+    }
+
+    /// Decodes up to `samples` samples from the octets of a Signal PDU
+    ///
+    /// CVSD starts from a fresh decoder; use [`CvsdDecoder`] to keep its state across PDUs.
+    #[must_use]
+    pub fn decode(self, data: &[u8], samples: usize) -> Vec<i16> {
+        match self {
+            Self::MuLaw => data
+                .iter()
+                .take(samples)
+                .map(|&byte| mu_law_decode(byte))
+                .collect(),
+            Self::ALaw => data
+                .iter()
+                .take(samples)
+                .map(|&byte| a_law_decode(byte))
+                .collect(),
+            Self::Pcm16BigEndian => data
+                .chunks_exact(2)
+                .take(samples)
+                .map(|pair| i16::from_be_bytes([pair[0], pair[1]]))
+                .collect(),
+            Self::Pcm16LittleEndian => data
+                .chunks_exact(2)
+                .take(samples)
+                .map(|pair| i16::from_le_bytes([pair[0], pair[1]]))
+                .collect(),
+            Self::Pcm8Unsigned => data
+                .iter()
+                .take(samples)
+                .map(|&byte| pcm8_decode(byte))
+                .collect(),
+            Self::Cvsd => CvsdDecoder::new().decode(data, samples),
+        }
+    }
+}
+
+/// Compresses a sample to 8-bit µ-law
+#[must_use]
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub fn mu_law_encode(sample: i16) -> u8 {
+    let mut magnitude = i32::from(sample);
+    let sign = if magnitude < 0 {
+        magnitude = -magnitude;
+        0x80
+    } else {
+        0x00
+    };
+    magnitude = magnitude.min(MU_LAW_CLIP) + MU_LAW_BIAS;
+
+    let mut exponent = 7;
+    let mut mask = 0x4000;
+    while exponent > 0 && magnitude & mask == 0 {
+        exponent -= 1;
+        mask >>= 1;
+    }
+    let mantissa = (magnitude >> (exponent + 3)) & 0x0F;
+    !((sign | (exponent << 4) | mantissa) as u8)
+}
+
+/// Expands an 8-bit µ-law value to a sample
+#[must_use]
+#[allow(clippy::cast_possible_truncation)]
+pub fn mu_law_decode(value: u8) -> i16 {
+    let value = !value;
+    let exponent = i32::from((value >> 4) & 0x07);
+    let mantissa = i32::from(value & 0x0F);
+    let magnitude = (((mantissa << 3) + MU_LAW_BIAS) << exponent) - MU_LAW_BIAS;
+    if value & 0x80 == 0 {
+        magnitude as i16
+    } else {
+        -magnitude as i16
+    }
+}
+
+/// Compresses a sample to 8-bit A-law
+#[must_use]
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_possible_wrap,
+    clippy::cast_sign_loss
+)]
+pub fn a_law_encode(sample: i16) -> u8 {
+    let mut magnitude = i32::from(sample) >> 3;
+    let mask = if magnitude >= 0 {
+        0xD5
+    } else {
+        magnitude = -magnitude - 1;
+        0x55
+    };
+
+    let Some(segment) = A_LAW_SEGMENT_END.iter().position(|&end| magnitude <= end) else {
+        return 0x7F ^ mask;
+    };
+    let shift = if segment < 2 { 1 } else { segment };
+    let value = ((segment as i32) << 4) | ((magnitude >> shift) & 0x0F);
+    (value as u8) ^ mask
+}
+
+/// Expands an 8-bit A-law value to a sample
+#[must_use]
+#[allow(clippy::cast_possible_truncation)]
+pub fn a_law_decode(value: u8) -> i16 {
+    let value = value ^ 0x55;
+    let mut magnitude = i32::from(value & 0x0F) << 4;
+    let segment = i32::from((value & 0x70) >> 4);
+    match segment {
+        0 => magnitude += 8,
+        1 => magnitude += 0x108,
+        _ => {
+            magnitude += 0x108;
+            magnitude <<= segment - 1;
+        }
+    }
+    if value & 0x80 == 0 {
+        -magnitude as i16
+    } else {
+        magnitude as i16
+    }
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+const fn pcm8_encode(sample: i16) -> u8 {
+    ((sample >> 8) + 128) as u8
+}
+
+#[allow(clippy::cast_possible_wrap)]
+const fn pcm8_decode(value: u8) -> i16 {
+    ((value as i16) - 128) << 8
+}
+
+/// State shared by the CVSD encoder and decoder, so that both reconstruct the same signal
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct CvsdState {
+    integrator: i32,
+    step: i32,
+    history: u32,
+}
+
+impl CvsdState {
+    const fn new() -> Self {
+        Self {
+            integrator: 0,
+            step: CVSD_MIN_STEP,
+            history: 0,
+        }
+    }
+
+    /// Applies one bit and returns the reconstructed sample
+    #[allow(clippy::cast_possible_truncation)]
+    fn apply(&mut self, bit: bool) -> i16 {
+        let run_mask = (1 << CVSD_RUN_LENGTH) - 1;
+        self.history = (self.history << 1 | u32::from(bit)) & run_mask;
+        self.step = if self.history == 0 || self.history == run_mask {
+            (self.step + self.step / 2).min(CVSD_MAX_STEP)
+        } else {
+            (self.step - self.step / 8).max(CVSD_MIN_STEP)
+        };
+
+        // A small leak keeps the integrator from drifting on silence
+        let leaked = self.integrator - self.integrator / 64;
+        let delta = if bit { self.step } else { -self.step };
+        self.integrator = (leaked + delta).clamp(i32::from(i16::MIN), i32::from(i16::MAX));
+        self.integrator as i16
+    }
+}
+
+/// CVSD encoder that keeps its state across Signal PDUs of one transmission
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CvsdEncoder {
+    state: CvsdState,
+}
+
+impl Default for CvsdEncoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CvsdEncoder {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            state: CvsdState::new(),
+        }
+    }
+
+    /// Encodes one bit per sample, most significant bit first
+    pub fn encode(&mut self, pcm: &[i16]) -> Vec<u8> {
+        let mut data = vec![0u8; pcm.len().div_ceil(8)];
+        for (index, &sample) in pcm.iter().enumerate() {
+            let bit = i32::from(sample) >= self.state.integrator;
+            self.state.apply(bit);
+            if bit {
+                data[index / 8] |= 0x80 >> (index % 8);
+            }
+        }
+        data
+    }
+}
+
+/// CVSD decoder that keeps its state across Signal PDUs of one transmission
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CvsdDecoder {
+    state: CvsdState,
+}
+
+impl Default for CvsdDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CvsdDecoder {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            state: CvsdState::new(),
+        }
+    }
+
+    /// Decodes `samples` bits, most significant bit first
+    pub fn decode(&mut self, data: &[u8], samples: usize) -> Vec<i16> {
+        (0..samples.min(data.len() * 8))
+            .map(|index| {
+                let bit = data[index / 8] & (0x80 >> (index % 8)) != 0;
+                self.state.apply(bit)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+    fn sine(frequency: f64, sample_rate: f64, amplitude: f64, samples: usize) -> Vec<i16> {
+        (0..samples)
+            .map(|n| {
+                let phase = 2.0 * std::f64::consts::PI * frequency * n as f64 / sample_rate;
+                (amplitude * phase.sin()) as i16
+            })
+            .collect()
+    }
+
+    fn max_error(a: &[i16], b: &[i16]) -> i32 {
+        a.iter()
+            .zip(b)
+            .map(|(&x, &y)| (i32::from(x) - i32::from(y)).abs())
+            .max()
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn mu_law_matches_g711_reference_values() {
+        assert_eq!(mu_law_encode(0), 0xFF);
+        assert_eq!(mu_law_encode(-1), 0x7F);
+        assert_eq!(mu_law_encode(i16::MAX), 0x80);
+        assert_eq!(mu_law_encode(i16::MIN), 0x00);
+        assert_eq!(mu_law_decode(0xFF), 0);
+        assert_eq!(mu_law_decode(0x80), 32_124);
+        assert_eq!(mu_law_decode(0x00), -32_124);
+    }
+
+    #[test]
+    fn a_law_matches_g711_reference_values() {
+        assert_eq!(a_law_encode(0), 0xD5);
+        assert_eq!(a_law_encode(-8), 0x55);
+        assert_eq!(a_law_encode(i16::MAX), 0xAA);
+        assert_eq!(a_law_encode(i16::MIN), 0x2A);
+        assert_eq!(a_law_decode(0xD5), 8);
+        assert_eq!(a_law_decode(0xAA), 32_256);
+        assert_eq!(a_law_decode(0x2A), -32_256);
+    }
+
+    #[test]
+    fn companded_codecs_stay_within_quantization_error() {
+        let pcm = sine(440.0, 8_000.0, 20_000.0, 400);
+        for codec in [AudioCodec::MuLaw, AudioCodec::ALaw] {
+            let decoded = codec.decode(&codec.encode(&pcm), pcm.len());
+            assert_eq!(decoded.len(), pcm.len());
+            for (&original, &restored) in pcm.iter().zip(&decoded) {
+                let tolerance = (i32::from(original).abs() / 16).max(16);
+                assert!(
+                    (i32::from(original) - i32::from(restored)).abs() <= tolerance,
+                    "{codec:?}: {original} decoded as {restored}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn linear_pcm_round_trips() {
+        let pcm = vec![0, 1, -1, 12_345, i16::MIN, i16::MAX];
+        for codec in [AudioCodec::Pcm16BigEndian, AudioCodec::Pcm16LittleEndian] {
+            assert_eq!(codec.decode(&codec.encode(&pcm), pcm.len()), pcm);
+        }
+        assert_eq!(
+            AudioCodec::Pcm16BigEndian.encode(&[0x0102]),
+            vec![0x01, 0x02]
+        );
+        assert_eq!(
+            AudioCodec::Pcm16LittleEndian.encode(&[0x0102]),
+            vec![0x02, 0x01]
+        );
+        assert_eq!(
+            AudioCodec::Pcm8Unsigned.decode(&AudioCodec::Pcm8Unsigned.encode(&[256, -512]), 2),
+            vec![256, -512]
+        );
+    }
+
+    #[test]
+    fn cvsd_tracks_a_voice_band_tone() {
+        let pcm = sine(300.0, 32_000.0, 8_000.0, 3_200);
+        let data = AudioCodec::Cvsd.encode(&pcm);
+        assert_eq!(data.len(), 400);
+
+        let decoded = AudioCodec::Cvsd.decode(&data, pcm.len());
+        assert_eq!(decoded.len(), pcm.len());
+        // Skip the start-up while the step size adapts
+        assert!(max_error(&pcm[320..], &decoded[320..]) < 1_500);
+    }
+
+    #[test]
+    fn cvsd_state_carries_across_frames() {
+        let pcm = sine(300.0, 32_000.0, 8_000.0, 640);
+        let mut encoder = CvsdEncoder::new();
+        let mut decoder = CvsdDecoder::new();
+        let mut restored = Vec::new();
+        for frame in pcm.chunks(160) {
+            let data = encoder.encode(frame);
+            restored.extend(decoder.decode(&data, frame.len()));
+        }
+        assert_eq!(
+            restored,
+            AudioCodec::Cvsd.decode(&AudioCodec::Cvsd.encode(&pcm), 640)
+        );
+    }
+}
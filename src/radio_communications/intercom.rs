@@ -0,0 +1,936 @@
+//     open-dis-rust - Rust implementation of the IEEE 1278.1-2012 Distributed Interactive
+//                     Simulation (DIS) application protocol
+//     Copyright (C) 2025 Cameron Howell
+//
+//     Licensed under the BSD 2-Clause License
+
+//! The intercom control protocol of IEEE 1278.1-2012 §5.8.7
+//!
+//! An [`IntercomSession`] runs one local intercom. Local actions (power, connect, disconnect,
+//! keying a line) are validated against the state of its lines and return the Intercom Control
+//! PDU to send. Received Intercom Control PDUs are handed to [`IntercomSession::handle`], which
+//! updates the lines and channels and returns the acknowledgements to send. The session does no
+//! I/O of its own.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{
+    common::{
+        data_types::{
+            entity_id::EntityId,
+            intercom_communications_parameters::{
+                IntercomCommunicationsParameters, SpecificDestination,
+            },
+        },
+        dis_error::DISError,
+        enums::{
+            IntercomControlCommand, IntercomControlCommunicationsType, IntercomControlControlType,
+            IntercomControlDestinationLineStateCommand, IntercomControlTransmitLineState,
+        },
+    },
+    radio_communications::{IntercomControlPdu, IntercomSignalPdu},
+};
+
+/// Identifies an intercom by the entity or object it is attached to and its intercom number
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct IntercomId {
+    pub reference_id: EntityId,
+    pub number: u16,
+}
+
+impl IntercomId {
+    #[must_use]
+    pub const fn new(reference_id: EntityId, number: u16) -> Self {
+        Self {
+            reference_id,
+            number,
+        }
+    }
+}
+
+/// Identifies a line of an intercom
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct LineAddress {
+    pub intercom: IntercomId,
+    pub line_id: u8,
+}
+
+impl LineAddress {
+    #[must_use]
+    pub const fn new(intercom: IntercomId, line_id: u8) -> Self {
+        Self { intercom, line_id }
+    }
+}
+
+/// Identifies a communications channel by its master intercom and channel number
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ChannelId {
+    pub master: IntercomId,
+    pub channel_id: u16,
+}
+
+impl ChannelId {
+    #[must_use]
+    pub const fn new(master: IntercomId, channel_id: u16) -> Self {
+        Self { master, channel_id }
+    }
+}
+
+/// The connection state of a local line
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum LineState {
+    #[default]
+    Idle,
+    /// A connect request requiring acknowledgement has been sent and not yet answered
+    Connecting(ChannelId),
+    Connected(ChannelId),
+}
+
+impl LineState {
+    /// The channel the line is connected or connecting to
+    #[must_use]
+    pub const fn channel(&self) -> Option<ChannelId> {
+        match self {
+            Self::Idle => None,
+            Self::Connecting(channel) | Self::Connected(channel) => Some(*channel),
+        }
+    }
+}
+
+/// A line of the local intercom
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Line {
+    pub state: LineState,
+    pub transmit_line_state: IntercomControlTransmitLineState,
+    pub priority: u8,
+    /// Whether the line may transmit on its channel
+    pub can_transmit: bool,
+    /// Whether the line may receive on its channel
+    pub can_receive: bool,
+}
+
+/// A remote line taking part in a channel
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Participant {
+    pub transmit_line_state: IntercomControlTransmitLineState,
+    pub priority: u8,
+}
+
+/// A communications channel a local line takes part in
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Channel {
+    pub communications_type: IntercomControlCommunicationsType,
+    pub participants: HashMap<LineAddress, Participant>,
+}
+
+impl Channel {
+    const fn is_half_duplex(&self) -> bool {
+        !matches!(
+            self.communications_type,
+            IntercomControlCommunicationsType::ConnectionFDX
+        )
+    }
+}
+
+/// Which end of a connection a line is
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Role {
+    Source,
+    Destination,
+}
+
+/// Whether a line may transmit and receive on a channel of the given type
+const fn permissions(
+    communications_type: IntercomControlCommunicationsType,
+    role: Role,
+) -> (bool, bool) {
+    match (communications_type, role) {
+        (
+            IntercomControlCommunicationsType::ConnectionHDXDestinationisReceiveOnly,
+            Role::Source,
+        )
+        | (
+            IntercomControlCommunicationsType::ConnectionHDXDestinationisTransmitOnly,
+            Role::Destination,
+        ) => (true, false),
+        (
+            IntercomControlCommunicationsType::ConnectionHDXDestinationisReceiveOnly,
+            Role::Destination,
+        )
+        | (
+            IntercomControlCommunicationsType::ConnectionHDXDestinationisTransmitOnly,
+            Role::Source,
+        ) => (false, true),
+        _ => (true, true),
+    }
+}
+
+fn state_error(command: IntercomControlCommand, reason: &str) -> DISError {
+    DISError::invalid_field(
+        "command".to_string(),
+        format!("{command:?}"),
+        reason.to_string(),
+    )
+}
+
+/// Runs the intercom control protocol for one local intercom
+#[derive(Clone, Debug)]
+pub struct IntercomSession {
+    local: IntercomId,
+    powered: bool,
+    lines: BTreeMap<u8, Line>,
+    channels: HashMap<ChannelId, Channel>,
+}
+
+impl IntercomSession {
+    /// A powered off intercom without lines in use
+    #[must_use]
+    pub fn new(local: IntercomId) -> Self {
+        Self {
+            local,
+            powered: false,
+            lines: BTreeMap::new(),
+            channels: HashMap::new(),
+        }
+    }
+
+    #[must_use]
+    pub const fn local(&self) -> IntercomId {
+        self.local
+    }
+
+    #[must_use]
+    pub const fn is_powered(&self) -> bool {
+        self.powered
+    }
+
+    /// The local line `line_id`; lines that were never used are idle
+    #[must_use]
+    pub fn line(&self, line_id: u8) -> Line {
+        self.lines.get(&line_id).copied().unwrap_or_default()
+    }
+
+    #[must_use]
+    pub fn channel(&self, channel: &ChannelId) -> Option<&Channel> {
+        self.channels.get(channel)
+    }
+
+    /// Turns the intercom on
+    pub fn power_on(&mut self) -> IntercomControlPdu {
+        self.powered = true;
+        self.control_pdu(
+            0,
+            IntercomControlControlType::Status,
+            IntercomControlCommand::On,
+            None,
+        )
+    }
+
+    /// Turns the intercom off, dropping every connection
+    pub fn power_off(&mut self) -> IntercomControlPdu {
+        self.powered = false;
+        self.lines.clear();
+        self.channels.clear();
+        self.control_pdu(
+            0,
+            IntercomControlControlType::Status,
+            IntercomControlCommand::Off,
+            None,
+        )
+    }
+
+    /// Connects local line `line_id` to `destination` on `channel`
+    ///
+    /// When `acknowledge` is set the line stays connecting until the destination grants or
+    /// denies the request; otherwise it is connected at once.
+    ///
+    /// # Errors
+    /// Returns `DISError::InvalidFieldValue` if the intercom is off or the line is in use.
+    pub fn connect(
+        &mut self,
+        line_id: u8,
+        channel: ChannelId,
+        communications_type: IntercomControlCommunicationsType,
+        destination: LineAddress,
+        priority: u8,
+        acknowledge: bool,
+    ) -> Result<IntercomControlPdu, DISError> {
+        let command = IntercomControlCommand::Connect;
+        if !self.powered {
+            return Err(state_error(command, "the intercom is off"));
+        }
+        if self.line(line_id).state != LineState::Idle {
+            return Err(state_error(command, "the line is already in use"));
+        }
+
+        let (can_transmit, can_receive) = permissions(communications_type, Role::Source);
+        let line = Line {
+            state: if acknowledge {
+                LineState::Connecting(channel)
+            } else {
+                LineState::Connected(channel)
+            },
+            transmit_line_state: IntercomControlTransmitLineState::NotTransmitting,
+            priority,
+            can_transmit,
+            can_receive,
+        };
+        self.lines.insert(line_id, line);
+        let entry = self.channels.entry(channel).or_default();
+        entry.communications_type = communications_type;
+        if !acknowledge {
+            entry
+                .participants
+                .insert(destination, Participant::default());
+        }
+
+        let control_type = if acknowledge {
+            IntercomControlControlType::RequestAcknowledgeRequired
+        } else {
+            IntercomControlControlType::RequestNoAcknowledge
+        };
+        let mut pdu = self.control_pdu(line_id, control_type, command, Some(channel));
+        pdu.intercom_parameters = vec![IntercomCommunicationsParameters::SpecificDestination(
+            SpecificDestination::new(
+                destination.intercom.reference_id,
+                destination.intercom.number,
+                destination.line_id,
+                priority,
+                IntercomControlDestinationLineStateCommand::None,
+            ),
+        )];
+        Ok(pdu)
+    }
+
+    /// Disconnects local line `line_id` from its channel, addressing the lines it talks to
+    ///
+    /// # Errors
+    /// Returns `DISError::InvalidFieldValue` if the line is not connected or connecting.
+    pub fn disconnect(&mut self, line_id: u8) -> Result<IntercomControlPdu, DISError> {
+        let command = IntercomControlCommand::Disconnect;
+        let Some(channel) = self.line(line_id).state.channel() else {
+            return Err(state_error(command, "the line is not connected"));
+        };
+        let mut pdu = self.control_pdu(
+            line_id,
+            IntercomControlControlType::RequestNoAcknowledge,
+            command,
+            Some(channel),
+        );
+        if let Some(entry) = self.channels.get(&channel) {
+            let priority = self.line(line_id).priority;
+            pdu.intercom_parameters = entry
+                .participants
+                .keys()
+                .map(|address| {
+                    IntercomCommunicationsParameters::SpecificDestination(SpecificDestination::new(
+                        address.intercom.reference_id,
+                        address.intercom.number,
+                        address.line_id,
+                        priority,
+                        IntercomControlDestinationLineStateCommand::None,
+                    ))
+                })
+                .collect();
+        }
+        self.release(line_id);
+        Ok(pdu)
+    }
+
+    /// Starts or stops transmitting on local line `line_id`
+    ///
+    /// # Errors
+    /// Returns `DISError::InvalidFieldValue` if the line is not connected, may not transmit, or
+    /// shares a half duplex channel with a line that is already transmitting.
+    pub fn set_transmitting(
+        &mut self,
+        line_id: u8,
+        transmitting: bool,
+    ) -> Result<IntercomControlPdu, DISError> {
+        let command = IntercomControlCommand::Status;
+        let line = self.line(line_id);
+        let LineState::Connected(channel) = line.state else {
+            return Err(state_error(command, "the line is not connected"));
+        };
+        if transmitting {
+            if !line.can_transmit {
+                return Err(state_error(command, "the line may only receive"));
+            }
+            let busy = self.channels.get(&channel).is_some_and(|channel| {
+                channel.is_half_duplex()
+                    && channel.participants.values().any(|participant| {
+                        participant.transmit_line_state
+                            == IntercomControlTransmitLineState::Transmitting
+                    })
+            });
+            if busy {
+                return Err(state_error(command, "another line is transmitting"));
+            }
+        }
+
+        if let Some(line) = self.lines.get_mut(&line_id) {
+            line.transmit_line_state = if transmitting {
+                IntercomControlTransmitLineState::Transmitting
+            } else {
+                IntercomControlTransmitLineState::NotTransmitting
+            };
+        }
+        Ok(self.status(line_id))
+    }
+
+    /// The status of local line `line_id`, to be sent on changes and as a heartbeat
+    #[must_use]
+    pub fn status(&self, line_id: u8) -> IntercomControlPdu {
+        let channel = self.line(line_id).state.channel();
+        self.control_pdu(
+            line_id,
+            IntercomControlControlType::Status,
+            IntercomControlCommand::Status,
+            channel,
+        )
+    }
+
+    /// Applies a received Intercom Control PDU, returning the responses to send
+    ///
+    /// # Errors
+    /// Returns `DISError::InvalidFieldValue` if the PDU answers a request this intercom did
+    /// not make.
+    pub fn handle(
+        &mut self,
+        pdu: &IntercomControlPdu,
+    ) -> Result<Vec<IntercomControlPdu>, DISError> {
+        let source = LineAddress::new(
+            IntercomId::new(pdu.source_intercom_reference_id, pdu.source_intercom_number),
+            pdu.source_line_id,
+        );
+        if source.intercom == self.local {
+            return Ok(Vec::new());
+        }
+        let channel = ChannelId::new(
+            IntercomId::new(pdu.master_intercom_reference_id, pdu.master_intercom_number),
+            pdu.master_channel_id,
+        );
+        let addressed: Vec<SpecificDestination> = pdu
+            .specific_destinations()
+            .filter(|destination| {
+                IntercomId::new(
+                    destination.destination_intercom_reference_id,
+                    destination.destination_intercom_number,
+                ) == self.local
+            })
+            .copied()
+            .collect();
+
+        match (pdu.control_type, pdu.command) {
+            (
+                IntercomControlControlType::RequestAcknowledgeRequired
+                | IntercomControlControlType::RequestNoAcknowledge,
+                IntercomControlCommand::Connect,
+            ) => Ok(self.accept_connect(pdu, source, channel, &addressed)),
+            (
+                IntercomControlControlType::RequestAcknowledgeRequired
+                | IntercomControlControlType::RequestNoAcknowledge,
+                IntercomControlCommand::Disconnect,
+            ) => Ok(self.accept_disconnect(pdu, source, channel, &addressed)),
+            (
+                control_type @ (IntercomControlControlType::AckRequestGranted
+                | IntercomControlControlType::NackRequestDenied),
+                IntercomControlCommand::Connect,
+            ) => {
+                self.complete_connect(
+                    control_type == IntercomControlControlType::AckRequestGranted,
+                    source,
+                    channel,
+                    &addressed,
+                )?;
+                Ok(Vec::new())
+            }
+            (_, IntercomControlCommand::Status) => {
+                self.update_participant(pdu, source, channel);
+                self.apply_line_state_commands(&addressed);
+                Ok(Vec::new())
+            }
+            (_, IntercomControlCommand::Off | IntercomControlCommand::Reset) => {
+                for channel in self.channels.values_mut() {
+                    channel
+                        .participants
+                        .retain(|address, _| address.intercom != source.intercom);
+                }
+                Ok(Vec::new())
+            }
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    /// The local lines that audio from `signal` is to be played on
+    #[must_use]
+    pub fn route_signal(&self, signal: &IntercomSignalPdu) -> Vec<u8> {
+        let talker = IntercomId::new(signal.intercom_reference_id, signal.intercom_number);
+        self.lines
+            .iter()
+            .filter(|(_, line)| line.can_receive)
+            .filter_map(|(line_id, line)| match line.state {
+                LineState::Connected(channel) => Some((*line_id, channel)),
+                _ => None,
+            })
+            .filter(|(_, channel)| {
+                self.channels.get(channel).is_some_and(|channel| {
+                    channel.participants.iter().any(|(address, participant)| {
+                        address.intercom == talker
+                            && participant.transmit_line_state
+                                != IntercomControlTransmitLineState::NotTransmitting
+                    })
+                })
+            })
+            .map(|(line_id, _)| line_id)
+            .collect()
+    }
+
+    fn accept_connect(
+        &mut self,
+        pdu: &IntercomControlPdu,
+        source: LineAddress,
+        channel: ChannelId,
+        addressed: &[SpecificDestination],
+    ) -> Vec<IntercomControlPdu> {
+        let acknowledge =
+            pdu.control_type == IntercomControlControlType::RequestAcknowledgeRequired;
+        let communications_type = pdu.communications_type();
+        let mut responses = Vec::new();
+
+        for destination in addressed {
+            let line_id = destination.destination_line_id;
+            let available = self.powered
+                && self
+                    .line(line_id)
+                    .state
+                    .channel()
+                    .is_none_or(|current| current == channel);
+            if available {
+                let (can_transmit, can_receive) =
+                    permissions(communications_type, Role::Destination);
+                self.lines.insert(
+                    line_id,
+                    Line {
+                        state: LineState::Connected(channel),
+                        transmit_line_state: IntercomControlTransmitLineState::NotTransmitting,
+                        priority: destination.destination_priority,
+                        can_transmit,
+                        can_receive,
+                    },
+                );
+                let entry = self.channels.entry(channel).or_default();
+                entry.communications_type = communications_type;
+                entry.participants.insert(source, participant_of(pdu));
+            }
+            if acknowledge {
+                let control_type = if available {
+                    IntercomControlControlType::AckRequestGranted
+                } else {
+                    IntercomControlControlType::NackRequestDenied
+                };
+                responses.push(self.response(
+                    line_id,
+                    control_type,
+                    IntercomControlCommand::Connect,
+                    channel,
+                    source,
+                ));
+            }
+        }
+
+        // Another intercom joining a channel a local line is on
+        if addressed.is_empty()
+            && let Some(entry) = self.channels.get_mut(&channel)
+        {
+            entry.participants.insert(source, participant_of(pdu));
+        }
+        responses
+    }
+
+    fn accept_disconnect(
+        &mut self,
+        pdu: &IntercomControlPdu,
+        source: LineAddress,
+        channel: ChannelId,
+        addressed: &[SpecificDestination],
+    ) -> Vec<IntercomControlPdu> {
+        if let Some(entry) = self.channels.get_mut(&channel) {
+            entry.participants.remove(&source);
+        }
+        let mut responses = Vec::new();
+        for destination in addressed {
+            let line_id = destination.destination_line_id;
+            if self.line(line_id).state.channel() == Some(channel) {
+                self.release(line_id);
+            }
+            if pdu.control_type == IntercomControlControlType::RequestAcknowledgeRequired {
+                responses.push(self.response(
+                    line_id,
+                    IntercomControlControlType::AckRequestGranted,
+                    IntercomControlCommand::Disconnect,
+                    channel,
+                    source,
+                ));
+            }
+        }
+        responses
+    }
+
+    fn complete_connect(
+        &mut self,
+        granted: bool,
+        source: LineAddress,
+        channel: ChannelId,
+        addressed: &[SpecificDestination],
+    ) -> Result<(), DISError> {
+        let pending: Vec<u8> = addressed
+            .iter()
+            .map(|destination| destination.destination_line_id)
+            .filter(|line_id| self.line(*line_id).state == LineState::Connecting(channel))
+            .collect();
+        if pending.is_empty() {
+            return Err(state_error(
+                IntercomControlCommand::Connect,
+                "no connect request is pending for this response",
+            ));
+        }
+
+        for line_id in pending {
+            if granted {
+                if let Some(line) = self.lines.get_mut(&line_id) {
+                    line.state = LineState::Connected(channel);
+                }
+                self.channels
+                    .entry(channel)
+                    .or_default()
+                    .participants
+                    .insert(source, Participant::default());
+            } else {
+                self.release(line_id);
+            }
+        }
+        Ok(())
+    }
+
+    fn update_participant(
+        &mut self,
+        pdu: &IntercomControlPdu,
+        source: LineAddress,
+        channel: ChannelId,
+    ) {
+        if let Some(entry) = self.channels.get_mut(&channel) {
+            entry.participants.insert(source, participant_of(pdu));
+        }
+    }
+
+    fn apply_line_state_commands(&mut self, addressed: &[SpecificDestination]) {
+        for destination in addressed {
+            let Some(line) = self.lines.get_mut(&destination.destination_line_id) else {
+                continue;
+            };
+            match destination.destination_line_state_command {
+                IntercomControlDestinationLineStateCommand::SetLineStateTransmitting
+                    if line.can_transmit =>
+                {
+                    line.transmit_line_state = IntercomControlTransmitLineState::Transmitting;
+                }
+                IntercomControlDestinationLineStateCommand::SetLineStateNotTransmitting => {
+                    line.transmit_line_state = IntercomControlTransmitLineState::NotTransmitting;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Returns local line `line_id` to idle, dropping its channel once no line uses it
+    fn release(&mut self, line_id: u8) {
+        let Some(line) = self.lines.remove(&line_id) else {
+            return;
+        };
+        if let Some(channel) = line.state.channel()
+            && !self
+                .lines
+                .values()
+                .any(|other| other.state.channel() == Some(channel))
+        {
+            self.channels.remove(&channel);
+        }
+    }
+
+    fn response(
+        &self,
+        line_id: u8,
+        control_type: IntercomControlControlType,
+        command: IntercomControlCommand,
+        channel: ChannelId,
+        requester: LineAddress,
+    ) -> IntercomControlPdu {
+        let mut pdu = self.control_pdu(line_id, control_type, command, Some(channel));
+        pdu.intercom_parameters = vec![IntercomCommunicationsParameters::SpecificDestination(
+            SpecificDestination::new(
+                requester.intercom.reference_id,
+                requester.intercom.number,
+                requester.line_id,
+                0,
+                IntercomControlDestinationLineStateCommand::None,
+            ),
+        )];
+        pdu
+    }
+
+    fn control_pdu(
+        &self,
+        line_id: u8,
+        control_type: IntercomControlControlType,
+        command: IntercomControlCommand,
+        channel: Option<ChannelId>,
+    ) -> IntercomControlPdu {
+        let line = self.line(line_id);
+        let mut pdu = IntercomControlPdu::new();
+        pdu.control_type = control_type;
+        pdu.command = command;
+        pdu.source_intercom_reference_id = self.local.reference_id;
+        pdu.source_intercom_number = self.local.number;
+        pdu.source_line_id = line_id;
+        pdu.transmit_priority = line.priority;
+        pdu.transmit_line_state = line.transmit_line_state;
+        if let Some(channel) = channel {
+            pdu.master_intercom_reference_id = channel.master.reference_id;
+            pdu.master_intercom_number = channel.master.number;
+            pdu.master_channel_id = channel.channel_id;
+            if let Some(entry) = self.channels.get(&channel) {
+                pdu.set_communications_type(entry.communications_type);
+            }
+        }
+        pdu
+    }
+}
+
+const fn participant_of(pdu: &IntercomControlPdu) -> Participant {
+    Participant {
+        transmit_line_state: pdu.transmit_line_state,
+        priority: pdu.transmit_priority,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::BytesMut;
+
+    use super::*;
+    use crate::common::pdu::Pdu;
+
+    fn intercom(entity: u16) -> IntercomId {
+        IntercomId::new(EntityId::new(1, 1, entity), 1)
+    }
+
+    fn channel() -> ChannelId {
+        ChannelId::new(intercom(1), 7)
+    }
+
+    /// Sends `pdu` over the wire, as the other intercom would receive it
+    fn transmit(pdu: &IntercomControlPdu) -> IntercomControlPdu {
+        let mut buf = BytesMut::new();
+        let _ = pdu.clone().serialize(&mut buf);
+        IntercomControlPdu::deserialize(&mut buf.freeze()).expect("valid PDU")
+    }
+
+    fn powered(entity: u16) -> IntercomSession {
+        let mut session = IntercomSession::new(intercom(entity));
+        let _ = session.power_on();
+        session
+    }
+
+    #[test]
+    fn connect_with_acknowledgement() {
+        let mut requester = powered(1);
+        let mut responder = powered(2);
+
+        let request = requester
+            .connect(
+                1,
+                channel(),
+                IntercomControlCommunicationsType::ConnectionFDX,
+                LineAddress::new(intercom(2), 3),
+                5,
+                true,
+            )
+            .expect("line is idle");
+        assert_eq!(requester.line(1).state, LineState::Connecting(channel()));
+        let request = transmit(&request);
+        assert_eq!(request.intercom_parameters_length, 1);
+
+        let responses = responder.handle(&request).expect("valid request");
+        assert_eq!(responses.len(), 1);
+        assert_eq!(
+            responses[0].control_type,
+            IntercomControlControlType::AckRequestGranted
+        );
+        assert_eq!(responder.line(3).state, LineState::Connected(channel()));
+
+        requester
+            .handle(&transmit(&responses[0]))
+            .expect("answers the pending request");
+        assert_eq!(requester.line(1).state, LineState::Connected(channel()));
+        assert!(requester.channel(&channel()).is_some_and(|channel| {
+            channel
+                .participants
+                .contains_key(&LineAddress::new(intercom(2), 3))
+        }));
+    }
+
+    #[test]
+    fn commands_are_validated_against_line_state() {
+        let mut off = IntercomSession::new(intercom(1));
+        let destination = LineAddress::new(intercom(2), 1);
+        let fdx = IntercomControlCommunicationsType::ConnectionFDX;
+        assert!(
+            off.connect(1, channel(), fdx, destination, 0, false)
+                .is_err()
+        );
+
+        let mut session = powered(1);
+        assert!(session.disconnect(1).is_err());
+        assert!(session.set_transmitting(1, true).is_err());
+        assert!(
+            session
+                .connect(1, channel(), fdx, destination, 0, false)
+                .is_ok()
+        );
+        assert!(
+            session
+                .connect(1, channel(), fdx, destination, 0, false)
+                .is_err()
+        );
+
+        // A grant for a request that was never made
+        let mut grant = powered(3).status(1);
+        grant.control_type = IntercomControlControlType::AckRequestGranted;
+        grant.command = IntercomControlCommand::Connect;
+        assert!(powered(4).handle(&grant).is_err());
+    }
+
+    #[test]
+    fn connect_is_denied_when_the_line_is_busy() {
+        let mut requester = powered(1);
+        let mut responder = powered(2);
+        let other_channel = ChannelId::new(intercom(9), 1);
+        let _ = responder
+            .connect(
+                3,
+                other_channel,
+                IntercomControlCommunicationsType::ConnectionFDX,
+                LineAddress::new(intercom(9), 1),
+                0,
+                false,
+            )
+            .expect("line is idle");
+
+        let request = requester
+            .connect(
+                1,
+                channel(),
+                IntercomControlCommunicationsType::ConnectionFDX,
+                LineAddress::new(intercom(2), 3),
+                0,
+                true,
+            )
+            .expect("line is idle");
+        let responses = responder
+            .handle(&transmit(&request))
+            .expect("valid request");
+        assert_eq!(
+            responses[0].control_type,
+            IntercomControlControlType::NackRequestDenied
+        );
+
+        requester.handle(&transmit(&responses[0])).expect("answers");
+        assert_eq!(requester.line(1).state, LineState::Idle);
+        assert!(requester.channel(&channel()).is_none());
+    }
+
+    #[test]
+    fn half_duplex_lines_take_turns_and_audio_follows_the_talker() {
+        let mut requester = powered(1);
+        let mut responder = powered(2);
+        let request = requester
+            .connect(
+                1,
+                channel(),
+                IntercomControlCommunicationsType::ConnectionHDX,
+                LineAddress::new(intercom(2), 1),
+                0,
+                false,
+            )
+            .expect("line is idle");
+        assert!(
+            responder
+                .handle(&transmit(&request))
+                .expect("valid")
+                .is_empty()
+        );
+
+        let keyed = requester
+            .set_transmitting(1, true)
+            .expect("channel is free");
+        let _ = responder.handle(&transmit(&keyed)).expect("valid status");
+        assert!(responder.set_transmitting(1, true).is_err());
+
+        let mut signal = IntercomSignalPdu::new();
+        signal.intercom_reference_id = intercom(1).reference_id;
+        signal.intercom_number = 1;
+        assert_eq!(responder.route_signal(&signal), vec![1]);
+
+        let released = requester.set_transmitting(1, false).expect("connected");
+        let _ = responder
+            .handle(&transmit(&released))
+            .expect("valid status");
+        assert!(responder.route_signal(&signal).is_empty());
+        assert!(responder.set_transmitting(1, true).is_ok());
+    }
+
+    #[test]
+    fn receive_only_destinations_cannot_transmit() {
+        let mut requester = powered(1);
+        let mut responder = powered(2);
+        let request = requester
+            .connect(
+                1,
+                channel(),
+                IntercomControlCommunicationsType::ConnectionHDXDestinationisReceiveOnly,
+                LineAddress::new(intercom(2), 1),
+                0,
+                false,
+            )
+            .expect("line is idle");
+        let _ = responder.handle(&transmit(&request)).expect("valid");
+        assert!(responder.set_transmitting(1, true).is_err());
+        assert!(requester.set_transmitting(1, true).is_ok());
+    }
+
+    #[test]
+    fn disconnect_releases_both_ends() {
+        let mut requester = powered(1);
+        let mut responder = powered(2);
+        let fdx = IntercomControlCommunicationsType::ConnectionFDX;
+        let request = requester
+            .connect(
+                1,
+                channel(),
+                fdx,
+                LineAddress::new(intercom(2), 1),
+                0,
+                false,
+            )
+            .expect("line is idle");
+        let _ = responder.handle(&transmit(&request)).expect("valid");
+
+        let disconnect = requester.disconnect(1).expect("connected");
+        assert_eq!(disconnect.intercom_parameters, request.intercom_parameters);
+        let _ = responder.handle(&transmit(&disconnect)).expect("valid");
+        assert_eq!(requester.line(1).state, LineState::Idle);
+        assert_eq!(responder.line(1).state, LineState::Idle);
+        assert!(responder.channel(&channel()).is_none());
+    }
+}
@@ -0,0 +1,540 @@
+//     open-dis-rust - Rust implementation of the IEEE 1278.1-2012 Distributed Interactive
+//                     Simulation (DIS) application protocol
+//     Copyright (C) 2025 Cameron Howell
+//
+//     Licensed under the BSD 2-Clause License
+
+//! Link 16 simulation payload of Signal PDUs, according to SISO-STD-002
+//!
+//! A Signal PDU with the TDL type `Link16StandardizedFormat` carries a 20-octet Link 16
+//! network header followed by the message data. For JTIDS header messages the message data
+//! is the 35-bit JTIDS header word followed by 75-bit message words (70 data bits and 5
+//! parity bits). The words are packed back to back in transmission order, bit 0 of each
+//! word first, filling each octet from its most significant bit. The Signal PDU pads the
+//! data to a 32-bit boundary, and its `data_length` gives the number of bits in use.
+//!
+//! The radio carrying the traffic describes its terminal with
+//! [`JtidsMidsModulationParameters`](crate::common::data_types::modulation_parameters::JtidsMidsModulationParameters)
+//! in its Transmitter PDU.
+
+use bytes::{Buf, BufMut, BytesMut};
+
+use crate::common::{SerializedLength, dis_error::DISError};
+
+/// Number of bits in the JTIDS header word
+pub const JTIDS_HEADER_BITS: usize = 35;
+/// Number of bits in a Link 16 message word, including parity
+pub const LINK16_WORD_BITS: usize = 75;
+/// Number of data bits in a Link 16 message word
+pub const LINK16_WORD_DATA_BITS: usize = 70;
+
+/// Mask of the bits of a Link 16 message word
+const WORD_MASK: u128 = (1 << LINK16_WORD_BITS) - 1;
+/// Mask of the data bits of a Link 16 message word
+const WORD_DATA_MASK: u128 = (1 << LINK16_WORD_DATA_BITS) - 1;
+
+/// Type of the message data following the Link 16 network header
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Link16MessageType {
+    #[default]
+    JtidsHeaderMessages,
+    RoundTripTimingAB,
+    RoundTripTimingReply,
+    JtidsVoiceCvsd,
+    JtidsVoiceLpc10,
+    JtidsVoiceLpc12,
+    JtidsLet,
+    Vmf,
+    /// A message type this crate does not know
+    Other(u8),
+}
+
+impl Link16MessageType {
+    #[must_use]
+    pub const fn from_u8(value: u8) -> Self {
+        match value {
+            0 => Self::JtidsHeaderMessages,
+            1 => Self::RoundTripTimingAB,
+            2 => Self::RoundTripTimingReply,
+            3 => Self::JtidsVoiceCvsd,
+            4 => Self::JtidsVoiceLpc10,
+            5 => Self::JtidsVoiceLpc12,
+            6 => Self::JtidsLet,
+            7 => Self::Vmf,
+            other => Self::Other(other),
+        }
+    }
+
+    #[must_use]
+    pub const fn to_u8(&self) -> u8 {
+        match self {
+            Self::JtidsHeaderMessages => 0,
+            Self::RoundTripTimingAB => 1,
+            Self::RoundTripTimingReply => 2,
+            Self::JtidsVoiceCvsd => 3,
+            Self::JtidsVoiceLpc10 => 4,
+            Self::JtidsVoiceLpc12 => 5,
+            Self::JtidsLet => 6,
+            Self::Vmf => 7,
+            Self::Other(other) => *other,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+/// The Link 16 network header that starts the data of a Link 16 Signal PDU
+pub struct Link16NetworkHeader {
+    /// Network Participation Group
+    pub npg_number: u16,
+    pub net_number: u8,
+    /// Transmission security crypto variable logical label
+    pub tsec_cvll: u8,
+    /// Message security crypto variable logical label
+    pub msec_cvll: u8,
+    pub message_type: Link16MessageType,
+    padding: u16,
+    pub time_slot_id: u32,
+    /// Perceived transmit time, in the format of the PDU header timestamp extended to 64 bits
+    pub perceived_transmit_time: u64,
+}
+
+impl Link16NetworkHeader {
+    #[must_use]
+    pub const fn new(
+        npg_number: u16,
+        net_number: u8,
+        message_type: Link16MessageType,
+        time_slot_id: u32,
+    ) -> Self {
+        Self {
+            npg_number,
+            net_number,
+            tsec_cvll: 0,
+            msec_cvll: 0,
+            message_type,
+            padding: 0,
+            time_slot_id,
+            perceived_transmit_time: 0,
+        }
+    }
+
+    pub fn serialize(&self, buf: &mut BytesMut) {
+        buf.put_u16(self.npg_number);
+        buf.put_u8(self.net_number);
+        buf.put_u8(self.tsec_cvll);
+        buf.put_u8(self.msec_cvll);
+        buf.put_u8(self.message_type.to_u8());
+        buf.put_u16(self.padding);
+        buf.put_u32(self.time_slot_id);
+        buf.put_u64(self.perceived_transmit_time);
+    }
+
+    pub fn deserialize<B: Buf>(buf: &mut B) -> Self {
+        Self {
+            npg_number: buf.get_u16(),
+            net_number: buf.get_u8(),
+            tsec_cvll: buf.get_u8(),
+            msec_cvll: buf.get_u8(),
+            message_type: Link16MessageType::from_u8(buf.get_u8()),
+            padding: buf.get_u16(),
+            time_slot_id: buf.get_u32(),
+            perceived_transmit_time: buf.get_u64(),
+        }
+    }
+}
+
+impl SerializedLength for Link16NetworkHeader {
+    const LENGTH: usize = 20;
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+/// The 35-bit JTIDS header word that precedes the message words of a time slot
+pub struct JtidsHeader {
+    /// Packing of the time slot, 3 bits
+    pub time_slot_type: u8,
+    pub relay_transmission: bool,
+    /// Track number of the transmitting unit, 15 bits
+    pub source_track_number: u16,
+    pub secure_data_unit_serial_number: u16,
+}
+
+impl JtidsHeader {
+    /// Packs the header into its 35 bits, bit 0 being the first transmitted
+    #[must_use]
+    pub fn to_bits(&self) -> u64 {
+        u64::from(self.time_slot_type & 0x07)
+            | u64::from(self.relay_transmission) << 3
+            | u64::from(self.source_track_number & 0x7FFF) << 4
+            | u64::from(self.secure_data_unit_serial_number) << 19
+    }
+
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub const fn from_bits(bits: u64) -> Self {
+        Self {
+            time_slot_type: (bits & 0x07) as u8,
+            relay_transmission: bits >> 3 & 1 == 1,
+            source_track_number: (bits >> 4 & 0x7FFF) as u16,
+            secure_data_unit_serial_number: (bits >> 19) as u16,
+        }
+    }
+}
+
+/// A 75-bit Link 16 message word: 70 data bits followed by 5 parity bits
+///
+/// Bit 0 is the first transmitted bit. The accessors for the word format, label, sublabel
+/// and message length indicator apply to J-series initial words.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Link16Word(u128);
+
+impl Link16Word {
+    /// A word from its 75 bits; higher bits are ignored
+    #[must_use]
+    pub const fn new(bits: u128) -> Self {
+        Self(bits & WORD_MASK)
+    }
+
+    /// A word from its 70 data bits and 5 parity bits
+    #[must_use]
+    pub const fn from_data(data: u128, parity: u8) -> Self {
+        Self((data & WORD_DATA_MASK) | ((parity as u128) & 0x1F) << LINK16_WORD_DATA_BITS)
+    }
+
+    #[must_use]
+    pub const fn bits(&self) -> u128 {
+        self.0
+    }
+
+    #[must_use]
+    pub const fn data(&self) -> u128 {
+        self.0 & WORD_DATA_MASK
+    }
+
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub const fn parity(&self) -> u8 {
+        (self.0 >> LINK16_WORD_DATA_BITS) as u8
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    const fn field(&self, offset: u32, width: u32) -> u8 {
+        (self.0 >> offset & ((1 << width) - 1)) as u8
+    }
+
+    /// Word format, bits 0-1: 0 for an initial word, 1 for an extension word and 2 for a
+    /// continuation word
+    #[must_use]
+    pub const fn word_format(&self) -> u8 {
+        self.field(0, 2)
+    }
+
+    #[must_use]
+    pub const fn is_initial_word(&self) -> bool {
+        self.word_format() == 0
+    }
+
+    /// Label of an initial word, bits 2-6
+    #[must_use]
+    pub const fn label(&self) -> u8 {
+        self.field(2, 5)
+    }
+
+    /// Sublabel of an initial word, bits 7-9
+    #[must_use]
+    pub const fn sublabel(&self) -> u8 {
+        self.field(7, 3)
+    }
+
+    /// Number of words following an initial word in the same message, bits 10-12
+    #[must_use]
+    pub const fn message_length_indicator(&self) -> u8 {
+        self.field(10, 3)
+    }
+
+    /// Label of a continuation word, bits 2-6
+    #[must_use]
+    pub const fn continuation_label(&self) -> u8 {
+        self.field(2, 5)
+    }
+
+    /// The J-series designation of an initial word, such as `J3.2`
+    #[must_use]
+    pub fn j_series(&self) -> Option<String> {
+        self.is_initial_word()
+            .then(|| format!("J{}.{}", self.label(), self.sublabel()))
+    }
+}
+
+/// The message data following the Link 16 network header
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Link16MessageData {
+    /// A JTIDS header word and the message words of the time slot
+    Jtids {
+        header: JtidsHeader,
+        words: Vec<Link16Word>,
+    },
+    /// Message data of a type this crate does not decode, kept verbatim with its bit length
+    Raw { data: Vec<u8>, bits: usize },
+}
+
+/// A Link 16 simulation payload: the network header and its message data
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Link16Message {
+    pub network_header: Link16NetworkHeader,
+    pub data: Link16MessageData,
+}
+
+impl Link16Message {
+    /// A JTIDS header message carrying `words`
+    #[must_use]
+    pub const fn jtids(
+        network_header: Link16NetworkHeader,
+        header: JtidsHeader,
+        words: Vec<Link16Word>,
+    ) -> Self {
+        Self {
+            network_header: Link16NetworkHeader {
+                message_type: Link16MessageType::JtidsHeaderMessages,
+                ..network_header
+            },
+            data: Link16MessageData::Jtids { header, words },
+        }
+    }
+
+    /// Number of bits this payload occupies in the `data` field of a Signal PDU
+    #[must_use]
+    pub const fn bit_length(&self) -> usize {
+        Link16NetworkHeader::LENGTH * 8
+            + match &self.data {
+                Link16MessageData::Jtids { words, .. } => {
+                    JTIDS_HEADER_BITS + words.len() * LINK16_WORD_BITS
+                }
+                Link16MessageData::Raw { bits, .. } => *bits,
+            }
+    }
+
+    /// The octets of the payload, with the last octet padded with zero bits
+    #[must_use]
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = BytesMut::with_capacity(self.bit_length().div_ceil(8));
+        self.network_header.serialize(&mut buf);
+        match &self.data {
+            Link16MessageData::Jtids { header, words } => {
+                let mut writer = BitWriter::default();
+                writer.write(u128::from(header.to_bits()), JTIDS_HEADER_BITS);
+                for word in words {
+                    writer.write(word.bits(), LINK16_WORD_BITS);
+                }
+                buf.put_slice(&writer.octets);
+            }
+            Link16MessageData::Raw { data, bits } => {
+                buf.put_slice(&data[..bits.div_ceil(8).min(data.len())]);
+            }
+        }
+        buf.to_vec()
+    }
+
+    /// Decodes the first `bits` bits of `data`
+    ///
+    /// JTIDS header messages are split into their header and message words; trailing bits
+    /// that do not make up a whole word are ignored. Other message types are kept raw.
+    ///
+    /// # Errors
+    /// Returns `DISError::BufferUnderflow` if `data` is shorter than `bits` or than the
+    /// network header, and `DISError::InvalidFieldValue` if a JTIDS header message has no
+    /// room for its JTIDS header word.
+    pub fn decode(data: &[u8], bits: usize) -> Result<Self, DISError> {
+        let octets = bits.div_ceil(8);
+        if data.len() < octets || bits < Link16NetworkHeader::LENGTH * 8 {
+            return Err(DISError::buffer_underflow(
+                octets.max(Link16NetworkHeader::LENGTH),
+                data.len(),
+            ));
+        }
+
+        let mut buf = data;
+        let network_header = Link16NetworkHeader::deserialize(&mut buf);
+        let message_bits = bits - Link16NetworkHeader::LENGTH * 8;
+        let message_data = match network_header.message_type {
+            Link16MessageType::JtidsHeaderMessages => {
+                if message_bits < JTIDS_HEADER_BITS {
+                    return Err(DISError::invalid_field(
+                        "data_length".to_string(),
+                        bits.to_string(),
+                        "no room for the JTIDS header word".to_string(),
+                    ));
+                }
+                let mut reader = BitReader::new(buf);
+                #[allow(clippy::cast_possible_truncation)]
+                let header = JtidsHeader::from_bits(reader.read(JTIDS_HEADER_BITS) as u64);
+                let word_count = (message_bits - JTIDS_HEADER_BITS) / LINK16_WORD_BITS;
+                let words = (0..word_count)
+                    .map(|_| Link16Word::new(reader.read(LINK16_WORD_BITS)))
+                    .collect();
+                Link16MessageData::Jtids { header, words }
+            }
+            _ => Link16MessageData::Raw {
+                data: buf[..message_bits.div_ceil(8)].to_vec(),
+                bits: message_bits,
+            },
+        };
+
+        Ok(Self {
+            network_header,
+            data: message_data,
+        })
+    }
+}
+
+/// Writes bit fields in transmission order, least significant bit of each field first,
+/// filling octets from their most significant bit
+#[derive(Default)]
+struct BitWriter {
+    octets: Vec<u8>,
+    bit_count: usize,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u128, width: usize) {
+        for bit in 0..width {
+            if self.bit_count.is_multiple_of(8) {
+                self.octets.push(0);
+            }
+            if value >> bit & 1 == 1
+                && let Some(last) = self.octets.last_mut()
+            {
+                *last |= 0x80 >> (self.bit_count % 8);
+            }
+            self.bit_count += 1;
+        }
+    }
+}
+
+/// Reads the bit fields written by [`BitWriter`]
+struct BitReader<'a> {
+    octets: &'a [u8],
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    const fn new(octets: &'a [u8]) -> Self {
+        Self {
+            octets,
+            position: 0,
+        }
+    }
+
+    fn read(&mut self, width: usize) -> u128 {
+        let mut value = 0;
+        for bit in 0..width {
+            let octet = self.octets.get(self.position / 8).copied().unwrap_or(0);
+            if octet & (0x80 >> (self.position % 8)) != 0 {
+                value |= 1 << bit;
+            }
+            self.position += 1;
+        }
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn j3_2() -> Link16Word {
+        // Initial word, label 3, sublabel 2, two words follow
+        Link16Word::from_data(3 << 2 | 2 << 7 | 2 << 10 | 0x2_AAAA << 40, 0x15)
+    }
+
+    #[test]
+    fn word_fields_follow_the_j_series_layout() {
+        let word = j3_2();
+        assert!(word.is_initial_word());
+        assert_eq!(word.label(), 3);
+        assert_eq!(word.sublabel(), 2);
+        assert_eq!(word.message_length_indicator(), 2);
+        assert_eq!(word.parity(), 0x15);
+        assert_eq!(word.j_series().as_deref(), Some("J3.2"));
+
+        let continuation = Link16Word::from_data(2 | 1 << 2, 0);
+        assert_eq!(continuation.word_format(), 2);
+        assert_eq!(continuation.continuation_label(), 1);
+        assert!(continuation.j_series().is_none());
+    }
+
+    #[test]
+    fn jtids_header_packs_into_35_bits() {
+        let header = JtidsHeader {
+            time_slot_type: 5,
+            relay_transmission: true,
+            source_track_number: 0x7ABC,
+            secure_data_unit_serial_number: 0xFFFF,
+        };
+        let bits = header.to_bits();
+        assert!(bits < 1 << JTIDS_HEADER_BITS);
+        assert_eq!(JtidsHeader::from_bits(bits), header);
+    }
+
+    #[test]
+    fn jtids_message_round_trip() {
+        let network_header = Link16NetworkHeader::new(7, 2, Link16MessageType::Vmf, 0x0001_0203);
+        let header = JtidsHeader {
+            time_slot_type: 1,
+            relay_transmission: false,
+            source_track_number: 12_345,
+            secure_data_unit_serial_number: 42,
+        };
+        let words = vec![
+            j3_2(),
+            Link16Word::from_data(2, 3),
+            Link16Word::new(u128::MAX),
+        ];
+        let message = Link16Message::jtids(network_header, header, words);
+        assert_eq!(
+            message.network_header.message_type,
+            Link16MessageType::JtidsHeaderMessages
+        );
+        assert_eq!(message.bit_length(), 160 + 35 + 3 * 75);
+
+        let encoded = message.encode();
+        assert_eq!(encoded.len(), message.bit_length().div_ceil(8));
+        let decoded =
+            Link16Message::decode(&encoded, message.bit_length()).expect("decodes the payload");
+        assert_eq!(decoded, message);
+    }
+
+    #[test]
+    fn other_message_types_are_kept_raw() {
+        let message = Link16Message {
+            network_header: Link16NetworkHeader::new(12, 0, Link16MessageType::JtidsVoiceCvsd, 9),
+            data: Link16MessageData::Raw {
+                data: vec![0xAB, 0xC0],
+                bits: 12,
+            },
+        };
+        let encoded = message.encode();
+        let decoded =
+            Link16Message::decode(&encoded, message.bit_length()).expect("decodes the payload");
+        assert_eq!(decoded, message);
+    }
+
+    #[test]
+    fn short_payloads_are_rejected() {
+        assert!(Link16Message::decode(&[0; 10], 80).is_err());
+        let network_header =
+            Link16NetworkHeader::new(7, 0, Link16MessageType::JtidsHeaderMessages, 0);
+        let mut buf = BytesMut::new();
+        network_header.serialize(&mut buf);
+        buf.put_u16(0);
+        assert!(Link16Message::decode(&buf, 160 + 16).is_err());
+    }
+
+    #[test]
+    fn data_shorter_than_the_network_header_is_rejected() {
+        let data = [0u8; 20];
+        assert!(matches!(
+            Link16Message::decode(&data, 159),
+            Err(DISError::BufferUnderflow { .. })
+        ));
+    }
+}
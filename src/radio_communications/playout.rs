@@ -0,0 +1,560 @@
+//     open-dis-rust - Rust implementation of the IEEE 1278.1-2012 Distributed Interactive
+//                     Simulation (DIS) application protocol
+//     Copyright (C) 2025 Cameron Howell
+//
+//     Licensed under the BSD 2-Clause License
+
+//! Playout of received Signal PDUs
+//!
+//! A [`JitterBuffer`] reorders the Signal PDUs of one transmitter by their DIS timestamp,
+//! holds them for a target delay to absorb network jitter, decodes them and conceals missing
+//! audio. An [`AudioMixer`] keeps a jitter buffer per transmitter and mixes the concurrent
+//! talkers into the PCM played by one receiver.
+//!
+//! Neither does any I/O: Signal PDUs are pushed with the time they were received, and PCM is
+//! pulled by the audio output as it needs it.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    common::{dis_error::DISError, timestamp},
+    radio_communications::{
+        SignalPdu,
+        audio::{AudioCodec, CvsdDecoder},
+        reception::RadioId,
+    },
+};
+
+/// Default time a transmission is buffered before it starts playing
+pub const DEFAULT_TARGET_DELAY: Duration = Duration::from_millis(60);
+
+/// Default longest stretch of missing audio that is concealed before playout stops
+pub const DEFAULT_MAX_CONCEALMENT: Duration = Duration::from_millis(100);
+
+/// Duration of one DIS time unit in nanoseconds, an hour divided into 2^31 units
+const DIS_TIME_UNIT_NANOS: f64 = 3_600_000_000_000.0 / 2_147_483_648.0;
+
+/// How received audio is played out
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PlayoutConfig {
+    /// Sample rate of the produced PCM, in samples per second
+    pub sample_rate: u32,
+    /// Time a transmission is buffered before it starts playing
+    pub target_delay: Duration,
+    /// Longest stretch of missing audio that is concealed
+    pub max_concealment: Duration,
+}
+
+impl PlayoutConfig {
+    #[must_use]
+    pub const fn new(sample_rate: u32) -> Self {
+        Self {
+            sample_rate,
+            target_delay: DEFAULT_TARGET_DELAY,
+            max_concealment: DEFAULT_MAX_CONCEALMENT,
+        }
+    }
+
+    #[must_use]
+    pub const fn with_target_delay(mut self, target_delay: Duration) -> Self {
+        self.target_delay = target_delay;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_max_concealment(mut self, max_concealment: Duration) -> Self {
+        self.max_concealment = max_concealment;
+        self
+    }
+
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    fn samples_in(&self, duration: Duration) -> usize {
+        (duration.as_secs_f64() * f64::from(self.sample_rate)).round() as usize
+    }
+
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    fn samples_in_dis_time_units(&self, units: u32) -> usize {
+        let nanos = f64::from(units) * DIS_TIME_UNIT_NANOS;
+        (nanos * f64::from(self.sample_rate) / 1.0e9).round() as usize
+    }
+}
+
+/// Linearly interpolates `pcm` from one sample rate to another
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    clippy::cast_precision_loss
+)]
+fn resample(pcm: &[i16], from: u32, to: u32) -> Vec<i16> {
+    if from == to || from == 0 || pcm.is_empty() {
+        return pcm.to_vec();
+    }
+    let step = f64::from(from) / f64::from(to);
+    let length = (pcm.len() as f64 / step).round() as usize;
+    (0..length)
+        .map(|index| {
+            let position = index as f64 * step;
+            let base = position as usize;
+            let fraction = position - base as f64;
+            let current = f64::from(pcm[base.min(pcm.len() - 1)]);
+            let next = f64::from(pcm[(base + 1).min(pcm.len() - 1)]);
+            fraction.mul_add(next - current, current).round() as i16
+        })
+        .collect()
+}
+
+/// What became of a Signal PDU pushed into a jitter buffer
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PushOutcome {
+    /// Queued for playout
+    Queued,
+    /// Dropped, as a frame with the same timestamp is already queued
+    Duplicate,
+    /// Dropped, as the audio that follows its timestamp has already been played
+    Late,
+}
+
+#[derive(Clone, Debug)]
+enum FrameAudio {
+    Pcm(Vec<i16>),
+    Cvsd { data: Vec<u8>, samples: usize },
+}
+
+#[derive(Clone, Debug)]
+struct Frame {
+    timestamp: u32,
+    sample_rate: u32,
+    audio: FrameAudio,
+}
+
+/// Reorders, delays, decodes and conceals the Signal PDUs of one transmitter
+#[derive(Clone, Debug)]
+pub struct JitterBuffer {
+    config: PlayoutConfig,
+    frames: VecDeque<Frame>,
+    /// Time the first buffered frame arrived, while not playing
+    buffering_since: Option<Instant>,
+    playing: bool,
+    /// Decoded samples waiting to be played
+    current: VecDeque<i16>,
+    /// Timestamp the next frame is expected to carry
+    next_timestamp: Option<u32>,
+    last_frame: Vec<i16>,
+    concealment_gain: f32,
+    concealed: usize,
+    cvsd: CvsdDecoder,
+}
+
+impl JitterBuffer {
+    #[must_use]
+    pub const fn new(config: PlayoutConfig) -> Self {
+        Self {
+            config,
+            frames: VecDeque::new(),
+            buffering_since: None,
+            playing: false,
+            current: VecDeque::new(),
+            next_timestamp: None,
+            last_frame: Vec::new(),
+            concealment_gain: 1.0,
+            concealed: 0,
+            cvsd: CvsdDecoder::new(),
+        }
+    }
+
+    #[must_use]
+    pub const fn config(&self) -> &PlayoutConfig {
+        &self.config
+    }
+
+    #[must_use]
+    pub const fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    /// Whether the buffer neither plays nor holds any audio
+    #[must_use]
+    pub fn is_idle(&self) -> bool {
+        !self.playing && self.frames.is_empty()
+    }
+
+    /// Number of frames waiting to be played
+    #[must_use]
+    pub fn buffered_frames(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Decodes a Signal PDU and queues it in timestamp order
+    ///
+    /// Duplicates and frames older than the audio already played are dropped, and the
+    /// returned outcome says so.
+    ///
+    /// # Errors
+    /// Returns `DISError::InvalidFieldValue` if the PDU does not carry encoded audio in a
+    /// supported encoding.
+    pub fn push(&mut self, pdu: &SignalPdu, now: Instant) -> Result<PushOutcome, DISError> {
+        let timestamp = pdu.header.timestamp;
+        if self
+            .next_timestamp
+            .is_some_and(|expected| timestamp::precedes(timestamp, expected))
+        {
+            return Ok(PushOutcome::Late);
+        }
+        if self.frames.iter().any(|frame| frame.timestamp == timestamp) {
+            return Ok(PushOutcome::Duplicate);
+        }
+
+        // CVSD is decoded at playout, once the frames are in order, as its decoder is stateful
+        let audio = if pdu.audio_codec() == Some(AudioCodec::Cvsd) {
+            let octets = usize::from(pdu.data_length).div_ceil(8).min(pdu.data.len());
+            FrameAudio::Cvsd {
+                data: pdu.data[..octets].to_vec(),
+                samples: usize::from(pdu.samples),
+            }
+        } else {
+            FrameAudio::Pcm(pdu.decode_audio()?)
+        };
+
+        let position = self
+            .frames
+            .iter()
+            .position(|frame| timestamp::precedes(timestamp, frame.timestamp))
+            .unwrap_or(self.frames.len());
+        self.frames.insert(
+            position,
+            Frame {
+                timestamp,
+                sample_rate: pdu.sample_rate,
+                audio,
+            },
+        );
+        if !self.playing && self.buffering_since.is_none() {
+            self.buffering_since = Some(now);
+        }
+        Ok(PushOutcome::Queued)
+    }
+
+    /// Produces `samples` samples of audio, or `None` while there is nothing to play
+    pub fn pull(&mut self, now: Instant, samples: usize) -> Option<Vec<i16>> {
+        if !self.playing {
+            let since = self.buffering_since?;
+            if now.saturating_duration_since(since) < self.config.target_delay {
+                return None;
+            }
+            self.playing = true;
+            self.buffering_since = None;
+        }
+
+        let mut output = Vec::with_capacity(samples);
+        while output.len() < samples {
+            if self.current.is_empty() && !self.refill() {
+                self.stop(now);
+                break;
+            }
+            let take = (samples - output.len()).min(self.current.len());
+            output.extend(self.current.drain(..take));
+        }
+        output.resize(samples, 0);
+        Some(output)
+    }
+
+    /// Moves the next frame, or concealment for missing audio, into the current samples
+    ///
+    /// Returns false once the concealment limit is reached.
+    fn refill(&mut self) -> bool {
+        let max_concealment = self.config.samples_in(self.config.max_concealment);
+        if let Some(timestamp) = self.frames.front().map(|frame| frame.timestamp) {
+            if let Some(expected) = self.next_timestamp {
+                let missing = self
+                    .config
+                    .samples_in_dis_time_units(timestamp::units_between(expected, timestamp));
+                // A gap shorter than the concealment limit is filled, a longer one is taken
+                // to be a new transmission and skipped
+                if missing > self.last_frame.len() / 2 && missing <= max_concealment {
+                    self.conceal(missing);
+                    self.next_timestamp = Some(timestamp);
+                    return true;
+                }
+            }
+            let Some(frame) = self.frames.pop_front() else {
+                return false;
+            };
+            let pcm = match frame.audio {
+                FrameAudio::Pcm(pcm) => pcm,
+                FrameAudio::Cvsd { data, samples } => self.cvsd.decode(&data, samples),
+            };
+            let pcm = resample(&pcm, frame.sample_rate, self.config.sample_rate);
+            self.next_timestamp = Some(timestamp::advance(
+                timestamp,
+                timestamp::units_in_samples(pcm.len(), self.config.sample_rate),
+            ));
+            self.current.extend(&pcm);
+            self.last_frame = pcm;
+            self.concealment_gain = 1.0;
+            self.concealed = 0;
+            return true;
+        }
+
+        if self.concealed >= max_concealment || self.last_frame.is_empty() {
+            return false;
+        }
+        let missing = self.last_frame.len().min(max_concealment - self.concealed);
+        self.conceal(missing);
+        let units = timestamp::units_in_samples(missing, self.config.sample_rate);
+        self.next_timestamp = self
+            .next_timestamp
+            .map(|expected| timestamp::advance(expected, units));
+        true
+    }
+
+    /// Fills `missing` samples by repeating the last frame with a fading gain
+    #[allow(clippy::cast_possible_truncation)]
+    fn conceal(&mut self, missing: usize) {
+        self.concealment_gain *= 0.5;
+        let gain = self.concealment_gain;
+        self.current.extend(
+            self.last_frame
+                .iter()
+                .cycle()
+                .take(missing)
+                .map(|&sample| (f32::from(sample) * gain) as i16),
+        );
+        self.concealed += missing;
+    }
+
+    fn stop(&mut self, now: Instant) {
+        self.playing = false;
+        self.current.clear();
+        self.next_timestamp = None;
+        self.last_frame.clear();
+        self.concealment_gain = 1.0;
+        self.concealed = 0;
+        self.cvsd = CvsdDecoder::new();
+        if !self.frames.is_empty() {
+            self.buffering_since = Some(now);
+        }
+    }
+}
+
+/// Mixes the transmitters heard by one receiver into a single PCM stream
+#[derive(Clone, Debug)]
+pub struct AudioMixer {
+    config: PlayoutConfig,
+    streams: HashMap<RadioId, JitterBuffer>,
+}
+
+impl AudioMixer {
+    #[must_use]
+    pub fn new(config: PlayoutConfig) -> Self {
+        Self {
+            config,
+            streams: HashMap::new(),
+        }
+    }
+
+    #[must_use]
+    pub const fn config(&self) -> &PlayoutConfig {
+        &self.config
+    }
+
+    /// Queues a Signal PDU in the jitter buffer of its transmitter
+    ///
+    /// # Errors
+    /// Returns `DISError::InvalidFieldValue` if the PDU does not carry encoded audio in a
+    /// supported encoding.
+    pub fn push(&mut self, pdu: &SignalPdu, now: Instant) -> Result<PushOutcome, DISError> {
+        let config = self.config;
+        self.streams
+            .entry(RadioId::new(pdu.entity_id, pdu.radio_id))
+            .or_insert_with(|| JitterBuffer::new(config))
+            .push(pdu, now)
+    }
+
+    /// The transmitters currently being played
+    pub fn talkers(&self) -> impl Iterator<Item = &RadioId> {
+        self.streams
+            .iter()
+            .filter(|(_, stream)| stream.is_playing())
+            .map(|(radio, _)| radio)
+    }
+
+    /// Produces `samples` samples of the mixed audio, silence when nobody talks
+    pub fn mix(&mut self, now: Instant, samples: usize) -> Vec<i16> {
+        let mut mixed = vec![0i32; samples];
+        for stream in self.streams.values_mut() {
+            if let Some(pcm) = stream.pull(now, samples) {
+                for (sum, sample) in mixed.iter_mut().zip(pcm) {
+                    *sum += i32::from(sample);
+                }
+            }
+        }
+        self.streams.retain(|_, stream| !stream.is_idle());
+
+        mixed
+            .into_iter()
+            .map(|sum| {
+                i16::try_from(sum.clamp(i32::from(i16::MIN), i32::from(i16::MAX)))
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::data_types::entity_id::EntityId;
+
+    const RATE: u32 = 8_000;
+    const FRAME: usize = 160;
+
+    fn config() -> PlayoutConfig {
+        PlayoutConfig::new(RATE).with_target_delay(Duration::from_millis(40))
+    }
+
+    fn signal_at(entity: u16, timestamp: u32, value: i16) -> SignalPdu {
+        let mut pdu = SignalPdu::new();
+        pdu.entity_id = EntityId::new(1, 1, entity);
+        pdu.radio_id = 1;
+        pdu.header.timestamp = timestamp;
+        pdu.set_audio(AudioCodec::Pcm16BigEndian, RATE, &[value; FRAME])
+            .expect("valid audio");
+        pdu
+    }
+
+    fn signal(entity: u16, index: usize, value: i16) -> SignalPdu {
+        signal_at(
+            entity,
+            timestamp::advance(1_000, timestamp::units_in_samples(index * FRAME, RATE)),
+            value,
+        )
+    }
+
+    #[test]
+    fn frames_play_in_timestamp_order_after_the_target_delay() {
+        let start = Instant::now();
+        let mut buffer = JitterBuffer::new(config());
+        for (index, value) in [(1, 200), (0, 100), (2, 300)] {
+            buffer.push(&signal(1, index, value), start).expect("audio");
+        }
+        assert!(buffer.pull(start, FRAME).is_none());
+
+        let now = start + Duration::from_millis(40);
+        for expected in [100, 200, 300] {
+            assert_eq!(buffer.pull(now, FRAME), Some(vec![expected; FRAME]));
+        }
+
+        // Frames older than what was played are dropped
+        assert_eq!(
+            buffer.push(&signal(1, 1, 999), now).expect("audio"),
+            PushOutcome::Late
+        );
+        assert_eq!(buffer.buffered_frames(), 0);
+    }
+
+    #[test]
+    fn frames_keep_playing_across_the_hour_wrap() {
+        let start = Instant::now();
+        let mut buffer = JitterBuffer::new(config());
+        let frame_units = timestamp::units_in_samples(FRAME, RATE);
+        let before_wrap = timestamp::from_units(timestamp::UNITS_PER_HOUR - 100, false);
+        let after_wrap = timestamp::advance(before_wrap, frame_units);
+        let later = timestamp::advance(after_wrap, frame_units);
+        assert!(timestamp::units(after_wrap) < timestamp::units(before_wrap));
+
+        let first = signal_at(1, before_wrap, 100);
+        assert_eq!(
+            buffer.push(&first, start).expect("audio"),
+            PushOutcome::Queued
+        );
+        let now = start + Duration::from_millis(40);
+        assert_eq!(buffer.pull(now, FRAME), Some(vec![100; FRAME]));
+
+        let second = signal_at(1, after_wrap, 200);
+        assert_eq!(
+            buffer.push(&second, now).expect("audio"),
+            PushOutcome::Queued
+        );
+        let third = signal_at(1, later, 300);
+        assert_eq!(
+            buffer.push(&third, now).expect("audio"),
+            PushOutcome::Queued
+        );
+        assert_eq!(
+            buffer.push(&third, now).expect("audio"),
+            PushOutcome::Duplicate
+        );
+        assert_eq!(buffer.push(&first, now).expect("audio"), PushOutcome::Late);
+
+        assert_eq!(buffer.pull(now, FRAME), Some(vec![200; FRAME]));
+        assert_eq!(buffer.pull(now, FRAME), Some(vec![300; FRAME]));
+    }
+
+    #[test]
+    fn gaps_are_concealed_with_fading_audio() {
+        let start = Instant::now();
+        let mut buffer = JitterBuffer::new(config());
+        buffer.push(&signal(1, 0, 1_000), start).expect("audio");
+        buffer.push(&signal(1, 2, 3_000), start).expect("audio");
+
+        let now = start + Duration::from_millis(40);
+        assert_eq!(buffer.pull(now, FRAME), Some(vec![1_000; FRAME]));
+        assert_eq!(buffer.pull(now, FRAME), Some(vec![500; FRAME]));
+        assert_eq!(buffer.pull(now, FRAME), Some(vec![3_000; FRAME]));
+    }
+
+    #[test]
+    fn playout_stops_after_the_concealment_limit() {
+        let start = Instant::now();
+        let mut buffer =
+            JitterBuffer::new(config().with_max_concealment(Duration::from_millis(40)));
+        buffer.push(&signal(1, 0, 1_000), start).expect("audio");
+
+        let now = start + Duration::from_millis(40);
+        let played: Vec<i16> = (0..4)
+            .filter_map(|_| buffer.pull(now, FRAME))
+            .flatten()
+            .collect();
+        assert_eq!(&played[..FRAME], &[1_000; FRAME]);
+        assert_eq!(&played[FRAME..2 * FRAME], &[500; FRAME]);
+        assert_eq!(&played[2 * FRAME..3 * FRAME], &[250; FRAME]);
+        assert!(played[3 * FRAME..].iter().all(|&sample| sample == 0));
+        assert!(buffer.is_idle());
+        assert!(buffer.pull(now, FRAME).is_none());
+    }
+
+    #[test]
+    fn concurrent_talkers_are_mixed() {
+        let start = Instant::now();
+        let mut mixer = AudioMixer::new(config());
+        mixer.push(&signal(1, 0, 1_000), start).expect("audio");
+        mixer.push(&signal(2, 0, 30_000), start).expect("audio");
+        mixer.push(&signal(3, 0, 2_000), start).expect("audio");
+
+        assert_eq!(mixer.mix(start, FRAME), vec![0; FRAME]);
+        let now = start + Duration::from_millis(40);
+        // 1000 + 30000 + 2000 saturates
+        assert_eq!(mixer.mix(now, FRAME), vec![i16::MAX; FRAME]);
+        assert_eq!(mixer.talkers().count(), 3);
+    }
+
+    #[test]
+    fn audio_is_resampled_to_the_output_rate() {
+        let start = Instant::now();
+        let mut buffer =
+            JitterBuffer::new(PlayoutConfig::new(16_000).with_target_delay(Duration::ZERO));
+        buffer.push(&signal(1, 0, 100), start).expect("audio");
+        assert_eq!(buffer.pull(start, 2 * FRAME), Some(vec![100; 2 * FRAME]));
+    }
+}
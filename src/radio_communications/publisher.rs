@@ -0,0 +1,296 @@
+//     open-dis-rust - Rust implementation of the IEEE 1278.1-2012 Distributed Interactive
+//                     Simulation (DIS) application protocol
+//     Copyright (C) 2025 Cameron Howell
+//
+//     Licensed under the BSD 2-Clause License
+
+//! Issuance of Transmitter PDUs for a local radio
+//!
+//! The [`TransmitterPublisher`] holds the state of a local radio and decides when a Transmitter
+//! PDU is due: on a change of transmit state, on a change of frequency, bandwidth, power,
+//! modulation, crypto or input source, when the antenna moves further than a threshold, and
+//! otherwise on a heartbeat. Time is passed in by the caller, so the policy can be driven by a
+//! simulated clock, and each issued PDU is timestamped with the time it was issued.
+
+use std::time::{Duration, Instant};
+
+use crate::{common::timestamp, radio_communications::TransmitterPdu};
+
+/// Default interval between Transmitter PDUs of an unchanged radio (`HBT_PDU_TRANSMITTER`)
+pub const DEFAULT_TRANSMITTER_HEARTBEAT: Duration = Duration::from_secs(2);
+
+/// Default antenna movement, in meters, that requires a new Transmitter PDU
+/// (`TRANS_POS_THRSH`)
+pub const DEFAULT_ANTENNA_POSITION_THRESHOLD: f64 = 500.0;
+
+/// When a Transmitter PDU is due
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TransmitterPublisherConfig {
+    pub heartbeat: Duration,
+    /// Antenna movement in meters that requires a new PDU
+    pub antenna_position_threshold: f64,
+    /// Frequency change in Hz that requires a new PDU; zero means any change
+    pub frequency_threshold: u64,
+    /// Power change in dB that requires a new PDU; zero means any change
+    pub power_threshold: f32,
+}
+
+impl Default for TransmitterPublisherConfig {
+    fn default() -> Self {
+        Self {
+            heartbeat: DEFAULT_TRANSMITTER_HEARTBEAT,
+            antenna_position_threshold: DEFAULT_ANTENNA_POSITION_THRESHOLD,
+            frequency_threshold: 0,
+            power_threshold: 0.0,
+        }
+    }
+}
+
+impl TransmitterPublisherConfig {
+    #[must_use]
+    pub const fn with_heartbeat(mut self, heartbeat: Duration) -> Self {
+        self.heartbeat = heartbeat;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_antenna_position_threshold(mut self, threshold: f64) -> Self {
+        self.antenna_position_threshold = threshold;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_frequency_threshold(mut self, threshold: u64) -> Self {
+        self.frequency_threshold = threshold;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_power_threshold(mut self, threshold: f32) -> Self {
+        self.power_threshold = threshold;
+        self
+    }
+}
+
+/// Why a Transmitter PDU is due
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum IssueReason {
+    /// No PDU has been sent for this radio yet
+    Initial,
+    TransmitStateChanged,
+    /// Frequency, bandwidth, power, modulation, crypto or input source changed
+    ParametersChanged,
+    AntennaMoved,
+    Heartbeat,
+}
+
+/// Holds the state of a local radio and issues its Transmitter PDUs
+#[derive(Debug)]
+pub struct TransmitterPublisher {
+    config: TransmitterPublisherConfig,
+    state: TransmitterPdu,
+    last_sent: Option<(TransmitterPdu, Instant)>,
+    /// An instant and the DIS timestamp it corresponds to, read from the wall clock at the
+    /// first issue unless set
+    clock_origin: Option<(Instant, u32)>,
+}
+
+impl TransmitterPublisher {
+    #[must_use]
+    pub const fn new(config: TransmitterPublisherConfig, state: TransmitterPdu) -> Self {
+        Self {
+            config,
+            state,
+            last_sent: None,
+            clock_origin: None,
+        }
+    }
+
+    /// Timestamps the issued PDUs relative to `origin`, the instant of the DIS timestamp
+    /// `timestamp`, instead of a relative timestamp read from the system clock when first
+    /// issuing. `timestamp` is in the §6.2.88 format, and its absolute bit is kept
+    #[must_use]
+    pub const fn with_clock_origin(mut self, origin: Instant, timestamp: u32) -> Self {
+        self.clock_origin = Some((origin, timestamp));
+        self
+    }
+
+    #[must_use]
+    pub const fn config(&self) -> &TransmitterPublisherConfig {
+        &self.config
+    }
+
+    /// The current state of the radio
+    #[must_use]
+    pub const fn state(&self) -> &TransmitterPdu {
+        &self.state
+    }
+
+    /// The state of the radio, to be updated as the simulation changes it
+    pub const fn state_mut(&mut self) -> &mut TransmitterPdu {
+        &mut self.state
+    }
+
+    /// Time the next heartbeat is due, if a PDU has been sent
+    #[must_use]
+    pub fn next_heartbeat(&self) -> Option<Instant> {
+        self.last_sent
+            .as_ref()
+            .map(|(_, sent)| *sent + self.config.heartbeat)
+    }
+
+    /// Why a Transmitter PDU is due at `now`, or `None` if none is
+    #[must_use]
+    pub fn due(&self, now: Instant) -> Option<IssueReason> {
+        let Some((sent, sent_at)) = &self.last_sent else {
+            return Some(IssueReason::Initial);
+        };
+        let state = &self.state;
+
+        if state.transmit_state != sent.transmit_state {
+            return Some(IssueReason::TransmitStateChanged);
+        }
+
+        let frequency_change = state.frequency.abs_diff(sent.frequency);
+        let power_change = (state.power - sent.power).abs();
+        let parameters_changed = frequency_change > self.config.frequency_threshold
+            || power_change > self.config.power_threshold
+            || state.transmit_frequency_bandwidth.to_bits()
+                != sent.transmit_frequency_bandwidth.to_bits()
+            || state.modulation_type != sent.modulation_type
+            || state.crypto_system != sent.crypto_system
+            || state.crypto_key_id != sent.crypto_key_id
+            || state.input_source != sent.input_source;
+        if parameters_changed {
+            return Some(IssueReason::ParametersChanged);
+        }
+
+        let moved = state.antenna_location.distance_to(&sent.antenna_location);
+        if moved > self.config.antenna_position_threshold {
+            return Some(IssueReason::AntennaMoved);
+        }
+
+        (now.saturating_duration_since(*sent_at) >= self.config.heartbeat)
+            .then_some(IssueReason::Heartbeat)
+    }
+
+    /// Returns the Transmitter PDU to send at `now`, timestamped with `now`, if one is due
+    pub fn poll(&mut self, now: Instant) -> Option<(TransmitterPdu, IssueReason)> {
+        let reason = self.due(now)?;
+        let (origin, origin_timestamp) = *self
+            .clock_origin
+            .get_or_insert_with(|| (now, timestamp::relative_now()));
+        self.state.header.timestamp = timestamp::advance(
+            origin_timestamp,
+            timestamp::units_in(now.saturating_duration_since(origin)),
+        );
+        self.last_sent = Some((self.state.clone(), now));
+        Some((self.state.clone(), reason))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{
+        data_types::WorldCoordinate,
+        enums::{TransmitterCryptoSystem, TransmitterTransmitState},
+    };
+
+    fn publisher() -> (TransmitterPublisher, Instant) {
+        let start = Instant::now();
+        let mut radio = TransmitterPdu::new();
+        radio.frequency = 30_000_000;
+        radio.power = 10.0;
+        let mut publisher = TransmitterPublisher::new(TransmitterPublisherConfig::default(), radio);
+        assert_eq!(
+            publisher.poll(start).map(|(_, reason)| reason),
+            Some(IssueReason::Initial)
+        );
+        (publisher, start)
+    }
+
+    #[test]
+    fn unchanged_radio_sends_heartbeats() {
+        let (mut publisher, start) = publisher();
+        assert!(
+            publisher
+                .poll(start + Duration::from_millis(1_999))
+                .is_none()
+        );
+        assert_eq!(
+            publisher
+                .poll(start + Duration::from_secs(2))
+                .map(|(_, reason)| reason),
+            Some(IssueReason::Heartbeat)
+        );
+        assert_eq!(
+            publisher.next_heartbeat(),
+            Some(start + Duration::from_secs(4))
+        );
+    }
+
+    #[test]
+    fn transmit_state_change_is_sent_at_once() {
+        let (mut publisher, start) = publisher();
+        publisher.state_mut().transmit_state = TransmitterTransmitState::Onandtransmitting;
+        let (pdu, reason) = publisher
+            .poll(start + Duration::from_millis(10))
+            .expect("state change is due");
+        assert_eq!(reason, IssueReason::TransmitStateChanged);
+        assert_eq!(
+            pdu.transmit_state,
+            TransmitterTransmitState::Onandtransmitting
+        );
+        assert!(publisher.poll(start + Duration::from_millis(20)).is_none());
+    }
+
+    #[test]
+    fn parameter_changes_respect_thresholds() {
+        let start = Instant::now();
+        let config = TransmitterPublisherConfig::default()
+            .with_frequency_threshold(1_000)
+            .with_power_threshold(1.0);
+        let mut publisher = TransmitterPublisher::new(config, TransmitterPdu::new());
+        let _ = publisher.poll(start);
+
+        publisher.state_mut().frequency = 500;
+        publisher.state_mut().power = 0.5;
+        assert!(publisher.due(start).is_none());
+
+        publisher.state_mut().frequency = 1_500;
+        assert_eq!(publisher.due(start), Some(IssueReason::ParametersChanged));
+        let _ = publisher.poll(start);
+
+        publisher.state_mut().crypto_system = TransmitterCryptoSystem::KY58;
+        assert_eq!(publisher.due(start), Some(IssueReason::ParametersChanged));
+    }
+
+    #[test]
+    fn issued_pdus_are_timestamped_when_issued() {
+        let start = Instant::now();
+        let mut publisher =
+            TransmitterPublisher::new(TransmitterPublisherConfig::default(), TransmitterPdu::new())
+                .with_clock_origin(start, timestamp::from_units(500, true));
+        let (first, _) = publisher.poll(start).expect("initial PDU");
+        assert_eq!(first.header.timestamp, 1_001);
+
+        let (heartbeat, _) = publisher
+            .poll(start + Duration::from_secs(2))
+            .expect("heartbeat");
+        // Two seconds are 1 193 046 units of 3600 s / 2^31
+        assert_eq!(
+            heartbeat.header.timestamp,
+            timestamp::from_units(500 + 1_193_046, true)
+        );
+    }
+
+    #[test]
+    fn antenna_movement_beyond_threshold_is_sent() {
+        let (mut publisher, start) = publisher();
+        publisher.state_mut().antenna_location = WorldCoordinate::new(400.0, 0.0, 0.0);
+        assert!(publisher.due(start).is_none());
+        publisher.state_mut().antenna_location = WorldCoordinate::new(400.0, 400.0, 0.0);
+        assert_eq!(publisher.due(start), Some(IssueReason::AntennaMoved));
+    }
+}
@@ -0,0 +1,485 @@
+//     open-dis-rust - Rust implementation of the IEEE 1278.1-2012 Distributed Interactive
+//                     Simulation (DIS) application protocol
+//     Copyright (C) 2025 Cameron Howell
+//
+//     Licensed under the BSD 2-Clause License
+
+//! A model of which remote transmitters a local receiver hears
+//!
+//! Transmitter PDUs are handed to the [`ReceptionModel`] together with the time they were
+//! received. For a [`LocalReceiver`] the model then decides which transmitters are heard, from
+//! the frequency overlap, the modulation and crypto settings and the received power after free
+//! space path loss. It builds the matching Receiver PDU and tells which Signal PDUs to play.
+
+use std::{
+    collections::HashMap,
+    f64::consts::PI,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    common::{
+        data_types::{WorldCoordinate, entity_id::EntityId, modulation_type::ModulationType},
+        enums::{ReceiverReceiverState, TransmitterCryptoSystem, TransmitterTransmitState},
+    },
+    radio_communications::{ReceiverPdu, SignalPdu, TransmitterPdu},
+};
+
+/// Default time after which a transmitter that has not been updated is removed, 2.4 times the
+/// standard 2 second heartbeat
+pub const DEFAULT_TRANSMITTER_TIMEOUT: Duration = Duration::from_millis(4_800);
+
+/// Default weakest received power a receiver can hear, in dBm
+pub const DEFAULT_RECEIVER_SENSITIVITY: f32 = -110.0;
+
+/// Speed of light in vacuum, in meters per second
+const SPEED_OF_LIGHT: f64 = 299_792_458.0;
+
+/// Free space path loss in dB over `distance` meters at `frequency` Hz
+///
+/// Distances under one meter are treated as one meter, so co-located radios get a finite loss.
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn free_space_path_loss(distance: f64, frequency: u64) -> f64 {
+    let ratio = 4.0 * PI * distance.max(1.0) * frequency as f64 / SPEED_OF_LIGHT;
+    20.0 * ratio.log10()
+}
+
+/// Identifies a radio by the entity it is attached to and its radio number
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct RadioId {
+    pub entity_id: EntityId,
+    pub radio_id: u16,
+}
+
+impl RadioId {
+    #[must_use]
+    pub const fn new(entity_id: EntityId, radio_id: u16) -> Self {
+        Self {
+            entity_id,
+            radio_id,
+        }
+    }
+}
+
+/// The last known state of a remote transmitter
+#[derive(Clone, Debug, PartialEq)]
+pub struct RemoteTransmitter {
+    pub radio: RadioId,
+    pub transmit_state: TransmitterTransmitState,
+    /// Center frequency in Hz
+    pub frequency: u64,
+    /// Bandwidth in Hz
+    pub bandwidth: f32,
+    /// Transmitted power in dBm
+    pub power: f32,
+    pub antenna_location: WorldCoordinate,
+    pub modulation_type: ModulationType,
+    pub crypto_system: TransmitterCryptoSystem,
+    pub crypto_key_id: u16,
+    /// Time of the last Transmitter PDU
+    pub last_update: Instant,
+}
+
+impl RemoteTransmitter {
+    #[must_use]
+    pub fn from_pdu(pdu: &TransmitterPdu, now: Instant) -> Self {
+        Self {
+            radio: RadioId::new(pdu.entity_id, pdu.radio_id),
+            transmit_state: pdu.transmit_state,
+            frequency: pdu.frequency,
+            bandwidth: pdu.transmit_frequency_bandwidth,
+            power: pdu.power,
+            antenna_location: pdu.antenna_location,
+            modulation_type: pdu.modulation_type.clone(),
+            crypto_system: pdu.crypto_system,
+            crypto_key_id: pdu.crypto_key_id,
+            last_update: now,
+        }
+    }
+}
+
+/// A receiver simulated by this application
+#[derive(Clone, Debug, PartialEq)]
+pub struct LocalReceiver {
+    pub radio: RadioId,
+    pub powered: bool,
+    /// Tuned center frequency in Hz
+    pub frequency: u64,
+    /// Bandwidth in Hz
+    pub bandwidth: f32,
+    pub antenna_location: WorldCoordinate,
+    /// Weakest received power that can be heard, in dBm
+    pub sensitivity: f32,
+    /// The modulation the receiver demodulates, or `None` to accept any
+    pub modulation_type: Option<ModulationType>,
+    pub crypto_system: TransmitterCryptoSystem,
+    pub crypto_key_id: u16,
+}
+
+impl LocalReceiver {
+    /// A powered, unencrypted receiver that accepts any modulation
+    #[must_use]
+    pub const fn new(
+        radio: RadioId,
+        frequency: u64,
+        bandwidth: f32,
+        antenna_location: WorldCoordinate,
+    ) -> Self {
+        Self {
+            radio,
+            powered: true,
+            frequency,
+            bandwidth,
+            antenna_location,
+            sensitivity: DEFAULT_RECEIVER_SENSITIVITY,
+            modulation_type: None,
+            crypto_system: TransmitterCryptoSystem::NoEncryptionDevice,
+            crypto_key_id: 0,
+        }
+    }
+
+    #[must_use]
+    pub const fn with_sensitivity(mut self, sensitivity: f32) -> Self {
+        self.sensitivity = sensitivity;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_modulation_type(mut self, modulation_type: ModulationType) -> Self {
+        self.modulation_type = Some(modulation_type);
+        self
+    }
+
+    #[must_use]
+    pub const fn with_crypto(
+        mut self,
+        crypto_system: TransmitterCryptoSystem,
+        key_id: u16,
+    ) -> Self {
+        self.crypto_system = crypto_system;
+        self.crypto_key_id = key_id;
+        self
+    }
+
+    /// Whether the receiver's band overlaps the band of `transmitter`
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn overlaps(&self, transmitter: &RemoteTransmitter) -> bool {
+        let band = |frequency: u64, bandwidth: f32| {
+            let half = f64::from(bandwidth.max(0.0)) / 2.0;
+            (frequency as f64 - half, frequency as f64 + half)
+        };
+        let (receiver_low, receiver_high) = band(self.frequency, self.bandwidth);
+        let (transmitter_low, transmitter_high) =
+            band(transmitter.frequency, transmitter.bandwidth);
+        transmitter_low <= receiver_high && receiver_low <= transmitter_high
+    }
+
+    /// Whether the receiver can demodulate and decrypt `transmitter`
+    #[must_use]
+    pub fn is_compatible(&self, transmitter: &RemoteTransmitter) -> bool {
+        let modulation_matches = self
+            .modulation_type
+            .as_ref()
+            .is_none_or(|modulation| *modulation == transmitter.modulation_type);
+        let crypto_matches = self.crypto_system == transmitter.crypto_system
+            && (self.crypto_system == TransmitterCryptoSystem::NoEncryptionDevice
+                || self.crypto_key_id == transmitter.crypto_key_id);
+        modulation_matches && crypto_matches
+    }
+}
+
+/// A transmitter heard by a receiver
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Reception {
+    pub transmitter: RadioId,
+    /// Received power in dBm
+    pub received_power: f32,
+    /// Distance between the antennas in meters
+    pub distance: f64,
+}
+
+/// Tracks remote transmitters and decides what local receivers hear
+#[derive(Clone, Debug)]
+pub struct ReceptionModel {
+    transmitters: HashMap<RadioId, RemoteTransmitter>,
+    timeout: Duration,
+}
+
+impl Default for ReceptionModel {
+    fn default() -> Self {
+        Self::new(DEFAULT_TRANSMITTER_TIMEOUT)
+    }
+}
+
+impl ReceptionModel {
+    /// Creates an empty model that drops transmitters not updated within `timeout`
+    #[must_use]
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            transmitters: HashMap::new(),
+            timeout,
+        }
+    }
+
+    #[must_use]
+    pub const fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Records the state announced by a Transmitter PDU
+    pub fn ingest_transmitter(&mut self, pdu: &TransmitterPdu, now: Instant) {
+        let transmitter = RemoteTransmitter::from_pdu(pdu, now);
+        self.transmitters.insert(transmitter.radio, transmitter);
+    }
+
+    /// Removes the transmitters that have not been updated within the timeout, returning them
+    pub fn expire(&mut self, now: Instant) -> Vec<RemoteTransmitter> {
+        let expired: Vec<RadioId> = self
+            .transmitters
+            .values()
+            .filter(|transmitter| {
+                now.saturating_duration_since(transmitter.last_update) > self.timeout
+            })
+            .map(|transmitter| transmitter.radio)
+            .collect();
+        expired
+            .iter()
+            .filter_map(|radio| self.transmitters.remove(radio))
+            .collect()
+    }
+
+    pub fn remove(&mut self, radio: &RadioId) -> Option<RemoteTransmitter> {
+        self.transmitters.remove(radio)
+    }
+
+    #[must_use]
+    pub fn get(&self, radio: &RadioId) -> Option<&RemoteTransmitter> {
+        self.transmitters.get(radio)
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.transmitters.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.transmitters.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &RemoteTransmitter> {
+        self.transmitters.values()
+    }
+
+    /// How `receiver` hears the transmitter `radio`, if it hears it at all
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn reception(&self, receiver: &LocalReceiver, radio: &RadioId) -> Option<Reception> {
+        let transmitter = self.transmitters.get(radio)?;
+        if !receiver.powered
+            || transmitter.radio == receiver.radio
+            || transmitter.transmit_state != TransmitterTransmitState::Onandtransmitting
+            || !receiver.overlaps(transmitter)
+            || !receiver.is_compatible(transmitter)
+        {
+            return None;
+        }
+
+        let distance = receiver
+            .antenna_location
+            .distance_to(&transmitter.antenna_location);
+        let received_power = (f64::from(transmitter.power)
+            - free_space_path_loss(distance, transmitter.frequency))
+            as f32;
+        (received_power >= receiver.sensitivity).then_some(Reception {
+            transmitter: transmitter.radio,
+            received_power,
+            distance,
+        })
+    }
+
+    /// Every transmitter `receiver` hears, strongest first
+    #[must_use]
+    pub fn heard_by(&self, receiver: &LocalReceiver) -> Vec<Reception> {
+        let mut receptions: Vec<Reception> = self
+            .transmitters
+            .keys()
+            .filter_map(|radio| self.reception(receiver, radio))
+            .collect();
+        receptions.sort_by(|a, b| b.received_power.total_cmp(&a.received_power));
+        receptions
+    }
+
+    /// The Receiver PDU describing what `receiver` hears, reporting the strongest transmitter
+    #[must_use]
+    pub fn receiver_pdu(&self, receiver: &LocalReceiver) -> ReceiverPdu {
+        let mut pdu = ReceiverPdu::new();
+        pdu.entity_id = receiver.radio.entity_id;
+        pdu.radio_id = receiver.radio.radio_id;
+        pdu.receiver_state = if !receiver.powered {
+            ReceiverReceiverState::Off
+        } else if let Some(reception) = self.heard_by(receiver).first() {
+            pdu.received_power = reception.received_power;
+            pdu.transmitter_radio_reference_id = reception.transmitter.entity_id;
+            pdu.transmitter_radio_id = reception.transmitter.radio_id;
+            ReceiverReceiverState::Onandreceiving
+        } else {
+            ReceiverReceiverState::Onbutnotreceiving
+        };
+        pdu
+    }
+
+    /// How `receiver` hears the transmitter of `signal`, if the signal is to be played to it
+    #[must_use]
+    pub fn route_signal(&self, signal: &SignalPdu, receiver: &LocalReceiver) -> Option<Reception> {
+        self.reception(receiver, &RadioId::new(signal.entity_id, signal.radio_id))
+    }
+
+    /// The receivers among `receivers` that `signal` is to be played to
+    pub fn route_signal_to<'a>(
+        &self,
+        signal: &SignalPdu,
+        receivers: impl IntoIterator<Item = &'a LocalReceiver>,
+    ) -> Vec<(&'a LocalReceiver, Reception)> {
+        receivers
+            .into_iter()
+            .filter_map(|receiver| {
+                self.route_signal(signal, receiver)
+                    .map(|reception| (receiver, reception))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FREQUENCY: u64 = 100_000_000;
+
+    fn transmitter(entity: u16, x: f64) -> TransmitterPdu {
+        let mut pdu = TransmitterPdu::new();
+        pdu.entity_id = EntityId::new(1, 1, entity);
+        pdu.radio_id = 1;
+        pdu.transmit_state = TransmitterTransmitState::Onandtransmitting;
+        pdu.frequency = FREQUENCY;
+        pdu.transmit_frequency_bandwidth = 25_000.0;
+        pdu.power = 40.0;
+        pdu.antenna_location = WorldCoordinate::new(x, 0.0, 0.0);
+        pdu
+    }
+
+    fn receiver() -> LocalReceiver {
+        LocalReceiver::new(
+            RadioId::new(EntityId::new(1, 1, 100), 1),
+            FREQUENCY,
+            25_000.0,
+            WorldCoordinate::default(),
+        )
+    }
+
+    #[test]
+    fn path_loss_matches_the_free_space_formula() {
+        let loss = free_space_path_loss(1_000.0, FREQUENCY);
+        assert!((loss - 72.45).abs() < 0.01, "{loss}");
+        // Doubling the distance costs 6 dB
+        let doubled = free_space_path_loss(2_000.0, FREQUENCY);
+        assert!((doubled - loss - 6.02).abs() < 0.01);
+    }
+
+    #[test]
+    fn strongest_transmitter_is_reported() {
+        let now = Instant::now();
+        let mut model = ReceptionModel::default();
+        model.ingest_transmitter(&transmitter(1, 10_000.0), now);
+        model.ingest_transmitter(&transmitter(2, 1_000.0), now);
+
+        let heard = model.heard_by(&receiver());
+        assert_eq!(heard.len(), 2);
+        assert_eq!(heard[0].transmitter.entity_id.entity_id, 2);
+        assert!((heard[0].received_power - (40.0 - 72.45)).abs() < 0.01);
+
+        let pdu = model.receiver_pdu(&receiver());
+        assert_eq!(pdu.receiver_state, ReceiverReceiverState::Onandreceiving);
+        assert_eq!(pdu.transmitter_radio_reference_id, EntityId::new(1, 1, 2));
+        assert_eq!(pdu.transmitter_radio_id, 1);
+        assert!((pdu.received_power - heard[0].received_power).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn transmitters_out_of_band_range_or_key_are_not_heard() {
+        let now = Instant::now();
+        let mut model = ReceptionModel::default();
+
+        let mut off_frequency = transmitter(1, 1_000.0);
+        off_frequency.frequency = FREQUENCY + 50_000;
+        model.ingest_transmitter(&off_frequency, now);
+
+        model.ingest_transmitter(&transmitter(2, 1.0e9), now);
+
+        let mut encrypted = transmitter(3, 1_000.0);
+        encrypted.crypto_system = TransmitterCryptoSystem::KY58;
+        encrypted.crypto_key_id = 7;
+        model.ingest_transmitter(&encrypted, now);
+
+        let mut idle = transmitter(4, 1_000.0);
+        idle.transmit_state = TransmitterTransmitState::Onbutnottransmitting;
+        model.ingest_transmitter(&idle, now);
+
+        assert!(model.heard_by(&receiver()).is_empty());
+        assert_eq!(
+            model.receiver_pdu(&receiver()).receiver_state,
+            ReceiverReceiverState::Onbutnotreceiving
+        );
+
+        let keyed = receiver().with_crypto(TransmitterCryptoSystem::KY58, 7);
+        let heard = model.heard_by(&keyed);
+        assert_eq!(heard.len(), 1);
+        assert_eq!(heard[0].transmitter.entity_id.entity_id, 3);
+
+        let mut off = receiver();
+        off.powered = false;
+        assert_eq!(
+            model.receiver_pdu(&off).receiver_state,
+            ReceiverReceiverState::Off
+        );
+    }
+
+    #[test]
+    fn signals_are_routed_to_receivers_that_hear_them() {
+        let now = Instant::now();
+        let mut model = ReceptionModel::default();
+        model.ingest_transmitter(&transmitter(1, 1_000.0), now);
+
+        let tuned = receiver();
+        let mut detuned = receiver();
+        detuned.radio.radio_id = 2;
+        detuned.frequency = FREQUENCY * 2;
+
+        let mut signal = SignalPdu::new();
+        signal.entity_id = EntityId::new(1, 1, 1);
+        signal.radio_id = 1;
+        let routes = model.route_signal_to(&signal, [&tuned, &detuned]);
+        assert_eq!(routes.len(), 1);
+        assert_eq!(routes[0].0.radio, tuned.radio);
+
+        // Signals from unknown transmitters go nowhere
+        signal.radio_id = 9;
+        assert!(model.route_signal(&signal, &tuned).is_none());
+    }
+
+    #[test]
+    fn silent_transmitters_expire() {
+        let now = Instant::now();
+        let mut model = ReceptionModel::default();
+        model.ingest_transmitter(&transmitter(1, 1_000.0), now);
+        model.ingest_transmitter(&transmitter(2, 1_000.0), now + Duration::from_secs(3));
+
+        let expired = model.expire(now + Duration::from_secs(5));
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].radio.entity_id.entity_id, 1);
+        assert_eq!(model.len(), 1);
+    }
+}
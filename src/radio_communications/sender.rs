@@ -0,0 +1,457 @@
+//     open-dis-rust - Rust implementation of the IEEE 1278.1-2012 Distributed Interactive
+//                     Simulation (DIS) application protocol
+//     Copyright (C) 2025 Cameron Howell
+//
+//     Licensed under the BSD 2-Clause License
+
+//! Push-to-talk audio transmission over Signal PDUs
+//!
+//! The [`AudioSender`] does no I/O of its own: PCM is pushed into it as it is captured, and
+//! the PDUs it returns from [`AudioSender::key`], [`AudioSender::poll`] and
+//! [`AudioSender::unkey`] are to be sent in the order they are returned. Each Signal PDU is
+//! timestamped with the time its first sample was captured, counted in samples from the time
+//! the transmitter was keyed, so the frames of one transmission are evenly spaced however
+//! late they are polled.
+
+use std::time::{Duration, Instant};
+
+use crate::{
+    common::{
+        constants::MAX_PDU_SIZE_OCTETS, data_types::encoding_scheme::EncodingScheme,
+        dis_error::DISError, enums::TransmitterTransmitState, timestamp,
+    },
+    radio_communications::{
+        SignalPdu, TransmitterPdu,
+        audio::{AudioCodec, CvsdEncoder},
+    },
+};
+
+/// Length in octets of a Signal PDU without its data
+const SIGNAL_PDU_FIXED_LENGTH: usize = 32;
+
+/// Default duration of the audio carried by one frame
+pub const DEFAULT_FRAME_DURATION: Duration = Duration::from_millis(20);
+
+/// How audio is cut into Signal PDUs
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct AudioSenderConfig {
+    pub codec: AudioCodec,
+    /// Sample rate of the pushed PCM, in samples per second
+    pub sample_rate: u32,
+    /// Duration of the audio sent at every frame
+    pub frame_duration: Duration,
+}
+
+impl AudioSenderConfig {
+    #[must_use]
+    pub const fn new(codec: AudioCodec, sample_rate: u32) -> Self {
+        Self {
+            codec,
+            sample_rate,
+            frame_duration: DEFAULT_FRAME_DURATION,
+        }
+    }
+
+    #[must_use]
+    pub const fn with_frame_duration(mut self, frame_duration: Duration) -> Self {
+        self.frame_duration = frame_duration;
+        self
+    }
+
+    /// Number of samples in one frame, at least one
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn samples_per_frame(&self) -> usize {
+        let samples = u128::from(self.sample_rate) * self.frame_duration.as_micros() / 1_000_000;
+        (samples as usize).max(1)
+    }
+
+    /// Largest number of samples that fits in a single Signal PDU
+    #[must_use]
+    pub const fn max_samples_per_pdu(&self) -> usize {
+        let max_bits = (MAX_PDU_SIZE_OCTETS - SIGNAL_PDU_FIXED_LENGTH) * 8;
+        let samples = max_bits / self.codec.bits_per_sample();
+        if samples > u16::MAX as usize {
+            u16::MAX as usize
+        } else {
+            samples
+        }
+    }
+}
+
+/// A PDU produced by the [`AudioSender`]
+#[derive(Debug)]
+pub enum SenderOutput {
+    Transmitter(TransmitterPdu),
+    Signal(SignalPdu),
+}
+
+/// Cuts a continuous PCM stream into Signal PDUs and keys the transmitter around it
+#[derive(Debug)]
+pub struct AudioSender {
+    config: AudioSenderConfig,
+    encoding: EncodingScheme,
+    transmitter: TransmitterPdu,
+    cvsd: CvsdEncoder,
+    pending: Vec<i16>,
+    /// Time at which the next frame is due, while the transmitter is keyed
+    next_frame: Option<Instant>,
+    /// An instant and the DIS timestamp it corresponds to, read from the wall clock at the
+    /// first key unless set
+    clock_origin: Option<(Instant, u32)>,
+    /// DIS timestamp of the time the transmitter was keyed
+    key_timestamp: u32,
+    /// Samples sent since the transmitter was keyed
+    samples_sent: usize,
+}
+
+impl AudioSender {
+    /// Creates a sender for the radio described by `transmitter`
+    ///
+    /// The entity and radio identifiers of the Signal PDUs are taken from `transmitter`.
+    ///
+    /// # Errors
+    /// Returns `DISError::InvalidFieldValue` if the codec has no Signal PDU encoding type
+    /// (A-law), or if the sample rate or frame duration is zero.
+    pub fn new(config: AudioSenderConfig, transmitter: TransmitterPdu) -> Result<Self, DISError> {
+        let encoding_type = config.codec.encoding_type().ok_or_else(|| {
+            DISError::invalid_field(
+                "codec".to_string(),
+                format!("{:?}", config.codec),
+                "the codec has no Signal PDU encoding type".to_string(),
+            )
+        })?;
+        if config.sample_rate == 0 {
+            return Err(DISError::invalid_field(
+                "sample_rate".to_string(),
+                "0".to_string(),
+                "the sample rate must be positive".to_string(),
+            ));
+        }
+        if config.frame_duration.is_zero() {
+            return Err(DISError::invalid_field(
+                "frame_duration".to_string(),
+                "0".to_string(),
+                "the frame duration must be positive".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            config,
+            encoding: EncodingScheme::audio(encoding_type),
+            transmitter,
+            cvsd: CvsdEncoder::new(),
+            pending: Vec::new(),
+            next_frame: None,
+            clock_origin: None,
+            key_timestamp: 0,
+            samples_sent: 0,
+        })
+    }
+
+    /// Timestamps the sent PDUs relative to `origin`, the instant of the DIS timestamp
+    /// `timestamp`, instead of a relative timestamp read from the system clock when first
+    /// keyed. `timestamp` is in the §6.2.88 format, and its absolute bit is kept
+    #[must_use]
+    pub const fn with_clock_origin(mut self, origin: Instant, timestamp: u32) -> Self {
+        self.clock_origin = Some((origin, timestamp));
+        self
+    }
+
+    #[must_use]
+    pub const fn config(&self) -> &AudioSenderConfig {
+        &self.config
+    }
+
+    /// The transmitter state as last announced
+    #[must_use]
+    pub const fn transmitter(&self) -> &TransmitterPdu {
+        &self.transmitter
+    }
+
+    #[must_use]
+    pub const fn is_keyed(&self) -> bool {
+        self.next_frame.is_some()
+    }
+
+    /// Number of samples pushed but not sent yet
+    #[must_use]
+    pub const fn pending_samples(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Starts transmitting, returning the Transmitter PDU announcing it
+    ///
+    /// The first frame is due one frame duration after `now`. Keying an already keyed sender
+    /// returns nothing.
+    pub fn key(&mut self, now: Instant) -> Vec<SenderOutput> {
+        if self.is_keyed() {
+            return Vec::new();
+        }
+        let (origin, origin_timestamp) = *self
+            .clock_origin
+            .get_or_insert_with(|| (now, timestamp::relative_now()));
+        self.key_timestamp = timestamp::advance(
+            origin_timestamp,
+            timestamp::units_in(now.saturating_duration_since(origin)),
+        );
+        self.samples_sent = 0;
+        self.next_frame = Some(now + self.config.frame_duration);
+        self.cvsd = CvsdEncoder::new();
+        vec![self.announce(TransmitterTransmitState::Onandtransmitting)]
+    }
+
+    /// Queues captured PCM for transmission
+    ///
+    /// Audio pushed while the sender is not keyed is discarded.
+    pub fn push(&mut self, pcm: &[i16]) {
+        if self.is_keyed() {
+            self.pending.extend_from_slice(pcm);
+        }
+    }
+
+    /// Returns the Signal PDUs of every frame that is due at `now` and fully captured
+    pub fn poll(&mut self, now: Instant) -> Vec<SenderOutput> {
+        let frame_samples = self.config.samples_per_frame();
+        let mut output = Vec::new();
+        while let Some(due) = self.next_frame {
+            if due > now || self.pending.len() < frame_samples {
+                break;
+            }
+            let frame: Vec<i16> = self.pending.drain(..frame_samples).collect();
+            output.extend(self.signal_pdus(&frame));
+            self.next_frame = Some(due + self.config.frame_duration);
+        }
+        output
+    }
+
+    /// Stops transmitting, returning the Signal PDUs of any remaining audio followed by the
+    /// Transmitter PDU announcing the end of the transmission
+    ///
+    /// Unkeying a sender that is not keyed returns nothing.
+    pub fn unkey(&mut self) -> Vec<SenderOutput> {
+        if !self.is_keyed() {
+            return Vec::new();
+        }
+        let remaining = std::mem::take(&mut self.pending);
+        let mut output = self.signal_pdus(&remaining);
+        self.next_frame = None;
+        output.push(self.announce(TransmitterTransmitState::Onbutnottransmitting));
+        output
+    }
+
+    fn announce(&mut self, state: TransmitterTransmitState) -> SenderOutput {
+        self.transmitter.transmit_state = state;
+        self.transmitter.header.timestamp = self.timestamp();
+        SenderOutput::Transmitter(self.transmitter.clone())
+    }
+
+    /// DIS timestamp of the next sample to be sent
+    fn timestamp(&self) -> u32 {
+        timestamp::advance(
+            self.key_timestamp,
+            timestamp::units_in_samples(self.samples_sent, self.config.sample_rate),
+        )
+    }
+
+    /// Encodes a frame into as many Signal PDUs as needed to stay under the maximum PDU size
+    fn signal_pdus(&mut self, frame: &[i16]) -> Vec<SenderOutput> {
+        frame
+            .chunks(self.config.max_samples_per_pdu())
+            .map(|chunk| SenderOutput::Signal(self.signal_pdu(chunk)))
+            .collect()
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn signal_pdu(&mut self, pcm: &[i16]) -> SignalPdu {
+        let data = match self.config.codec {
+            AudioCodec::Cvsd => self.cvsd.encode(pcm),
+            codec => codec.encode(pcm),
+        };
+
+        // `max_samples_per_pdu` keeps both values within 16 bits
+        let mut pdu = SignalPdu::new();
+        pdu.header.timestamp = self.timestamp();
+        pdu.entity_id = self.transmitter.entity_id;
+        pdu.radio_id = self.transmitter.radio_id;
+        pdu.set_encoding(self.encoding);
+        pdu.sample_rate = self.config.sample_rate;
+        pdu.samples = pcm.len() as u16;
+        pdu.data_length = (pcm.len() * self.config.codec.bits_per_sample()) as u16;
+        pdu.data = data;
+        self.samples_sent += pcm.len();
+        pdu
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::BytesMut;
+
+    use super::*;
+    use crate::common::{data_types::entity_id::EntityId, pdu::Pdu};
+
+    fn transmitter() -> TransmitterPdu {
+        let mut pdu = TransmitterPdu::new();
+        pdu.entity_id = EntityId::new(1, 2, 3);
+        pdu.radio_id = 4;
+        pdu
+    }
+
+    fn sender(config: AudioSenderConfig) -> AudioSender {
+        AudioSender::new(config, transmitter()).expect("valid configuration")
+    }
+
+    fn signals(output: &[SenderOutput]) -> Vec<&SignalPdu> {
+        output
+            .iter()
+            .filter_map(|pdu| match pdu {
+                SenderOutput::Signal(signal) => Some(signal),
+                SenderOutput::Transmitter(_) => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn a_law_is_rejected() {
+        let config = AudioSenderConfig::new(AudioCodec::ALaw, 8_000);
+        assert!(AudioSender::new(config, transmitter()).is_err());
+    }
+
+    #[test]
+    fn frames_are_sent_when_due() {
+        let config = AudioSenderConfig::new(AudioCodec::MuLaw, 8_000);
+        let mut sender = sender(config);
+        let start = Instant::now();
+
+        let keyed = sender.key(start);
+        assert!(matches!(
+            keyed.as_slice(),
+            [SenderOutput::Transmitter(pdu)]
+                if pdu.transmit_state == TransmitterTransmitState::Onandtransmitting
+        ));
+
+        sender.push(&[100; 400]);
+        // Nothing is due before the end of the first frame
+        assert!(sender.poll(start + Duration::from_millis(19)).is_empty());
+
+        let output = sender.poll(start + Duration::from_millis(40));
+        let pdus = signals(&output);
+        assert_eq!(pdus.len(), 2);
+        for pdu in pdus {
+            assert_eq!(pdu.entity_id, EntityId::new(1, 2, 3));
+            assert_eq!(pdu.radio_id, 4);
+            assert_eq!(pdu.sample_rate, 8_000);
+            assert_eq!(pdu.samples, 160);
+            assert_eq!(pdu.data_length, 1_280);
+            assert_eq!(pdu.audio_codec(), Some(AudioCodec::MuLaw));
+        }
+        assert_eq!(sender.pending_samples(), 80);
+
+        // The third frame is due, but not fully captured
+        assert!(sender.poll(start + Duration::from_millis(60)).is_empty());
+    }
+
+    #[test]
+    fn unkey_flushes_the_partial_frame_before_the_transmitter() {
+        let config = AudioSenderConfig::new(AudioCodec::Pcm16BigEndian, 16_000)
+            .with_frame_duration(Duration::from_millis(10));
+        let mut sender = sender(config);
+        let _ = sender.key(Instant::now());
+        sender.push(&[1; 50]);
+
+        let output = sender.unkey();
+        assert_eq!(output.len(), 2);
+        assert!(matches!(&output[0], SenderOutput::Signal(pdu) if pdu.samples == 50));
+        assert!(matches!(
+            &output[1],
+            SenderOutput::Transmitter(pdu)
+                if pdu.transmit_state == TransmitterTransmitState::Onbutnottransmitting
+        ));
+        assert!(!sender.is_keyed());
+
+        // Audio pushed while unkeyed is dropped
+        sender.push(&[1; 50]);
+        assert_eq!(sender.pending_samples(), 0);
+        assert!(sender.unkey().is_empty());
+    }
+
+    #[test]
+    fn large_frames_are_split_under_the_maximum_pdu_size() {
+        let config = AudioSenderConfig::new(AudioCodec::Pcm16LittleEndian, 48_000)
+            .with_frame_duration(Duration::from_millis(100));
+        let mut sender = sender(config);
+        let start = Instant::now();
+        let _ = sender.key(start);
+        sender.push(&[7; 4_800]);
+
+        let output = sender.poll(start + Duration::from_millis(100));
+        let pdus = signals(&output);
+        assert_eq!(pdus.len(), 2);
+        assert_eq!(
+            pdus.iter()
+                .map(|pdu| usize::from(pdu.samples))
+                .sum::<usize>(),
+            4_800
+        );
+        for pdu in pdus {
+            let mut buf = BytesMut::new();
+            let _ = pdu.clone().serialize(&mut buf);
+            assert!(buf.len() <= MAX_PDU_SIZE_OCTETS);
+        }
+    }
+
+    #[test]
+    fn signal_pdus_are_timestamped_by_the_samples_sent() {
+        let config = AudioSenderConfig::new(AudioCodec::Pcm16LittleEndian, 48_000)
+            .with_frame_duration(Duration::from_millis(100));
+        let start = Instant::now();
+        let origin_timestamp = timestamp::from_units(timestamp::UNITS_PER_HOUR - 100_000, true);
+        let mut sender = sender(config).with_clock_origin(start, origin_timestamp);
+        let keyed_at = start + Duration::from_secs(1);
+        let _ = sender.key(keyed_at);
+        sender.push(&vec![7; 14_400]);
+
+        // Three frames caught up at once, each split in two PDUs
+        let output = sender.poll(keyed_at + Duration::from_millis(300));
+        let pdus = signals(&output);
+        assert_eq!(pdus.len(), 6);
+        let key_timestamp = timestamp::advance(
+            origin_timestamp,
+            timestamp::units_in(Duration::from_secs(1)),
+        );
+        assert_eq!(pdus[0].header.timestamp, key_timestamp);
+        assert!(
+            pdus.iter()
+                .all(|pdu| timestamp::is_absolute(pdu.header.timestamp))
+        );
+
+        let mut samples_sent = 0;
+        for pair in pdus.windows(2) {
+            let elapsed =
+                timestamp::units_between(pair[0].header.timestamp, pair[1].header.timestamp);
+            let before = timestamp::units_in_samples(samples_sent, 48_000);
+            samples_sent += usize::from(pair[0].samples);
+            let after = timestamp::units_in_samples(samples_sent, 48_000);
+            assert!(elapsed > 0);
+            assert_eq!(u64::from(elapsed), after - before);
+        }
+    }
+
+    #[test]
+    fn cvsd_state_continues_across_frames() {
+        let config = AudioSenderConfig::new(AudioCodec::Cvsd, 16_000);
+        let mut sender = sender(config);
+        let start = Instant::now();
+        let pcm: Vec<i16> = (0..640).map(|n| (n % 64) * 100).collect();
+        let _ = sender.key(start);
+        sender.push(&pcm);
+
+        let output = sender.poll(start + Duration::from_millis(40));
+        let data: Vec<u8> = signals(&output)
+            .iter()
+            .flat_map(|pdu| pdu.data.clone())
+            .collect();
+        assert_eq!(data, AudioCodec::Cvsd.encode(&pcm));
+    }
+}
@@ -6,8 +6,15 @@
 
 //! The Simulation Management (SIMAN) protocol family
 
+pub mod clock;
+pub mod manager;
+pub mod participant;
+pub mod requests;
+
 use crate::common::data_types::ClockTime;
-use crate::common::data_types::datum_records::{FixedDatumRecord, VariableDatumRecord};
+use crate::common::data_types::datum_records::{
+    FixedDatumRecord, VariableDatumRecord, impl_datum_set,
+};
 use crate::common::enums::{ActionResponseRequestStatus, EventType, FrozenBehavior, Reason};
 use crate::common::generic_header::GenericHeader;
 use crate::{
@@ -88,7 +95,7 @@ define_pdu! {
 }
 
 define_pdu! {
-    #[derive(Debug)]
+    #[derive(Clone, Debug)]
     /// Implemented according to IEEE 1278.1-2012 §7.5.6
     pub struct AcknowledgePdu {
         header: PduHeader,
@@ -105,7 +112,7 @@ define_pdu! {
 }
 
 define_pdu! {
-    #[derive(Debug)]
+    #[derive(Clone, Debug)]
     /// Implemented according to IEEE 1278.1-2012 §7.5.7
     pub struct ActionRequestPdu {
         header: PduHeader,
@@ -118,14 +125,16 @@ define_pdu! {
             pub action_id: u32,
             pub number_of_fixed_datum_records: u32,
             pub number_of_variable_datum_records: u32,
+            #[count = number_of_fixed_datum_records]
             pub fixed_datum_records: Vec<FixedDatumRecord>,
+            #[count = number_of_variable_datum_records]
             pub variable_datum_records: Vec<VariableDatumRecord>,
         }
     }
 }
 
 define_pdu! {
-    #[derive(Debug)]
+    #[derive(Clone, Debug)]
     /// Implemented according to IEEE 1278.1-2012 §7.5.8
     pub struct ActionResponsePdu {
         header: PduHeader,
@@ -138,14 +147,16 @@ define_pdu! {
             pub request_status: ActionResponseRequestStatus,
             pub number_of_fixed_datum_records: u32,
             pub number_of_variable_datum_records: u32,
+            #[count = number_of_fixed_datum_records]
             pub fixed_datum_records: Vec<FixedDatumRecord>,
+            #[count = number_of_variable_datum_records]
             pub variable_datum_records: Vec<VariableDatumRecord>,
         }
     }
 }
 
 define_pdu! {
-    #[derive(Debug)]
+    #[derive(Clone, Debug)]
     /// Implemented according to IEEE 1278.1-2012 §7.5.9
     pub struct DataQueryPdu {
         header: PduHeader,
@@ -158,14 +169,16 @@ define_pdu! {
             pub time_interval: u32,
             pub number_of_fixed_datum_records: u32,
             pub number_of_variable_datum_records: u32,
+            #[count = number_of_fixed_datum_records]
             pub fixed_datum_records: Vec<FixedDatumRecord>,
+            #[count = number_of_variable_datum_records]
             pub variable_datum_records: Vec<VariableDatumRecord>,
         }
     }
 }
 
 define_pdu! {
-    #[derive(Debug)]
+    #[derive(Clone, Debug)]
     /// Implemented according to IEEE 1278.1-2012 §7.5.10
     pub struct SetDataPdu {
         header: PduHeader,
@@ -178,14 +191,16 @@ define_pdu! {
             padding: u32,
             pub number_of_fixed_datum_records: u32,
             pub number_of_variable_datum_records: u32,
+            #[count = number_of_fixed_datum_records]
             pub fixed_datum_records: Vec<FixedDatumRecord>,
+            #[count = number_of_variable_datum_records]
             pub variable_datum_records: Vec<VariableDatumRecord>,
         }
     }
 }
 
 define_pdu! {
-    #[derive(Debug)]
+    #[derive(Clone, Debug)]
     /// Implemented according to IEEE 1278.1-2012 §7.5.11
     pub struct DataPdu {
         header: PduHeader,
@@ -198,7 +213,9 @@ define_pdu! {
             padding: u32,
             pub number_of_fixed_datum_records: u32,
             pub number_of_variable_datum_records: u32,
+            #[count = number_of_fixed_datum_records]
             pub fixed_datum_records: Vec<FixedDatumRecord>,
+            #[count = number_of_variable_datum_records]
             pub variable_datum_records: Vec<VariableDatumRecord>,
         }
     }
@@ -218,7 +235,9 @@ define_pdu! {
             padding: u32,
             pub number_of_fixed_datum_records: u32,
             pub number_of_variable_datum_records: u32,
+            #[count = number_of_fixed_datum_records]
             pub fixed_datum_records: Vec<FixedDatumRecord>,
+            #[count = number_of_variable_datum_records]
             pub variable_datum_records: Vec<VariableDatumRecord>,
         }
     }
@@ -236,12 +255,23 @@ define_pdu! {
             pub receiving_entity_id: EntityId,
             pub number_of_fixed_datum_records: u32,
             pub number_of_variable_datum_records: u32,
+            #[count = number_of_fixed_datum_records]
             pub fixed_datum_records: Vec<FixedDatumRecord>,
+            #[count = number_of_variable_datum_records]
             pub variable_datum_records: Vec<VariableDatumRecord>,
         }
     }
 }
 
+impl_datum_set!(
+    ActionRequestPdu,
+    ActionResponsePdu,
+    SetDataPdu,
+    DataPdu,
+    EventReportPdu,
+    CommentPdu,
+);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -425,6 +455,91 @@ mod tests {
             let pdu = ActionRequestPdu::new();
             assert_eq!(pdu.header().length, DEFAULT_LENGTH);
         }
+
+        #[test]
+        fn datum_set_round_trip() {
+            use crate::common::{
+                data_types::{
+                    EntityType, WorldCoordinate,
+                    datum_records::{DatumSet, VariableDatumRecord},
+                },
+                enums::VariableRecordTypes,
+            };
+
+            let location = WorldCoordinate::new(1.0, -2.0, 3.5);
+            let datums = DatumSet::new()
+                .with_fixed(VariableRecordTypes::EntityIDList, &-7i32)
+                .with_fixed(VariableRecordTypes::Velocity, &12.5f32)
+                .with_variable(VariableRecordTypes::Position, &location)
+                .with_variable(VariableRecordTypes::Marking, &"EAGLE 1".to_string())
+                .with_variable(VariableRecordTypes::EntityType1, &EntityType::default());
+            assert_eq!(datums.variable[1].length_bits, 56);
+            assert!(datums.validate().is_ok());
+
+            let mut pdu = ActionRequestPdu::new();
+            pdu.set_datum_set(datums);
+            let mut serialize_buf = BytesMut::new();
+            let _ = pdu.serialize(&mut serialize_buf);
+            // 8 octets per fixed datum; 8 + 24, 8 + 8 (7 padded) and 8 + 8 per variable datum
+            assert_eq!(usize::from(pdu.header().length), 40 + 2 * 8 + 32 + 16 + 16);
+            assert_eq!(serialize_buf.len(), usize::from(pdu.header().length));
+
+            let mut deserialize_buf = serialize_buf.freeze();
+            let new_pdu = ActionRequestPdu::deserialize(&mut deserialize_buf).unwrap_or_default();
+            let received = new_pdu.datum_set();
+            assert_eq!(
+                received.fixed::<i32>(VariableRecordTypes::EntityIDList),
+                Some(-7)
+            );
+            assert_eq!(
+                received.fixed::<f32>(VariableRecordTypes::Velocity),
+                Some(12.5)
+            );
+            assert_eq!(
+                received.variable::<WorldCoordinate>(VariableRecordTypes::Position),
+                Some(location)
+            );
+            assert_eq!(
+                received
+                    .variable::<String>(VariableRecordTypes::Marking)
+                    .as_deref(),
+                Some("EAGLE 1")
+            );
+            assert!(
+                received
+                    .variable::<f64>(VariableRecordTypes::Marking)
+                    .is_none()
+            );
+
+            let mut mismatched = VariableDatumRecord::new(VariableRecordTypes::Marking, &7u16);
+            mismatched.length_bits = 32;
+            assert!(mismatched.validate().is_err());
+
+            pdu.variable_datum_records.push(mismatched);
+            let mut refused = BytesMut::new();
+            assert!(pdu.serialize(&mut refused).is_err());
+            assert!(refused.is_empty());
+        }
+
+        #[test]
+        fn record_counts_beyond_the_buffer_are_rejected() {
+            use crate::common::dis_error::DISError;
+
+            let mut pdu = ActionRequestPdu::new();
+            let mut serialize_buf = BytesMut::new();
+            let _ = pdu.serialize(&mut serialize_buf);
+            // Number of fixed datum records, after the header, IDs, request ID and action ID
+            serialize_buf[32..36].copy_from_slice(&1000u32.to_be_bytes());
+
+            let mut deserialize_buf = serialize_buf.freeze();
+            assert!(matches!(
+                ActionRequestPdu::deserialize(&mut deserialize_buf),
+                Err(DISError::BufferUnderflow {
+                    attempted: 8,
+                    available: 0
+                })
+            ));
+        }
     }
 
     mod action_response_pdu_tests {
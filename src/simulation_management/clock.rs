@@ -0,0 +1,353 @@
+//     open-dis-rust - Rust implementation of the IEEE 1278.1-2012 Distributed Interactive
+//                     Simulation (DIS) application protocol
+//     Copyright (C) 2025 Cameron Howell
+//
+//     Licensed under the BSD 2-Clause License
+
+//! Simulation time driven by Start/Resume and Stop/Freeze PDUs, IEEE 1278.1-2012 §5.6.5.4 and
+//! §5.6.5.5
+//!
+//! A [`SimulationClock`] schedules the start or stop each PDU asks for at the PDU's real-world
+//! time, so every participant that applies the same PDUs agrees on scenario time. While running,
+//! simulation time advances with real-world time multiplied by an optional time scale; while
+//! frozen or stopped it holds. The clock does no I/O of its own: scheduled changes take effect
+//! when [`SimulationClock::poll`] is called at or after their time.
+
+use std::time::Duration;
+
+use chrono::{DateTime, TimeDelta, Utc};
+
+use crate::{
+    common::{
+        data_types::ClockTime,
+        dis_error::DISError,
+        enums::{FrozenBehavior, Reason},
+    },
+    simulation_management::{StartResumePdu, StopFreezePdu, participant::ParticipantState},
+};
+
+/// Whether simulation time is advancing
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ClockState {
+    /// Not started, or stopped for termination, reset or restart
+    #[default]
+    Stopped,
+    Running,
+    Frozen,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Change {
+    Start { simulation_time: Duration },
+    Stop { state: ClockState },
+}
+
+#[derive(Copy, Clone, Debug)]
+struct ScheduledChange {
+    at: DateTime<Utc>,
+    change: Change,
+}
+
+/// Scenario time shared by the participants of an exercise
+#[derive(Clone, Debug)]
+pub struct SimulationClock {
+    state: ClockState,
+    time_scale: f64,
+    /// Real-world time at which `anchor_simulation_time` was reached
+    anchor_real_world_time: DateTime<Utc>,
+    anchor_simulation_time: Duration,
+    scheduled: Vec<ScheduledChange>,
+}
+
+impl Default for SimulationClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SimulationClock {
+    /// A stopped clock at simulation time zero, running at real-world speed once started
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            state: ClockState::Stopped,
+            time_scale: 1.0,
+            anchor_real_world_time: DateTime::UNIX_EPOCH,
+            anchor_simulation_time: Duration::ZERO,
+            scheduled: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub const fn state(&self) -> ClockState {
+        self.state
+    }
+
+    /// Simulation seconds per real-world second
+    #[must_use]
+    pub const fn time_scale(&self) -> f64 {
+        self.time_scale
+    }
+
+    /// Changes the time scale from `now` on, keeping the simulation time already elapsed
+    ///
+    /// # Errors
+    /// Returns `DISError::InvalidFieldValue` if the scale is not a positive finite number.
+    pub fn set_time_scale(&mut self, time_scale: f64, now: DateTime<Utc>) -> Result<(), DISError> {
+        if !time_scale.is_finite() || time_scale <= 0.0 {
+            return Err(DISError::invalid_field(
+                "time_scale".to_string(),
+                time_scale.to_string(),
+                "must be positive and finite".to_string(),
+            ));
+        }
+        self.anchor_simulation_time = self.simulation_time_at(now);
+        self.anchor_real_world_time = now;
+        self.time_scale = time_scale;
+        Ok(())
+    }
+
+    /// Schedules the start a Start/Resume PDU asks for
+    ///
+    /// The clock runs from the PDU's simulation time at its real-world time.
+    ///
+    /// # Errors
+    /// Returns `DISError::InvalidFieldValue` if the real-world time is not a valid UTC time.
+    pub fn handle_start_resume(&mut self, pdu: &StartResumePdu) -> Result<(), DISError> {
+        let at = DateTime::<Utc>::try_from(pdu.real_world_time)?;
+        self.schedule(
+            at,
+            Change::Start {
+                simulation_time: pdu.simulation_time.to_duration(),
+            },
+        );
+        Ok(())
+    }
+
+    /// Schedules the stop or freeze a Stop/Freeze PDU asks for
+    ///
+    /// The clock keeps running if the frozen behavior says to run the simulation clock.
+    ///
+    /// # Errors
+    /// Returns `DISError::InvalidFieldValue` if the real-world time is not a valid UTC time.
+    pub fn handle_stop_freeze(&mut self, pdu: &StopFreezePdu) -> Result<(), DISError> {
+        let at = DateTime::<Utc>::try_from(pdu.real_world_time)?;
+        self.schedule(
+            at,
+            Change::Stop {
+                state: stopped_state(pdu.reason, pdu.frozen_behavior),
+            },
+        );
+        Ok(())
+    }
+
+    /// Real-world time of the next scheduled start or stop
+    #[must_use]
+    pub fn next_change(&self) -> Option<DateTime<Utc>> {
+        self.scheduled.first().map(|scheduled| scheduled.at)
+    }
+
+    /// Applies the starts and stops scheduled at or before `now`; returns whether the state
+    /// changed
+    pub fn poll(&mut self, now: DateTime<Utc>) -> bool {
+        let before = self.state;
+        while let Some(scheduled) = self.scheduled.first().copied() {
+            if scheduled.at > now {
+                break;
+            }
+            self.scheduled.remove(0);
+            self.apply(scheduled);
+        }
+        self.state != before
+    }
+
+    /// Simulation time at real-world time `real_world_time`, given the current state
+    #[must_use]
+    pub fn simulation_time_at(&self, real_world_time: DateTime<Utc>) -> Duration {
+        if self.state != ClockState::Running {
+            return self.anchor_simulation_time;
+        }
+        let elapsed = real_world_time - self.anchor_real_world_time;
+        let scaled = |delta: TimeDelta| delta.to_std().unwrap_or_default().mul_f64(self.time_scale);
+        if elapsed >= TimeDelta::zero() {
+            self.anchor_simulation_time + scaled(elapsed)
+        } else {
+            self.anchor_simulation_time.saturating_sub(scaled(-elapsed))
+        }
+    }
+
+    /// Real-world time at which the running clock reads `simulation_time`
+    ///
+    /// Returns `None` unless the clock is running.
+    #[must_use]
+    pub fn real_world_time_at(&self, simulation_time: Duration) -> Option<DateTime<Utc>> {
+        if self.state != ClockState::Running {
+            return None;
+        }
+        let scaled = |delta: Duration| TimeDelta::from_std(delta.div_f64(self.time_scale)).ok();
+        let offset = match simulation_time.checked_sub(self.anchor_simulation_time) {
+            Some(ahead) => scaled(ahead)?,
+            None => -scaled(self.anchor_simulation_time.saturating_sub(simulation_time))?,
+        };
+        self.anchor_real_world_time.checked_add_signed(offset)
+    }
+
+    /// Converts an absolute real-world clock time to a relative simulation clock time
+    ///
+    /// # Errors
+    /// Returns `DISError::InvalidFieldValue` if the real-world time is not a valid UTC time.
+    pub fn to_simulation_time(&self, real_world_time: ClockTime) -> Result<ClockTime, DISError> {
+        let real_world_time = DateTime::<Utc>::try_from(real_world_time)?;
+        Ok(ClockTime::from_duration(
+            self.simulation_time_at(real_world_time),
+            false,
+        ))
+    }
+
+    /// Converts a relative simulation clock time to an absolute real-world clock time
+    ///
+    /// Returns `None` unless the clock is running.
+    #[must_use]
+    pub fn to_real_world_time(&self, simulation_time: ClockTime) -> Option<ClockTime> {
+        self.real_world_time_at(simulation_time.to_duration())
+            .and_then(|time| ClockTime::try_from(time).ok())
+    }
+
+    fn schedule(&mut self, at: DateTime<Utc>, change: Change) {
+        let index = self
+            .scheduled
+            .partition_point(|scheduled| scheduled.at <= at);
+        self.scheduled.insert(index, ScheduledChange { at, change });
+    }
+
+    fn apply(&mut self, scheduled: ScheduledChange) {
+        match scheduled.change {
+            Change::Start { simulation_time } => {
+                self.anchor_simulation_time = simulation_time;
+                self.state = ClockState::Running;
+            }
+            Change::Stop { state } => {
+                self.anchor_simulation_time = self.simulation_time_at(scheduled.at);
+                self.state = state;
+            }
+        }
+        self.anchor_real_world_time = scheduled.at;
+    }
+}
+
+fn stopped_state(reason: Reason, frozen_behavior: FrozenBehavior) -> ClockState {
+    let state = ParticipantState::after_stop_freeze(reason, frozen_behavior);
+    if state.runs_simulation_clock() {
+        ClockState::Running
+    } else if state == ParticipantState::Stopped {
+        ClockState::Stopped
+    } else {
+        ClockState::Frozen
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn at(minute: u32, second: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 6, 1, 12, minute, second)
+            .single()
+            .expect("a valid date")
+    }
+
+    fn start(real_world_time: DateTime<Utc>, simulation_time: Duration) -> StartResumePdu {
+        let mut pdu = StartResumePdu::new();
+        pdu.real_world_time = ClockTime::try_from(real_world_time).expect("after the epoch");
+        pdu.simulation_time = ClockTime::from_duration(simulation_time, false);
+        pdu
+    }
+
+    fn stop(real_world_time: DateTime<Utc>, reason: Reason) -> StopFreezePdu {
+        let mut pdu = StopFreezePdu::new();
+        pdu.real_world_time = ClockTime::try_from(real_world_time).expect("after the epoch");
+        pdu.reason = reason;
+        pdu
+    }
+
+    #[test]
+    fn starts_and_freezes_at_the_scheduled_times() {
+        let mut clock = SimulationClock::new();
+        clock
+            .handle_stop_freeze(&stop(at(7, 30), Reason::Recess))
+            .expect("valid time");
+        clock
+            .handle_start_resume(&start(at(3, 45), Duration::from_hours(2)))
+            .expect("valid time");
+        assert_eq!(clock.next_change(), Some(at(3, 45)));
+
+        assert!(!clock.poll(at(3, 44)));
+        assert_eq!(clock.state(), ClockState::Stopped);
+
+        // Polled late, the clock still counts from the scheduled start
+        assert!(clock.poll(at(4, 15)));
+        assert_eq!(clock.state(), ClockState::Running);
+        assert_eq!(
+            clock.simulation_time_at(at(4, 15)),
+            Duration::from_hours(2) + Duration::from_secs(30)
+        );
+        assert_eq!(clock.next_change(), Some(at(7, 30)));
+
+        assert!(clock.poll(at(11, 0)));
+        assert_eq!(clock.state(), ClockState::Frozen);
+        assert_eq!(
+            clock.simulation_time_at(at(30, 0)),
+            Duration::from_hours(2) + Duration::from_secs(225)
+        );
+        assert_eq!(clock.real_world_time_at(Duration::from_hours(2)), None);
+    }
+
+    #[test]
+    fn time_scale_converts_between_real_and_simulation_time() {
+        let mut clock = SimulationClock::new();
+        clock
+            .handle_start_resume(&start(at(0, 0), Duration::ZERO))
+            .expect("valid time");
+        clock.poll(at(0, 0));
+        clock.set_time_scale(4.0, at(3, 45)).expect("a valid scale");
+        assert!(clock.set_time_scale(0.0, at(3, 45)).is_err());
+
+        let expected = Duration::from_secs(225 + 4 * 225);
+        assert_eq!(clock.simulation_time_at(at(7, 30)), expected);
+        assert_eq!(clock.real_world_time_at(expected), Some(at(7, 30)));
+
+        let real_world_time = ClockTime::try_from(at(7, 30)).expect("after the epoch");
+        let simulation_time = clock
+            .to_simulation_time(real_world_time)
+            .expect("valid time");
+        assert!(!simulation_time.is_absolute());
+        assert_eq!(simulation_time.to_duration(), expected);
+        assert_eq!(
+            clock.to_real_world_time(simulation_time),
+            Some(real_world_time)
+        );
+    }
+
+    #[test]
+    fn frozen_behavior_can_keep_the_clock_running() {
+        let mut clock = SimulationClock::new();
+        clock
+            .handle_start_resume(&start(at(0, 0), Duration::ZERO))
+            .expect("valid time");
+        let mut freeze = stop(at(3, 45), Reason::Recess);
+        freeze.frozen_behavior = FrozenBehavior::RunSimulationClock;
+        clock.handle_stop_freeze(&freeze).expect("valid time");
+        clock.poll(at(5, 0));
+        assert_eq!(clock.state(), ClockState::Running);
+        assert_eq!(clock.simulation_time_at(at(5, 0)), Duration::from_mins(5));
+
+        clock
+            .handle_stop_freeze(&stop(at(7, 30), Reason::Termination))
+            .expect("valid time");
+        clock.poll(at(7, 30));
+        assert_eq!(clock.state(), ClockState::Stopped);
+        assert_eq!(clock.simulation_time_at(at(9, 0)), Duration::from_secs(450));
+    }
+}
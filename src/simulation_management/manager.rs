@@ -0,0 +1,575 @@
+//     open-dis-rust - Rust implementation of the IEEE 1278.1-2012 Distributed Interactive
+//                     Simulation (DIS) application protocol
+//     Copyright (C) 2025 Cameron Howell
+//
+//     Licensed under the BSD 2-Clause License
+
+//! The simulation manager role of simulation management, IEEE 1278.1-2012 §5.6.5
+//!
+//! A [`SimulationManager`] builds the Start/Resume, Stop/Freeze, Create Entity and Remove
+//! Entity PDUs sent to a [`Target`], which may address every site or application through the
+//! `ALL_SITES` and `ALL_APPLIC` wildcards. It collects the Acknowledge PDUs of the registered
+//! participants the target addresses, completes a command once all of them have answered or its
+//! timeout expires, and tracks the state of each participant from the commands it complied
+//! with. It also assigns entity IDs to simulations that ask for one with a Create Entity PDU
+//! originating from the `RQST_ASSIGN_ID` entity number. The manager does no I/O of its own;
+//! time is passed in by the caller.
+//!
+//! [`Target`]: crate::common::addressing::Target
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    common::{
+        constants::RQST_ASSIGN_ID,
+        data_types::{ClockTime, entity_id::EntityId, simulation_address::SimulationAddress},
+        enums::{AcknowledgeFlag, AcknowledgeResponseFlag, FrozenBehavior, Reason},
+        id_allocator::{DEFAULT_ENTITY_ID_REUSE_DELAY, EntityIdAllocator},
+    },
+    simulation_management::{
+        AcknowledgePdu, CreateEntityPdu, RemoveEntityPdu, StartResumePdu, StopFreezePdu,
+        participant::ParticipantState,
+    },
+};
+
+/// Default time to wait for the acknowledgements of a command
+pub const DEFAULT_ACKNOWLEDGE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A command sent by the simulation manager
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Command {
+    StartResume,
+    StopFreeze {
+        reason: Reason,
+        frozen_behavior: FrozenBehavior,
+    },
+    CreateEntity,
+    RemoveEntity,
+}
+
+impl Command {
+    /// The acknowledge flag of the Acknowledge PDUs that answer this command
+    #[must_use]
+    pub const fn acknowledge_flag(&self) -> AcknowledgeFlag {
+        match self {
+            Self::StartResume => AcknowledgeFlag::StartResume,
+            Self::StopFreeze { .. } => AcknowledgeFlag::StopFreeze,
+            Self::CreateEntity => AcknowledgeFlag::CreateEntity,
+            Self::RemoveEntity => AcknowledgeFlag::RemoveEntity,
+        }
+    }
+}
+
+/// The answers collected for a command once all participants answered or the timeout expired
+#[derive(Clone, Debug)]
+pub struct CommandResult {
+    pub request_id: u32,
+    pub command: Command,
+    pub target: EntityId,
+    pub acknowledgements: Vec<(SimulationAddress, AcknowledgeResponseFlag)>,
+    /// Participants that did not answer before the timeout
+    pub missing: Vec<SimulationAddress>,
+}
+
+impl CommandResult {
+    /// Whether every addressed participant answered that it is able to comply
+    #[must_use]
+    pub fn all_complied(&self) -> bool {
+        self.missing.is_empty()
+            && self
+                .acknowledgements
+                .iter()
+                .all(|(_, flag)| *flag == AcknowledgeResponseFlag::AbleToComply)
+    }
+}
+
+#[derive(Debug)]
+struct PendingCommand {
+    command: Command,
+    target: EntityId,
+    expected: Vec<SimulationAddress>,
+    acknowledgements: Vec<(SimulationAddress, AcknowledgeResponseFlag)>,
+    deadline: Instant,
+}
+
+impl PendingCommand {
+    fn is_complete(&self) -> bool {
+        self.expected.iter().all(|participant| {
+            self.acknowledgements
+                .iter()
+                .any(|(address, _)| address == participant)
+        })
+    }
+
+    fn into_result(self, request_id: u32) -> CommandResult {
+        let missing = self
+            .expected
+            .iter()
+            .filter(|participant| {
+                !self
+                    .acknowledgements
+                    .iter()
+                    .any(|(address, _)| address == *participant)
+            })
+            .copied()
+            .collect();
+        CommandResult {
+            request_id,
+            command: self.command,
+            target: self.target,
+            acknowledgements: self.acknowledgements,
+            missing,
+        }
+    }
+}
+
+/// Sends simulation management commands and collects their acknowledgements
+#[derive(Debug)]
+pub struct SimulationManager {
+    manager_id: EntityId,
+    acknowledge_timeout: Duration,
+    next_request_id: u32,
+    participants: HashMap<SimulationAddress, ParticipantState>,
+    pending: BTreeMap<u32, PendingCommand>,
+    entity_id_reuse_delay: Duration,
+    entity_ids: HashMap<SimulationAddress, EntityIdAllocator>,
+    /// Entity IDs assigned, by requesting simulation and request ID, so a retransmitted
+    /// request is answered with the same ID
+    assigned_ids: HashMap<(SimulationAddress, u32), EntityId>,
+}
+
+impl SimulationManager {
+    /// A manager sending from `manager_id`
+    #[must_use]
+    pub fn new(manager_id: EntityId) -> Self {
+        Self {
+            manager_id,
+            acknowledge_timeout: DEFAULT_ACKNOWLEDGE_TIMEOUT,
+            next_request_id: 1,
+            participants: HashMap::new(),
+            pending: BTreeMap::new(),
+            entity_id_reuse_delay: DEFAULT_ENTITY_ID_REUSE_DELAY,
+            entity_ids: HashMap::new(),
+            assigned_ids: HashMap::new(),
+        }
+    }
+
+    #[must_use]
+    pub const fn with_acknowledge_timeout(mut self, timeout: Duration) -> Self {
+        self.acknowledge_timeout = timeout;
+        self
+    }
+
+    /// Sets how long a released entity ID is held back before it is assigned again
+    #[must_use]
+    pub const fn with_entity_id_reuse_delay(mut self, reuse_delay: Duration) -> Self {
+        self.entity_id_reuse_delay = reuse_delay;
+        self
+    }
+
+    #[must_use]
+    pub const fn manager_id(&self) -> EntityId {
+        self.manager_id
+    }
+
+    /// Registers a simulation whose acknowledgements are collected for the commands addressed
+    /// to it
+    pub fn add_participant(&mut self, address: SimulationAddress) {
+        self.participants.entry(address).or_default();
+    }
+
+    pub fn remove_participant(&mut self, address: SimulationAddress) -> Option<ParticipantState> {
+        self.participants.remove(&address)
+    }
+
+    /// The state of a participant, as far as the manager knows from its acknowledgements
+    #[must_use]
+    pub fn state(&self, address: SimulationAddress) -> Option<ParticipantState> {
+        self.participants.get(&address).copied()
+    }
+
+    pub fn participants(&self) -> impl Iterator<Item = (SimulationAddress, ParticipantState)> {
+        self.participants
+            .iter()
+            .map(|(address, state)| (*address, *state))
+    }
+
+    #[must_use]
+    pub fn is_pending(&self, request_id: u32) -> bool {
+        self.pending.contains_key(&request_id)
+    }
+
+    /// Earliest time a pending command times out
+    #[must_use]
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.pending.values().map(|pending| pending.deadline).min()
+    }
+
+    /// Builds a Start/Resume PDU for `target`
+    pub fn start_resume(
+        &mut self,
+        target: impl Into<EntityId>,
+        real_world_time: ClockTime,
+        simulation_time: ClockTime,
+        now: Instant,
+    ) -> StartResumePdu {
+        let target = target.into();
+        let mut pdu = StartResumePdu::new();
+        pdu.originating_entity_id = self.manager_id;
+        pdu.receiving_entity_id = target;
+        pdu.real_world_time = real_world_time;
+        pdu.simulation_time = simulation_time;
+        pdu.request_id = self.track(Command::StartResume, target, now);
+        pdu
+    }
+
+    /// Builds a Stop/Freeze PDU for `target`
+    pub fn stop_freeze(
+        &mut self,
+        target: impl Into<EntityId>,
+        real_world_time: ClockTime,
+        reason: Reason,
+        frozen_behavior: FrozenBehavior,
+        now: Instant,
+    ) -> StopFreezePdu {
+        let target = target.into();
+        let mut pdu = StopFreezePdu::new();
+        pdu.originating_entity_id = self.manager_id;
+        pdu.receiving_entity_id = target;
+        pdu.real_world_time = real_world_time;
+        pdu.reason = reason;
+        pdu.frozen_behavior = frozen_behavior;
+        pdu.request_id = self.track(
+            Command::StopFreeze {
+                reason,
+                frozen_behavior,
+            },
+            target,
+            now,
+        );
+        pdu
+    }
+
+    /// Builds a Create Entity PDU for `target`
+    pub fn create_entity(&mut self, target: impl Into<EntityId>, now: Instant) -> CreateEntityPdu {
+        let target = target.into();
+        let mut pdu = CreateEntityPdu::new();
+        pdu.originating_entity_id = self.manager_id;
+        pdu.receiving_entity_id = target;
+        pdu.request_id = self.track(Command::CreateEntity, target, now);
+        pdu
+    }
+
+    /// Builds a Remove Entity PDU for `target`
+    pub fn remove_entity(&mut self, target: impl Into<EntityId>, now: Instant) -> RemoveEntityPdu {
+        let target = target.into();
+        let mut pdu = RemoveEntityPdu::new();
+        pdu.originating_entity_id = self.manager_id;
+        pdu.receiving_entity_id = target;
+        pdu.request_id = self.track(Command::RemoveEntity, target, now);
+        pdu
+    }
+
+    fn track(&mut self, command: Command, target: EntityId, now: Instant) -> u32 {
+        let request_id = self.next_request_id;
+        self.next_request_id = self.next_request_id.wrapping_add(1).max(1);
+
+        let mut expected: Vec<SimulationAddress> = self
+            .participants
+            .keys()
+            .filter(|address| target.addresses_simulation(**address))
+            .copied()
+            .collect();
+        // A command for a specific simulation that is not registered still expects its answer
+        let address = target.simulation_address;
+        if expected.is_empty() && !address.is_wildcard() {
+            self.participants.entry(address).or_default();
+            expected.push(address);
+        }
+        expected.sort_by_key(|address| (address.site_id, address.application_id));
+
+        self.pending.insert(
+            request_id,
+            PendingCommand {
+                command,
+                target,
+                expected,
+                acknowledgements: Vec::new(),
+                deadline: now + self.acknowledge_timeout,
+            },
+        );
+        request_id
+    }
+
+    /// Answers a Create Entity PDU asking the manager to assign an entity ID
+    ///
+    /// A simulation asks by sending from its own site and application with the
+    /// `RQST_ASSIGN_ID` entity number. The acknowledgement is addressed to the assigned ID, or
+    /// back to the requesting ID with `UnableToComply` if the simulation has no entity numbers
+    /// left. A retransmitted request, with the same request ID, is answered with the ID already
+    /// assigned to it. Returns `None` for Create Entity PDUs that do not ask for an ID.
+    pub fn handle_create_entity(
+        &mut self,
+        pdu: &CreateEntityPdu,
+        now: Instant,
+    ) -> Option<AcknowledgePdu> {
+        let requester = pdu.originating_entity_id;
+        if requester.entity_id != RQST_ASSIGN_ID {
+            return None;
+        }
+        let address = requester.simulation_address;
+        let request = (address, pdu.request_id);
+        let assigned = if let Some(assigned) = self.assigned_ids.get(&request) {
+            Some(*assigned)
+        } else {
+            let reuse_delay = self.entity_id_reuse_delay;
+            let assigned = self
+                .entity_ids
+                .entry(address)
+                .or_insert_with(|| EntityIdAllocator::new(address).with_reuse_delay(reuse_delay))
+                .allocate(now);
+            if let Some(assigned) = assigned {
+                self.assigned_ids.insert(request, assigned);
+            }
+            assigned
+        };
+
+        let mut ack = AcknowledgePdu::new();
+        ack.originating_entity_id = self.manager_id;
+        ack.acknowledge_flag = AcknowledgeFlag::CreateEntity;
+        ack.request_id = pdu.request_id;
+        if let Some(assigned) = assigned {
+            ack.receiving_entity_id = assigned;
+            ack.response_flag = AcknowledgeResponseFlag::AbleToComply;
+        } else {
+            ack.receiving_entity_id = requester;
+            ack.response_flag = AcknowledgeResponseFlag::UnableToComply;
+        }
+        Some(ack)
+    }
+
+    /// Returns an assigned entity ID for reassignment once the reuse delay has passed; returns
+    /// whether the manager had assigned it
+    pub fn release_entity_id(&mut self, id: EntityId, now: Instant) -> bool {
+        self.assigned_ids.retain(|_, assigned| *assigned != id);
+        self.entity_ids
+            .get_mut(&id.simulation_address)
+            .is_some_and(|allocator| allocator.release(id, now))
+    }
+
+    /// Records an Acknowledge PDU
+    ///
+    /// Returns the result of the command once every addressed participant has answered.
+    /// Acknowledgements that answer no pending command are ignored.
+    pub fn handle_acknowledge(&mut self, pdu: &AcknowledgePdu) -> Option<CommandResult> {
+        let request_id = pdu.request_id;
+        let pending = self.pending.get_mut(&request_id)?;
+        if pending.command.acknowledge_flag() != pdu.acknowledge_flag {
+            return None;
+        }
+
+        let address = pdu.originating_entity_id.simulation_address;
+        if let Some(entry) = pending
+            .acknowledgements
+            .iter_mut()
+            .find(|(participant, _)| *participant == address)
+        {
+            entry.1 = pdu.response_flag;
+        } else {
+            pending.acknowledgements.push((address, pdu.response_flag));
+        }
+
+        if pdu.response_flag == AcknowledgeResponseFlag::AbleToComply {
+            let state = self.participants.entry(address).or_default();
+            match pending.command {
+                Command::StartResume => *state = ParticipantState::Running,
+                Command::StopFreeze {
+                    reason,
+                    frozen_behavior,
+                } => *state = ParticipantState::after_stop_freeze(reason, frozen_behavior),
+                Command::CreateEntity | Command::RemoveEntity => {}
+            }
+        }
+
+        if !pending.is_complete() || pending.expected.is_empty() {
+            return None;
+        }
+        self.pending
+            .remove(&request_id)
+            .map(|pending| pending.into_result(request_id))
+    }
+
+    /// Completes the commands whose timeout has expired at `now`
+    pub fn poll(&mut self, now: Instant) -> Vec<CommandResult> {
+        self.pending
+            .extract_if(.., |_, pending| pending.deadline <= now)
+            .map(|(request_id, pending)| pending.into_result(request_id))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        common::{addressing::Target, constants::NO_ENTITY},
+        simulation_management::participant::{
+            SimulationControl, SimulationParticipant, assigned_entity_id,
+        },
+    };
+
+    struct Refuses;
+
+    impl SimulationControl for Refuses {
+        fn start_resume(&mut self, _pdu: &StartResumePdu) -> AcknowledgeResponseFlag {
+            AcknowledgeResponseFlag::UnableToComply
+        }
+    }
+
+    struct Complies;
+
+    impl SimulationControl for Complies {}
+
+    const EVERYONE: Target = Target::AllSimulations;
+
+    fn manager() -> SimulationManager {
+        let mut manager = SimulationManager::new(EntityId::new(1, 1, NO_ENTITY));
+        manager.add_participant(SimulationAddress::new(1, 2));
+        manager.add_participant(SimulationAddress::new(1, 3));
+        manager
+    }
+
+    #[test]
+    fn start_completes_when_every_participant_acknowledges() {
+        let now = Instant::now();
+        let mut manager = manager();
+        let mut first = SimulationParticipant::new(SimulationAddress::new(1, 2), Complies);
+        let mut second = SimulationParticipant::new(SimulationAddress::new(1, 3), Complies);
+
+        let start = manager.start_resume(EVERYONE, ClockTime::default(), ClockTime::default(), now);
+        let ack = first.handle_start_resume(&start).expect("addressed");
+        assert!(manager.handle_acknowledge(&ack).is_none());
+        let ack = second.handle_start_resume(&start).expect("addressed");
+        let result = manager
+            .handle_acknowledge(&ack)
+            .expect("every participant answered");
+        assert!(result.all_complied());
+        assert_eq!(
+            manager.state(SimulationAddress::new(1, 3)),
+            Some(ParticipantState::Running)
+        );
+    }
+
+    #[test]
+    fn missing_acknowledgements_time_out() {
+        let now = Instant::now();
+        let mut manager = manager();
+        let mut refuses = SimulationParticipant::new(SimulationAddress::new(1, 2), Refuses);
+
+        let start = manager.start_resume(EVERYONE, ClockTime::default(), ClockTime::default(), now);
+        let ack = refuses.handle_start_resume(&start).expect("addressed");
+        assert_eq!(ack.response_flag, AcknowledgeResponseFlag::UnableToComply);
+        assert!(manager.handle_acknowledge(&ack).is_none());
+        assert_eq!(
+            manager.state(SimulationAddress::new(1, 2)),
+            Some(ParticipantState::Stopped)
+        );
+
+        assert!(manager.poll(now + Duration::from_secs(4)).is_empty());
+        let results = manager.poll(now + DEFAULT_ACKNOWLEDGE_TIMEOUT);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].missing, vec![SimulationAddress::new(1, 3)]);
+        assert!(!results[0].all_complied());
+    }
+
+    #[test]
+    fn freeze_of_one_simulation_tracks_its_state() {
+        let now = Instant::now();
+        let mut manager = manager();
+        let mut participant = SimulationParticipant::new(SimulationAddress::new(1, 3), Complies);
+        let behavior = FrozenBehavior::ProcessUpdates;
+
+        let stop = manager.stop_freeze(
+            Target::Simulation(SimulationAddress::new(1, 3)),
+            ClockTime::default(),
+            Reason::Recess,
+            behavior,
+            now,
+        );
+        let ack = participant.handle_stop_freeze(&stop).expect("addressed");
+        let result = manager.handle_acknowledge(&ack).expect("only participant");
+        assert_eq!(result.acknowledgements.len(), 1);
+        assert_eq!(
+            manager.state(SimulationAddress::new(1, 3)),
+            Some(ParticipantState::Frozen(behavior))
+        );
+        assert_eq!(participant.state(), ParticipantState::Frozen(behavior));
+    }
+
+    #[test]
+    fn unregistered_simulation_is_expected_when_addressed_directly() {
+        let now = Instant::now();
+        let mut manager = SimulationManager::new(EntityId::new(1, 1, NO_ENTITY));
+        let mut participant = SimulationParticipant::new(SimulationAddress::new(4, 4), Complies);
+        let remove = manager.remove_entity(EntityId::new(4, 4, 12), now);
+        let ack = participant
+            .handle_remove_entity(&remove)
+            .expect("addressed");
+        let result = manager.handle_acknowledge(&ack).expect("answered");
+        assert_eq!(
+            result.acknowledgements,
+            vec![(
+                SimulationAddress::new(4, 4),
+                AcknowledgeResponseFlag::UnableToComply
+            )]
+        );
+    }
+
+    #[test]
+    fn assigns_entity_ids_on_request() {
+        let now = Instant::now();
+        let reuse_delay = Duration::from_secs(10);
+        let mut manager = SimulationManager::new(EntityId::new(1, 1, NO_ENTITY))
+            .with_entity_id_reuse_delay(reuse_delay);
+        let participant = SimulationParticipant::new(SimulationAddress::new(4, 4), Complies);
+
+        let request = participant.request_entity_id(manager.manager_id(), 7);
+        assert_eq!(
+            request.originating_entity_id,
+            EntityId::new(4, 4, RQST_ASSIGN_ID)
+        );
+        let ack = manager
+            .handle_create_entity(&request, now)
+            .expect("asks for an ID");
+        assert_eq!(ack.request_id, 7);
+        let first = assigned_entity_id(&ack).expect("assigned");
+        assert_eq!(first, EntityId::new(4, 4, 1));
+
+        // A retransmission of the same request gets the same ID
+        let ack = manager
+            .handle_create_entity(&request, now)
+            .expect("asks for an ID");
+        assert_eq!(assigned_entity_id(&ack), Some(first));
+
+        let another = participant.request_entity_id(manager.manager_id(), 8);
+        let ack = manager
+            .handle_create_entity(&another, now)
+            .expect("asks for an ID");
+        assert_eq!(assigned_entity_id(&ack), Some(EntityId::new(4, 4, 2)));
+
+        assert!(manager.release_entity_id(first, now));
+        assert!(!manager.release_entity_id(EntityId::new(4, 5, 1), now));
+        // A released ID is no longer the answer to the request that was given it
+        let ack = manager
+            .handle_create_entity(&request, now)
+            .expect("asks for an ID");
+        assert_eq!(assigned_entity_id(&ack), Some(EntityId::new(4, 4, 3)));
+
+        let mut create = CreateEntityPdu::new();
+        create.originating_entity_id = EntityId::new(4, 4, 9);
+        assert!(manager.handle_create_entity(&create, now).is_none());
+    }
+}
@@ -0,0 +1,328 @@
+//     open-dis-rust - Rust implementation of the IEEE 1278.1-2012 Distributed Interactive
+//                     Simulation (DIS) application protocol
+//     Copyright (C) 2025 Cameron Howell
+//
+//     Licensed under the BSD 2-Clause License
+
+//! The simulation side of simulation management, IEEE 1278.1-2012 §5.6.5
+//!
+//! A [`SimulationParticipant`] answers the Start/Resume, Stop/Freeze, Create Entity and Remove
+//! Entity PDUs addressed to its simulation with Acknowledge PDUs, asking a user-supplied
+//! [`SimulationControl`] whether it can comply, and tracks whether the simulation is running,
+//! frozen or stopped. It does no I/O of its own.
+
+use crate::{
+    common::{
+        constants::{NO_ENTITY, RQST_ASSIGN_ID},
+        data_types::{entity_id::EntityId, simulation_address::SimulationAddress},
+        enums::{AcknowledgeFlag, AcknowledgeResponseFlag, FrozenBehavior, Reason},
+        id_allocator::is_reserved_entity_number,
+    },
+    simulation_management::{
+        AcknowledgePdu, CreateEntityPdu, RemoveEntityPdu, StartResumePdu, StopFreezePdu,
+    },
+};
+
+/// Whether a simulation is taking part in the exercise
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ParticipantState {
+    /// Not started, or stopped for termination, reset or restart
+    #[default]
+    Stopped,
+    Running,
+    /// Frozen; the flags say what the simulation keeps doing while frozen
+    Frozen(FrozenBehavior),
+}
+
+impl ParticipantState {
+    /// The state a simulation is in after complying with a Stop/Freeze PDU
+    #[must_use]
+    pub const fn after_stop_freeze(reason: Reason, frozen_behavior: FrozenBehavior) -> Self {
+        match reason {
+            Reason::Termination | Reason::StopForReset | Reason::StopForRestart => Self::Stopped,
+            _ => Self::Frozen(frozen_behavior),
+        }
+    }
+
+    /// Whether the simulation clock advances
+    #[must_use]
+    pub const fn runs_simulation_clock(&self) -> bool {
+        match self {
+            Self::Running => true,
+            Self::Frozen(behavior) => behavior.contains(FrozenBehavior::RunSimulationClock),
+            Self::Stopped => false,
+        }
+    }
+
+    /// Whether the simulation sends updates for its entities
+    #[must_use]
+    pub const fn transmits_updates(&self) -> bool {
+        match self {
+            Self::Running => true,
+            Self::Frozen(behavior) => behavior.contains(FrozenBehavior::TransmitUpdates),
+            Self::Stopped => false,
+        }
+    }
+
+    /// Whether the simulation processes updates received for other entities
+    #[must_use]
+    pub const fn processes_updates(&self) -> bool {
+        match self {
+            Self::Running => true,
+            Self::Frozen(behavior) => behavior.contains(FrozenBehavior::ProcessUpdates),
+            Self::Stopped => false,
+        }
+    }
+}
+
+/// The entity ID a simulation manager assigned in answer to
+/// [`SimulationParticipant::request_entity_id`], or `None` if it refused
+#[must_use]
+pub fn assigned_entity_id(ack: &AcknowledgePdu) -> Option<EntityId> {
+    (ack.acknowledge_flag == AcknowledgeFlag::CreateEntity
+        && ack.response_flag == AcknowledgeResponseFlag::AbleToComply
+        && !is_reserved_entity_number(ack.receiving_entity_id.entity_id))
+    .then_some(ack.receiving_entity_id)
+}
+
+/// The application's answers to simulation management requests
+///
+/// Start/Resume and Stop/Freeze are complied with unless overridden; Create Entity and Remove
+/// Entity are refused unless overridden.
+pub trait SimulationControl {
+    fn start_resume(&mut self, pdu: &StartResumePdu) -> AcknowledgeResponseFlag {
+        let _ = pdu;
+        AcknowledgeResponseFlag::AbleToComply
+    }
+
+    fn stop_freeze(&mut self, pdu: &StopFreezePdu) -> AcknowledgeResponseFlag {
+        let _ = pdu;
+        AcknowledgeResponseFlag::AbleToComply
+    }
+
+    fn create_entity(&mut self, pdu: &CreateEntityPdu) -> AcknowledgeResponseFlag {
+        let _ = pdu;
+        AcknowledgeResponseFlag::UnableToComply
+    }
+
+    fn remove_entity(&mut self, pdu: &RemoveEntityPdu) -> AcknowledgeResponseFlag {
+        let _ = pdu;
+        AcknowledgeResponseFlag::UnableToComply
+    }
+}
+
+/// Answers the simulation management requests addressed to one simulation
+#[derive(Debug)]
+pub struct SimulationParticipant<C> {
+    address: SimulationAddress,
+    state: ParticipantState,
+    control: C,
+}
+
+impl<C: SimulationControl> SimulationParticipant<C> {
+    #[must_use]
+    pub const fn new(address: SimulationAddress, control: C) -> Self {
+        Self {
+            address,
+            state: ParticipantState::Stopped,
+            control,
+        }
+    }
+
+    #[must_use]
+    pub const fn address(&self) -> SimulationAddress {
+        self.address
+    }
+
+    /// The entity ID standing for the simulation itself
+    #[must_use]
+    pub const fn simulation_id(&self) -> EntityId {
+        EntityId::new(self.address.site_id, self.address.application_id, NO_ENTITY)
+    }
+
+    #[must_use]
+    pub const fn state(&self) -> ParticipantState {
+        self.state
+    }
+
+    #[must_use]
+    pub const fn control(&self) -> &C {
+        &self.control
+    }
+
+    pub const fn control_mut(&mut self) -> &mut C {
+        &mut self.control
+    }
+
+    /// Builds a Create Entity PDU asking the simulation manager `manager` to assign an entity
+    /// ID; the answer is read with [`assigned_entity_id`]
+    #[must_use]
+    pub fn request_entity_id(&self, manager: EntityId, request_id: u32) -> CreateEntityPdu {
+        let mut pdu = CreateEntityPdu::new();
+        pdu.originating_entity_id = EntityId::new(
+            self.address.site_id,
+            self.address.application_id,
+            RQST_ASSIGN_ID,
+        );
+        pdu.receiving_entity_id = manager;
+        pdu.request_id = request_id;
+        pdu
+    }
+
+    /// Answers a Start/Resume PDU, or returns `None` if it is not addressed to this simulation
+    pub fn handle_start_resume(&mut self, pdu: &StartResumePdu) -> Option<AcknowledgePdu> {
+        if !pdu.receiving_entity_id.addresses_simulation(self.address) {
+            return None;
+        }
+        let response_flag = self.control.start_resume(pdu);
+        if response_flag == AcknowledgeResponseFlag::AbleToComply {
+            self.state = ParticipantState::Running;
+        }
+        Some(self.acknowledge(
+            pdu.originating_entity_id,
+            AcknowledgeFlag::StartResume,
+            response_flag,
+            pdu.request_id,
+        ))
+    }
+
+    /// Answers a Stop/Freeze PDU, or returns `None` if it is not addressed to this simulation
+    pub fn handle_stop_freeze(&mut self, pdu: &StopFreezePdu) -> Option<AcknowledgePdu> {
+        if !pdu.receiving_entity_id.addresses_simulation(self.address) {
+            return None;
+        }
+        let response_flag = self.control.stop_freeze(pdu);
+        if response_flag == AcknowledgeResponseFlag::AbleToComply {
+            self.state = ParticipantState::after_stop_freeze(pdu.reason, pdu.frozen_behavior);
+        }
+        Some(self.acknowledge(
+            pdu.originating_entity_id,
+            AcknowledgeFlag::StopFreeze,
+            response_flag,
+            pdu.request_id,
+        ))
+    }
+
+    /// Answers a Create Entity PDU, or returns `None` if it is not addressed to this simulation
+    pub fn handle_create_entity(&mut self, pdu: &CreateEntityPdu) -> Option<AcknowledgePdu> {
+        if !pdu.receiving_entity_id.addresses_simulation(self.address) {
+            return None;
+        }
+        let response_flag = self.control.create_entity(pdu);
+        Some(self.acknowledge(
+            pdu.originating_entity_id,
+            AcknowledgeFlag::CreateEntity,
+            response_flag,
+            pdu.request_id,
+        ))
+    }
+
+    /// Answers a Remove Entity PDU, or returns `None` if it is not addressed to this simulation
+    pub fn handle_remove_entity(&mut self, pdu: &RemoveEntityPdu) -> Option<AcknowledgePdu> {
+        if !pdu.receiving_entity_id.addresses_simulation(self.address) {
+            return None;
+        }
+        let response_flag = self.control.remove_entity(pdu);
+        Some(self.acknowledge(
+            pdu.originating_entity_id,
+            AcknowledgeFlag::RemoveEntity,
+            response_flag,
+            pdu.request_id,
+        ))
+    }
+
+    fn acknowledge(
+        &self,
+        manager: EntityId,
+        acknowledge_flag: AcknowledgeFlag,
+        response_flag: AcknowledgeResponseFlag,
+        request_id: u32,
+    ) -> AcknowledgePdu {
+        let mut pdu = AcknowledgePdu::new();
+        pdu.originating_entity_id = self.simulation_id();
+        pdu.receiving_entity_id = manager;
+        pdu.acknowledge_flag = acknowledge_flag;
+        pdu.response_flag = response_flag;
+        pdu.request_id = request_id;
+        pdu
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::addressing::Target;
+
+    struct AcceptAll;
+
+    impl SimulationControl for AcceptAll {
+        fn create_entity(&mut self, _pdu: &CreateEntityPdu) -> AcknowledgeResponseFlag {
+            AcknowledgeResponseFlag::AbleToComply
+        }
+    }
+
+    fn participant() -> SimulationParticipant<AcceptAll> {
+        SimulationParticipant::new(SimulationAddress::new(3, 7), AcceptAll)
+    }
+
+    #[test]
+    fn stop_freeze_respects_reason_and_frozen_behavior() {
+        let mut participant = participant();
+        let mut start = StartResumePdu::new();
+        start.receiving_entity_id = Target::AllSimulations.entity_id();
+        start.request_id = 1;
+        let ack = participant
+            .handle_start_resume(&start)
+            .expect("addressed to all simulations");
+        assert_eq!(ack.acknowledge_flag, AcknowledgeFlag::StartResume);
+        assert_eq!(ack.response_flag, AcknowledgeResponseFlag::AbleToComply);
+        assert_eq!(ack.originating_entity_id, EntityId::new(3, 7, NO_ENTITY));
+        assert_eq!(participant.state(), ParticipantState::Running);
+
+        let mut stop = StopFreezePdu::new();
+        stop.receiving_entity_id = EntityId::new(3, 7, NO_ENTITY);
+        stop.reason = Reason::Recess;
+        stop.frozen_behavior = FrozenBehavior::RunSimulationClock;
+        let ack = participant
+            .handle_stop_freeze(&stop)
+            .expect("addressed to this simulation");
+        assert_eq!(ack.acknowledge_flag, AcknowledgeFlag::StopFreeze);
+        let state = participant.state();
+        assert!(state.runs_simulation_clock());
+        assert!(!state.transmits_updates());
+        assert!(!state.processes_updates());
+
+        stop.reason = Reason::Termination;
+        let _ = participant.handle_stop_freeze(&stop);
+        assert_eq!(participant.state(), ParticipantState::Stopped);
+    }
+
+    #[test]
+    fn requests_for_other_simulations_are_ignored() {
+        let mut participant = participant();
+        let mut start = StartResumePdu::new();
+        start.receiving_entity_id = EntityId::new(3, 8, NO_ENTITY);
+        assert!(participant.handle_start_resume(&start).is_none());
+        assert_eq!(participant.state(), ParticipantState::Stopped);
+    }
+
+    #[test]
+    fn entity_requests_use_the_control_answers() {
+        let mut participant = participant();
+        let mut create = CreateEntityPdu::new();
+        create.receiving_entity_id = EntityId::new(3, 7, NO_ENTITY);
+        let ack = participant
+            .handle_create_entity(&create)
+            .expect("addressed to this simulation");
+        assert_eq!(ack.acknowledge_flag, AcknowledgeFlag::CreateEntity);
+        assert_eq!(ack.response_flag, AcknowledgeResponseFlag::AbleToComply);
+
+        let mut remove = RemoveEntityPdu::new();
+        remove.receiving_entity_id = EntityId::new(3, 7, 12);
+        let ack = participant
+            .handle_remove_entity(&remove)
+            .expect("addressed to this simulation");
+        assert_eq!(ack.acknowledge_flag, AcknowledgeFlag::RemoveEntity);
+        assert_eq!(ack.response_flag, AcknowledgeResponseFlag::UnableToComply);
+    }
+}
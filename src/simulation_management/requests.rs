@@ -0,0 +1,732 @@
+//     open-dis-rust - Rust implementation of the IEEE 1278.1-2012 Distributed Interactive
+//                     Simulation (DIS) application protocol
+//     Copyright (C) 2025 Cameron Howell
+//
+//     Licensed under the BSD 2-Clause License
+
+//! Request/response correlation for the Action Request, Data Query and Set Data exchanges,
+//! IEEE 1278.1-2012 §5.6.5.8 to §5.6.5.10
+//!
+//! A [`RequestClient`] stamps outgoing requests with request IDs and hands back a
+//! [`ResponseFuture`] that resolves to the matching Action Response or Data PDU, or to
+//! [`DISError::RequestTimeout`] once the response is overdue. A Data Query with a non-zero time
+//! interval yields a [`DataStream`] of the periodic Data PDUs instead. A [`RequestServer`]
+//! answers incoming requests from a user-supplied [`RequestHandler`] and produces the periodic
+//! Data PDUs of standing queries. Neither does I/O of its own: received responses are fed in
+//! with the `handle_*` methods and timeouts are resolved by `poll`.
+
+use std::{
+    collections::{BTreeMap, VecDeque},
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    task::{Context, Poll, Waker},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    common::{
+        data_types::{datum_records::DatumSet, entity_id::EntityId},
+        dis_error::DISError,
+        enums::ActionResponseRequestStatus,
+    },
+    simulation_management::{
+        ActionRequestPdu, ActionResponsePdu, DataPdu, DataQueryPdu, SetDataPdu,
+    },
+};
+
+/// How long a client waits for a response before failing the request
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[derive(Debug)]
+struct Slot<T> {
+    result: Option<Result<T, DISError>>,
+    waker: Option<Waker>,
+}
+
+impl<T> Slot<T> {
+    fn resolve(slot: &Mutex<Self>, result: Result<T, DISError>) {
+        let mut slot = lock(slot);
+        slot.result = Some(result);
+        if let Some(waker) = slot.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// Resolves to the response to one request, or to an error if none arrives in time
+#[derive(Debug)]
+pub struct ResponseFuture<T> {
+    request_id: u32,
+    slot: Arc<Mutex<Slot<T>>>,
+}
+
+impl<T> ResponseFuture<T> {
+    fn new(request_id: u32) -> Self {
+        Self {
+            request_id,
+            slot: Arc::new(Mutex::new(Slot {
+                result: None,
+                waker: None,
+            })),
+        }
+    }
+
+    #[must_use]
+    pub const fn request_id(&self) -> u32 {
+        self.request_id
+    }
+}
+
+impl<T> Future for ResponseFuture<T> {
+    type Output = Result<T, DISError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut slot = lock(&self.slot);
+        if let Some(result) = slot.result.take() {
+            return Poll::Ready(result);
+        }
+        slot.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+#[derive(Debug, Default)]
+struct StreamState {
+    queue: VecDeque<Result<DataPdu, DISError>>,
+    closed: bool,
+    waker: Option<Waker>,
+}
+
+impl StreamState {
+    fn push(state: &Mutex<Self>, item: Option<Result<DataPdu, DISError>>) {
+        let mut state = lock(state);
+        match item {
+            Some(item) => state.queue.push_back(item),
+            None => state.closed = true,
+        }
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// The Data PDUs answering a periodic Data Query
+///
+/// The stream ends after a timeout error, or when the subscription is cancelled.
+#[derive(Debug)]
+pub struct DataStream {
+    request_id: u32,
+    state: Arc<Mutex<StreamState>>,
+}
+
+impl DataStream {
+    #[must_use]
+    pub const fn request_id(&self) -> u32 {
+        self.request_id
+    }
+
+    /// The next Data PDU, or `None` once the stream has ended
+    pub const fn next(&mut self) -> NextData<'_> {
+        NextData { stream: self }
+    }
+
+    /// The next Data PDU if one has already arrived
+    pub fn try_next(&mut self) -> Option<Result<DataPdu, DISError>> {
+        lock(&self.state).queue.pop_front()
+    }
+
+    /// Whether the stream has ended and every Data PDU has been taken
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        let state = lock(&self.state);
+        state.closed && state.queue.is_empty()
+    }
+}
+
+/// Future returned by [`DataStream::next`]
+#[derive(Debug)]
+pub struct NextData<'a> {
+    stream: &'a mut DataStream,
+}
+
+impl Future for NextData<'_> {
+    type Output = Option<Result<DataPdu, DISError>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = lock(&self.stream.state);
+        if let Some(item) = state.queue.pop_front() {
+            return Poll::Ready(Some(item));
+        }
+        if state.closed {
+            return Poll::Ready(None);
+        }
+        state.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+#[derive(Debug)]
+enum Waiting {
+    Action(Arc<Mutex<Slot<ActionResponsePdu>>>),
+    Data(Arc<Mutex<Slot<DataPdu>>>),
+    Stream {
+        state: Arc<Mutex<StreamState>>,
+        interval: Duration,
+        query: DataQueryPdu,
+    },
+}
+
+impl Waiting {
+    fn fail(self, error: DISError) {
+        match self {
+            Self::Action(slot) => Slot::resolve(&slot, Err(error)),
+            Self::Data(slot) => Slot::resolve(&slot, Err(error)),
+            Self::Stream { state, .. } => {
+                StreamState::push(&state, Some(Err(error)));
+                StreamState::push(&state, None);
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+struct PendingRequest {
+    waiting: Waiting,
+    deadline: Instant,
+}
+
+/// Sends Action Request, Data Query and Set Data PDUs and matches their responses
+#[derive(Debug)]
+pub struct RequestClient {
+    originating_id: EntityId,
+    timeout: Duration,
+    next_request_id: u32,
+    pending: BTreeMap<u32, PendingRequest>,
+}
+
+impl RequestClient {
+    #[must_use]
+    pub const fn new(originating_id: EntityId) -> Self {
+        Self {
+            originating_id,
+            timeout: DEFAULT_REQUEST_TIMEOUT,
+            next_request_id: 1,
+            pending: BTreeMap::new(),
+        }
+    }
+
+    /// Sets how long to wait for a response, and between the Data PDUs of a subscription
+    /// beyond its time interval
+    #[must_use]
+    pub const fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    #[must_use]
+    pub const fn originating_id(&self) -> EntityId {
+        self.originating_id
+    }
+
+    /// Number of requests and subscriptions awaiting responses
+    #[must_use]
+    pub fn pending_len(&self) -> usize {
+        self.pending.len()
+    }
+
+    #[must_use]
+    pub fn is_pending(&self, request_id: u32) -> bool {
+        self.pending.contains_key(&request_id)
+    }
+
+    /// Earliest time a request or subscription times out
+    #[must_use]
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.pending.values().map(|pending| pending.deadline).min()
+    }
+
+    /// Stamps an Action Request PDU for sending and returns the future of its response
+    pub fn action_request(
+        &mut self,
+        mut pdu: ActionRequestPdu,
+        now: Instant,
+    ) -> (ActionRequestPdu, ResponseFuture<ActionResponsePdu>) {
+        let request_id = self.allocate_request_id();
+        pdu.originating_entity_id = self.originating_id;
+        pdu.request_id = request_id;
+        let future = ResponseFuture::new(request_id);
+        self.track(request_id, Waiting::Action(Arc::clone(&future.slot)), now);
+        (pdu, future)
+    }
+
+    /// Stamps a Set Data PDU for sending and returns the future of the Data PDU answering it
+    pub fn set_data(
+        &mut self,
+        mut pdu: SetDataPdu,
+        now: Instant,
+    ) -> (SetDataPdu, ResponseFuture<DataPdu>) {
+        let request_id = self.allocate_request_id();
+        pdu.originating_entity_id = self.originating_id;
+        pdu.request_id = request_id;
+        let future = ResponseFuture::new(request_id);
+        self.track(request_id, Waiting::Data(Arc::clone(&future.slot)), now);
+        (pdu, future)
+    }
+
+    /// Stamps a one-off Data Query PDU for sending and returns the future of its Data PDU
+    ///
+    /// The time interval is set to zero.
+    pub fn data_query(
+        &mut self,
+        mut pdu: DataQueryPdu,
+        now: Instant,
+    ) -> (DataQueryPdu, ResponseFuture<DataPdu>) {
+        let request_id = self.allocate_request_id();
+        pdu.originating_entity_id = self.originating_id;
+        pdu.request_id = request_id;
+        pdu.time_interval = 0;
+        let future = ResponseFuture::new(request_id);
+        self.track(request_id, Waiting::Data(Arc::clone(&future.slot)), now);
+        (pdu, future)
+    }
+
+    /// Stamps a periodic Data Query PDU for sending and returns the stream of its Data PDUs
+    ///
+    /// The time interval is sent in milliseconds, saturating at `u32::MAX`; an interval below
+    /// one millisecond is sent as one. The subscription times out if no Data PDU arrives
+    /// within the interval plus the client timeout.
+    pub fn data_subscription(
+        &mut self,
+        mut pdu: DataQueryPdu,
+        interval: Duration,
+        now: Instant,
+    ) -> (DataQueryPdu, DataStream) {
+        let request_id = self.allocate_request_id();
+        pdu.originating_entity_id = self.originating_id;
+        pdu.request_id = request_id;
+        pdu.time_interval = u32::try_from(interval.as_millis())
+            .unwrap_or(u32::MAX)
+            .max(1);
+        let stream = DataStream {
+            request_id,
+            state: Arc::new(Mutex::new(StreamState::default())),
+        };
+        self.pending.insert(
+            request_id,
+            PendingRequest {
+                waiting: Waiting::Stream {
+                    state: Arc::clone(&stream.state),
+                    interval,
+                    query: pdu.clone(),
+                },
+                deadline: now + interval + self.timeout,
+            },
+        );
+        (pdu, stream)
+    }
+
+    /// Stops waiting for a request, failing its future with [`DISError::RequestCancelled`]
+    ///
+    /// For a subscription this ends its stream and returns the Data Query PDU, with a time
+    /// interval of zero, that tells the server to stop sending.
+    pub fn cancel(&mut self, request_id: u32) -> Option<DataQueryPdu> {
+        let pending = self.pending.remove(&request_id)?;
+        let stop = match &pending.waiting {
+            Waiting::Stream { query, .. } => {
+                let mut stop = query.clone();
+                stop.time_interval = 0;
+                Some(stop)
+            }
+            Waiting::Action(_) | Waiting::Data(_) => None,
+        };
+        pending
+            .waiting
+            .fail(DISError::request_cancelled(request_id));
+        stop
+    }
+
+    /// Resolves the request an Action Response PDU answers; returns whether it matched one
+    pub fn handle_action_response(&mut self, pdu: &ActionResponsePdu) -> bool {
+        if pdu.receiving_entity_id != self.originating_id {
+            return false;
+        }
+        let Some(PendingRequest {
+            waiting: Waiting::Action(slot),
+            ..
+        }) = self.pending.get(&pdu.request_id)
+        else {
+            return false;
+        };
+        Slot::resolve(slot, Ok(pdu.clone()));
+        self.pending.remove(&pdu.request_id);
+        true
+    }
+
+    /// Resolves the request or feeds the subscription a Data PDU answers; returns whether it
+    /// matched one
+    pub fn handle_data(&mut self, pdu: &DataPdu, now: Instant) -> bool {
+        if pdu.receiving_entity_id != self.originating_id {
+            return false;
+        }
+        let timeout = self.timeout;
+        let Some(pending) = self.pending.get_mut(&pdu.request_id) else {
+            return false;
+        };
+        match &pending.waiting {
+            Waiting::Action(_) => false,
+            Waiting::Stream {
+                state, interval, ..
+            } => {
+                StreamState::push(state, Some(Ok(pdu.clone())));
+                pending.deadline = now + *interval + timeout;
+                true
+            }
+            Waiting::Data(slot) => {
+                Slot::resolve(slot, Ok(pdu.clone()));
+                self.pending.remove(&pdu.request_id);
+                true
+            }
+        }
+    }
+
+    /// Fails the requests and subscriptions whose responses are overdue at `now`; returns
+    /// their request IDs
+    pub fn poll(&mut self, now: Instant) -> Vec<u32> {
+        let mut expired = Vec::new();
+        for (request_id, pending) in self
+            .pending
+            .extract_if(.., |_, pending| pending.deadline <= now)
+        {
+            pending.waiting.fail(DISError::request_timeout(request_id));
+            expired.push(request_id);
+        }
+        expired
+    }
+
+    fn allocate_request_id(&mut self) -> u32 {
+        let request_id = self.next_request_id;
+        self.next_request_id = self.next_request_id.wrapping_add(1).max(1);
+        request_id
+    }
+
+    fn track(&mut self, request_id: u32, waiting: Waiting, now: Instant) {
+        self.pending.insert(
+            request_id,
+            PendingRequest {
+                waiting,
+                deadline: now + self.timeout,
+            },
+        );
+    }
+}
+
+/// The application's answers to Action Request, Data Query and Set Data PDUs
+///
+/// Unless overridden, actions are rejected and queries and set requests are answered with no
+/// datum records.
+pub trait RequestHandler {
+    /// The status of the requested action and the datum records to return with it
+    fn action(&mut self, request: &ActionRequestPdu) -> (ActionResponseRequestStatus, DatumSet) {
+        let _ = request;
+        (
+            ActionResponseRequestStatus::RequestRejected,
+            DatumSet::new(),
+        )
+    }
+
+    /// The current values of the queried datums
+    fn data_query(&mut self, query: &DataQueryPdu) -> DatumSet {
+        let _ = query;
+        DatumSet::new()
+    }
+
+    /// Applies the values of a Set Data PDU and returns the datums as they now stand
+    fn set_data(&mut self, request: &SetDataPdu) -> DatumSet {
+        let _ = request;
+        DatumSet::new()
+    }
+}
+
+#[derive(Debug)]
+struct Subscription {
+    query: DataQueryPdu,
+    interval: Duration,
+    next_due: Instant,
+}
+
+fn same_query(a: &DataQueryPdu, b: &DataQueryPdu) -> bool {
+    a.originating_entity_id == b.originating_entity_id && a.request_id == b.request_id
+}
+
+/// Answers Action Request, Data Query and Set Data PDUs from a [`RequestHandler`]
+#[derive(Debug)]
+pub struct RequestServer<H> {
+    handler: H,
+    subscriptions: Vec<Subscription>,
+}
+
+impl<H: RequestHandler> RequestServer<H> {
+    #[must_use]
+    pub const fn new(handler: H) -> Self {
+        Self {
+            handler,
+            subscriptions: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub const fn handler(&self) -> &H {
+        &self.handler
+    }
+
+    pub const fn handler_mut(&mut self) -> &mut H {
+        &mut self.handler
+    }
+
+    /// Number of standing periodic Data Queries
+    #[must_use]
+    pub const fn subscription_count(&self) -> usize {
+        self.subscriptions.len()
+    }
+
+    /// Answers an Action Request PDU
+    pub fn handle_action_request(&mut self, request: &ActionRequestPdu) -> ActionResponsePdu {
+        let (request_status, datums) = self.handler.action(request);
+        let mut response = ActionResponsePdu::new();
+        response.originating_entity_id = request.receiving_entity_id;
+        response.receiving_entity_id = request.originating_entity_id;
+        response.request_id = request.request_id;
+        response.request_status = request_status;
+        response.set_datum_set(datums);
+        response
+    }
+
+    /// Applies a Set Data PDU and returns the Data PDU that answers it
+    pub fn handle_set_data(&mut self, request: &SetDataPdu) -> DataPdu {
+        let datums = self.handler.set_data(request);
+        data_pdu(
+            request.receiving_entity_id,
+            request.originating_entity_id,
+            request.request_id,
+            datums,
+        )
+    }
+
+    /// Answers a Data Query PDU and, if its time interval is non-zero, keeps it standing
+    ///
+    /// The time interval is in milliseconds. A query with the same requester and request ID
+    /// as a standing query replaces it, so a repeat with a time interval of zero ends it.
+    pub fn handle_data_query(&mut self, query: &DataQueryPdu, now: Instant) -> DataPdu {
+        self.subscriptions
+            .retain(|subscription| !same_query(&subscription.query, query));
+        if query.time_interval != 0 {
+            let interval = Duration::from_millis(u64::from(query.time_interval));
+            self.subscriptions.push(Subscription {
+                query: query.clone(),
+                interval,
+                next_due: now + interval,
+            });
+        }
+        self.answer(query)
+    }
+
+    /// Earliest time a standing query is due
+    #[must_use]
+    pub fn next_due(&self) -> Option<Instant> {
+        self.subscriptions
+            .iter()
+            .map(|subscription| subscription.next_due)
+            .min()
+    }
+
+    /// Returns the Data PDUs of the standing queries due at `now`
+    pub fn poll(&mut self, now: Instant) -> Vec<DataPdu> {
+        let mut due = Vec::new();
+        for subscription in &mut self.subscriptions {
+            if subscription.next_due > now {
+                continue;
+            }
+            while subscription.next_due <= now {
+                subscription.next_due += subscription.interval;
+            }
+            due.push(subscription.query.clone());
+        }
+        due.iter().map(|query| self.answer(query)).collect()
+    }
+
+    fn answer(&mut self, query: &DataQueryPdu) -> DataPdu {
+        let datums = self.handler.data_query(query);
+        data_pdu(
+            query.receiving_entity_id,
+            query.originating_entity_id,
+            query.request_id,
+            datums,
+        )
+    }
+}
+
+fn data_pdu(
+    originating_id: EntityId,
+    receiving_id: EntityId,
+    request_id: u32,
+    datums: DatumSet,
+) -> DataPdu {
+    let mut pdu = DataPdu::new();
+    pdu.originating_entity_id = originating_id;
+    pdu.receiving_entity_id = receiving_id;
+    pdu.request_id = request_id;
+    pdu.set_datum_set(datums);
+    pdu
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+
+    use super::*;
+    use crate::common::enums::VariableRecordTypes;
+
+    const CLIENT: EntityId = EntityId::new(1, 1, 0);
+    const SERVER: EntityId = EntityId::new(1, 2, 0);
+
+    struct Fuel(u32);
+
+    impl RequestHandler for Fuel {
+        fn action(
+            &mut self,
+            _request: &ActionRequestPdu,
+        ) -> (ActionResponseRequestStatus, DatumSet) {
+            (ActionResponseRequestStatus::Complete, DatumSet::new())
+        }
+
+        fn data_query(&mut self, _query: &DataQueryPdu) -> DatumSet {
+            DatumSet::new().with_fixed(VariableRecordTypes::default(), &self.0)
+        }
+
+        fn set_data(&mut self, request: &SetDataPdu) -> DatumSet {
+            if let Some(fuel) = request
+                .datum_set()
+                .fixed::<u32>(VariableRecordTypes::default())
+            {
+                self.0 = fuel;
+            }
+            self.data_query(&DataQueryPdu::new())
+        }
+    }
+
+    fn addressed<T>(mut pdu: T, set: impl FnOnce(&mut T)) -> T {
+        set(&mut pdu);
+        pdu
+    }
+
+    #[test]
+    fn responses_resolve_the_matching_futures() {
+        let now = Instant::now();
+        let mut client = RequestClient::new(CLIENT);
+        let mut server = RequestServer::new(Fuel(40));
+
+        let request = addressed(ActionRequestPdu::new(), |pdu| {
+            pdu.receiving_entity_id = SERVER;
+        });
+        let (action, action_future) = client.action_request(request, now);
+        let set = SetDataPdu::new();
+        let mut set = addressed(set, |pdu| pdu.receiving_entity_id = SERVER);
+        set.set_datum_set(DatumSet::new().with_fixed(VariableRecordTypes::default(), &55u32));
+        let (set, set_future) = client.set_data(set, now);
+        assert_ne!(action.request_id, set.request_id);
+        assert_eq!(client.pending_len(), 2);
+
+        assert!(client.handle_data(&server.handle_set_data(&set), now));
+        assert!(client.handle_action_response(&server.handle_action_request(&action)));
+        assert_eq!(client.pending_len(), 0);
+
+        let response = block_on(action_future).expect("answered");
+        assert_eq!(
+            response.request_status,
+            ActionResponseRequestStatus::Complete
+        );
+        let data = block_on(set_future).expect("answered");
+        assert_eq!(
+            data.datum_set()
+                .fixed::<u32>(VariableRecordTypes::default()),
+            Some(55)
+        );
+        assert_eq!(server.handler().0, 55);
+    }
+
+    #[test]
+    fn unanswered_requests_time_out() {
+        let now = Instant::now();
+        let mut client = RequestClient::new(CLIENT).with_timeout(Duration::from_millis(100));
+        let (query, future) = client.data_query(DataQueryPdu::new(), now);
+        assert_eq!(query.time_interval, 0);
+        assert_eq!(
+            client.next_deadline(),
+            Some(now + Duration::from_millis(100))
+        );
+
+        assert!(client.poll(now + Duration::from_millis(99)).is_empty());
+        assert_eq!(
+            client.poll(now + Duration::from_millis(100)),
+            vec![query.request_id]
+        );
+        assert!(matches!(
+            block_on(future),
+            Err(DISError::RequestTimeout { request_id }) if request_id == query.request_id
+        ));
+
+        let mut stray = DataPdu::new();
+        stray.receiving_entity_id = CLIENT;
+        stray.request_id = query.request_id;
+        assert!(!client.handle_data(&stray, now));
+    }
+
+    #[test]
+    fn periodic_queries_stream_data_until_cancelled() {
+        let now = Instant::now();
+        let interval = Duration::from_millis(500);
+        let mut client = RequestClient::new(CLIENT);
+        let mut server = RequestServer::new(Fuel(40));
+
+        let query = addressed(DataQueryPdu::new(), |pdu| pdu.receiving_entity_id = SERVER);
+        let (query, mut stream) = client.data_subscription(query, interval, now);
+        assert_eq!(query.time_interval, 500);
+
+        assert!(client.handle_data(&server.handle_data_query(&query, now), now));
+        assert_eq!(server.subscription_count(), 1);
+        assert!(server.poll(now + Duration::from_millis(499)).is_empty());
+        server.handler_mut().0 = 35;
+        let later = now + interval;
+        for data in server.poll(later) {
+            assert!(client.handle_data(&data, later));
+        }
+        assert_eq!(server.next_due(), Some(later + interval));
+
+        let fuel = |item: Option<Result<DataPdu, DISError>>| {
+            item.expect("queued")
+                .expect("not an error")
+                .datum_set()
+                .fixed::<u32>(VariableRecordTypes::default())
+        };
+        assert_eq!(fuel(block_on(stream.next())), Some(40));
+        assert_eq!(fuel(stream.try_next()), Some(35));
+        assert!(stream.try_next().is_none());
+
+        let stop = client.cancel(stream.request_id()).expect("a subscription");
+        assert_eq!(stop.time_interval, 0);
+        assert!(matches!(
+            block_on(stream.next()),
+            Some(Err(DISError::RequestCancelled { .. }))
+        ));
+        assert!(block_on(stream.next()).is_none());
+        assert!(stream.is_finished());
+
+        let _ = server.handle_data_query(&stop, later);
+        assert_eq!(server.subscription_count(), 0);
+    }
+}
@@ -6,19 +6,22 @@
 
 //! The Simulation Management with Reliability (SIMAN-R) protocol family
 
+pub mod records;
+pub mod reliability;
+
 use crate::{
     common::{
         GenericHeader, SerializedLength,
         data_types::{
             ClockTime,
-            datum_records::{FixedDatumRecord, VariableDatumRecord},
+            datum_records::{FixedDatumRecord, VariableDatumRecord, impl_datum_set},
             entity_id::EntityId,
             record_specification::RecordSpecification,
         },
         enums::{
             AcknowledgeFlag, AcknowledgeResponseFlag, ActionResponseRequestStatus, EventType,
             FrozenBehavior, PduType, ProtocolFamily, Reason, RecordQueryREventType,
-            RequiredReliabilityService, VariableRecordTypes,
+            RecordREventType, RequiredReliabilityService, VariableRecordTypes,
         },
         pdu::Pdu,
         pdu_header::PduHeader,
@@ -27,7 +30,7 @@ use crate::{
 };
 
 define_pdu! {
-    #[derive(Debug)]
+    #[derive(Clone, Debug)]
     /// Implemented according to IEEE 1278.1-2012 §7.11.2
     pub struct CreateEntityReliablePdu {
         header: PduHeader,
@@ -45,7 +48,7 @@ define_pdu! {
 }
 
 define_pdu! {
-    #[derive(Debug)]
+    #[derive(Clone, Debug)]
     /// Implemented according to IEEE 1278.1-2012 §7.11.3
     pub struct RemoveEntityReliablePdu {
         header: PduHeader,
@@ -63,7 +66,7 @@ define_pdu! {
 }
 
 define_pdu! {
-    #[derive(Debug)]
+    #[derive(Clone, Debug)]
     /// Implemented according to IEEE 1278.1-2012 §7.11.4
     pub struct StartResumeReliablePdu {
         header: PduHeader,
@@ -83,7 +86,7 @@ define_pdu! {
 }
 
 define_pdu! {
-    #[derive(Debug)]
+    #[derive(Clone, Debug)]
     /// Implemented according to IEEE 1278.1-2012 §7.11.5
     pub struct StopFreezeReliablePdu {
         header: PduHeader,
@@ -103,7 +106,7 @@ define_pdu! {
 }
 
 define_pdu! {
-    #[derive(Debug)]
+    #[derive(Clone, Debug)]
     /// Implemented according to IEEE 1278.1-2012 §7.11.6
     pub struct AcknowledgeReliablePdu {
         header: PduHeader,
@@ -120,7 +123,7 @@ define_pdu! {
 }
 
 define_pdu! {
-    #[derive(Debug)]
+    #[derive(Clone, Debug)]
     /// Implemented according to IEEE 1278.1-2012 §7.11.7
     pub struct ActionRequestReliablePdu {
         header: PduHeader,
@@ -137,14 +140,16 @@ define_pdu! {
             padding3: u32,
             pub number_of_fixed_datum_records: u32,
             pub number_of_variable_datum_records: u32,
+            #[count = number_of_fixed_datum_records]
             pub fixed_datum_records: Vec<FixedDatumRecord>,
+            #[count = number_of_variable_datum_records]
             pub variable_datum_records: Vec<VariableDatumRecord>,
         }
     }
 }
 
 define_pdu! {
-    #[derive(Debug)]
+    #[derive(Clone, Debug)]
     /// Implemented according to IEEE 1278.1-2012 §7.11.8
     pub struct ActionResponseReliablePdu {
         header: PduHeader,
@@ -157,14 +162,16 @@ define_pdu! {
             pub request_status: ActionResponseRequestStatus,
             pub number_of_fixed_datum_records: u32,
             pub number_of_variable_datum_records: u32,
+            #[count = number_of_fixed_datum_records]
             pub fixed_datum_records: Vec<FixedDatumRecord>,
+            #[count = number_of_variable_datum_records]
             pub variable_datum_records: Vec<VariableDatumRecord>,
         }
     }
 }
 
 define_pdu! {
-    #[derive(Debug)]
+    #[derive(Clone, Debug)]
     /// Implemented according to IEEE 1278.1-2012 §7.11.9
     pub struct DataQueryReliablePdu {
         header: PduHeader,
@@ -180,14 +187,16 @@ define_pdu! {
             pub time_interval: u32,
             pub number_of_fixed_datum_records: u32,
             pub number_of_variable_datum_records: u32,
+            #[count = number_of_fixed_datum_records]
             pub fixed_datum_ids: Vec<VariableRecordTypes>,
+            #[count = number_of_variable_datum_records]
             pub variable_datum_ids: Vec<VariableRecordTypes>,
         }
     }
 }
 
 define_pdu! {
-    #[derive(Debug)]
+    #[derive(Clone, Debug)]
     /// Implemented according to IEEE 1278.1-2012 §7.11.10
     pub struct SetDataReliablePdu {
         header: PduHeader,
@@ -202,14 +211,16 @@ define_pdu! {
             pub request_id: u32,
             pub number_of_fixed_datum_records: u32,
             pub number_of_variable_datum_records: u32,
+            #[count = number_of_fixed_datum_records]
             pub fixed_datum_records: Vec<FixedDatumRecord>,
+            #[count = number_of_variable_datum_records]
             pub variable_datum_records: Vec<VariableDatumRecord>,
         }
     }
 }
 
 define_pdu! {
-    #[derive(Debug)]
+    #[derive(Clone, Debug)]
     /// Implemented according to IEEE 1278.1-2012 §7.11.11
     pub struct DataReliablePdu {
         header: PduHeader,
@@ -224,7 +235,9 @@ define_pdu! {
             padding2: u16,
             pub number_of_fixed_datum_records: u32,
             pub number_of_variable_datum_records: u32,
+            #[count = number_of_fixed_datum_records]
             pub fixed_datum_records: Vec<FixedDatumRecord>,
+            #[count = number_of_variable_datum_records]
             pub variable_datum_records: Vec<VariableDatumRecord>,
         }
     }
@@ -244,7 +257,9 @@ define_pdu! {
             padding: u32,
             pub number_of_fixed_datum_records: u32,
             pub number_of_variable_datum_records: u32,
+            #[count = number_of_fixed_datum_records]
             pub fixed_datum_records: Vec<FixedDatumRecord>,
+            #[count = number_of_variable_datum_records]
             pub variable_datum_records: Vec<VariableDatumRecord>,
         }
     }
@@ -262,14 +277,16 @@ define_pdu! {
             pub receiving_entity_id: EntityId,
             pub number_of_fixed_datum_records: u32,
             pub number_of_variable_datum_records: u32,
+            #[count = number_of_fixed_datum_records]
             pub fixed_datum_records: Vec<FixedDatumRecord>,
+            #[count = number_of_variable_datum_records]
             pub variable_datum_records: Vec<VariableDatumRecord>,
         }
     }
 }
 
 define_pdu! {
-    #[derive(Debug)]
+    #[derive(Clone, Debug)]
     /// Implemented according to IEEE 1278.1-2012 §7.11.14
     pub struct RecordQueryReliablePdu {
         header: PduHeader,
@@ -284,13 +301,14 @@ define_pdu! {
             pub event_type: RecordQueryREventType,
             pub time: u32,
             pub number_of_records: u32,
+            #[count = number_of_records]
             pub record_ids: Vec<u32>,
         }
     }
 }
 
 define_pdu! {
-    #[derive(Debug)]
+    #[derive(Clone, Debug)]
     /// Implemented according to IEEE 1278.1-2012 §7.11.15
     pub struct SetRecordReliablePdu {
         header: PduHeader,
@@ -309,6 +327,35 @@ define_pdu! {
     }
 }
 
+define_pdu! {
+    #[derive(Clone, Debug)]
+    /// Implemented according to IEEE 1278.1-2012 §7.11.16
+    pub struct RecordReliablePdu {
+        header: PduHeader,
+        pdu_type: PduType::RecordReliable,
+        protocol_family: ProtocolFamily::SimulationManagementWithReliability,
+        fields: {
+            pub originating_entity_id: EntityId,
+            pub receiving_entity_id: EntityId,
+            pub request_id: u32,
+            pub required_reliability_service: RequiredReliabilityService,
+            padding: u8,
+            pub event_type: RecordREventType,
+            pub response_serial_number: u32,
+            pub record_sets: RecordSpecification,
+        }
+    }
+}
+
+impl_datum_set!(
+    ActionRequestReliablePdu,
+    ActionResponseReliablePdu,
+    SetDataReliablePdu,
+    DataReliablePdu,
+    EventReportReliablePdu,
+    CommentReliablePdu,
+);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -744,4 +791,56 @@ mod tests {
             assert_eq!(pdu.header().length, DEFAULT_LENGTH);
         }
     }
+
+    mod record_reliable_pdu_tests {
+        use super::*;
+        use crate::common::data_types::record_specification_element::RecordSpecificationElement;
+
+        #[test]
+        fn cast_to_any() {
+            let pdu = RecordReliablePdu::new();
+            let any_pdu = pdu.as_any();
+
+            assert!(any_pdu.is::<RecordReliablePdu>());
+        }
+
+        #[test]
+        fn serialize_then_deserialize() {
+            let mut pdu = RecordReliablePdu::new();
+            pdu.request_id = 12;
+            pdu.response_serial_number = 3;
+            pdu.record_sets = RecordSpecification::new(
+                1,
+                vec![RecordSpecificationElement::new(
+                    240_000,
+                    1,
+                    32,
+                    2,
+                    vec![1, 2, 3, 4, 5, 6, 7, 8],
+                )],
+            );
+            let mut serialize_buf = BytesMut::new();
+            let _ = pdu.serialize(&mut serialize_buf);
+            // Two 32-bit records after the 12-octet record set header, padded to 64 bits
+            assert_eq!(serialize_buf.len(), 40 + 24);
+
+            let mut deserialize_buf = serialize_buf.freeze();
+            let new_pdu = RecordReliablePdu::deserialize(&mut deserialize_buf).unwrap_or_default();
+            assert_eq!(new_pdu.header, pdu.header);
+            assert_eq!(new_pdu.request_id, 12);
+            assert_eq!(new_pdu.response_serial_number, 3);
+            assert_eq!(new_pdu.record_sets.record_sets.len(), 1);
+            assert_eq!(
+                new_pdu.record_sets.record_sets[0].record_values,
+                vec![1, 2, 3, 4, 5, 6, 7, 8]
+            );
+        }
+
+        #[test]
+        fn check_default_pdu_length() {
+            const DEFAULT_LENGTH: u16 = 320 / BITS_PER_BYTE;
+            let pdu = RecordReliablePdu::new();
+            assert_eq!(pdu.header().length, DEFAULT_LENGTH);
+        }
+    }
 }
@@ -0,0 +1,383 @@
+//     open-dis-rust - Rust implementation of the IEEE 1278.1-2012 Distributed Interactive
+//                     Simulation (DIS) application protocol
+//     Copyright (C) 2025 Cameron Howell
+//
+//     Licensed under the BSD 2-Clause License
+
+//! Answers Record Query-R and Set Record-R PDUs with Record-R PDUs
+//!
+//! A [`RecordService`] looks record values up in a user-supplied [`RecordProvider`]. A periodic
+//! query with a time of zero is answered once. A periodic query with a non-zero time, in
+//! milliseconds, is answered at once and then every time interval from [`RecordService::poll`].
+//! An event-driven query is answered at once and then whenever the application reports a change
+//! to one of its records through [`RecordService::notify`]. The service does no I/O of its own.
+
+use std::time::{Duration, Instant};
+
+use crate::{
+    common::{
+        data_types::{
+            entity_id::EntityId, record_specification::RecordSpecification,
+            record_specification_element::RecordSpecificationElement,
+        },
+        enums::{RecordQueryREventType, RecordREventType},
+    },
+    simulation_management_with_reliability::{
+        RecordQueryReliablePdu, RecordReliablePdu, SetRecordReliablePdu,
+    },
+};
+
+/// The source of the record values sent in Record-R PDUs
+pub trait RecordProvider {
+    /// The current values of the requested records; IDs the provider does not hold are left
+    /// out
+    fn records(&mut self, record_ids: &[u32]) -> Vec<RecordSpecificationElement>;
+
+    /// Applies the values of a Set Record-R PDU and returns the records as they now stand
+    ///
+    /// The default accepts no changes and returns no records.
+    fn set_records(
+        &mut self,
+        record_sets: &[RecordSpecificationElement],
+    ) -> Vec<RecordSpecificationElement> {
+        let _ = record_sets;
+        Vec::new()
+    }
+}
+
+/// Identifies a standing query by its requester and request ID
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct QueryId {
+    pub requester: EntityId,
+    pub request_id: u32,
+}
+
+impl QueryId {
+    #[must_use]
+    pub const fn new(requester: EntityId, request_id: u32) -> Self {
+        Self {
+            requester,
+            request_id,
+        }
+    }
+
+    const fn of(query: &RecordQueryReliablePdu) -> Self {
+        Self::new(query.originating_entity_id, query.request_id)
+    }
+}
+
+#[derive(Debug)]
+enum Schedule {
+    Periodic {
+        interval: Duration,
+        next_due: Instant,
+    },
+    EventDriven,
+}
+
+#[derive(Debug)]
+struct Subscription {
+    query: RecordQueryReliablePdu,
+    schedule: Schedule,
+    response_serial_number: u32,
+}
+
+impl Subscription {
+    fn respond(&mut self, records: Vec<RecordSpecificationElement>) -> RecordReliablePdu {
+        self.response_serial_number = self.response_serial_number.wrapping_add(1);
+        record_pdu(&self.query, self.response_serial_number, records)
+    }
+}
+
+/// Answers record queries from the records of a [`RecordProvider`]
+#[derive(Debug)]
+pub struct RecordService<P> {
+    provider: P,
+    subscriptions: Vec<Subscription>,
+}
+
+impl<P: RecordProvider> RecordService<P> {
+    #[must_use]
+    pub const fn new(provider: P) -> Self {
+        Self {
+            provider,
+            subscriptions: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub const fn provider(&self) -> &P {
+        &self.provider
+    }
+
+    pub const fn provider_mut(&mut self) -> &mut P {
+        &mut self.provider
+    }
+
+    /// Number of standing periodic and event-driven queries
+    #[must_use]
+    pub const fn subscription_count(&self) -> usize {
+        self.subscriptions.len()
+    }
+
+    /// Answers a Record Query-R PDU and, unless it is a one-off query, keeps it standing
+    ///
+    /// A query with the same requester and request ID as a standing query replaces it.
+    pub fn handle_query(
+        &mut self,
+        query: &RecordQueryReliablePdu,
+        now: Instant,
+    ) -> RecordReliablePdu {
+        let id = QueryId::of(query);
+        self.subscriptions
+            .retain(|subscription| QueryId::of(&subscription.query) != id);
+
+        let schedule = match query.event_type {
+            RecordQueryREventType::Periodic if query.time == 0 => None,
+            RecordQueryREventType::Periodic => {
+                let interval = Duration::from_millis(u64::from(query.time));
+                Some(Schedule::Periodic {
+                    interval,
+                    next_due: now + interval,
+                })
+            }
+            RecordQueryREventType::InternalEntityStateData => Some(Schedule::EventDriven),
+        };
+
+        let records = self.provider.records(&query.record_ids);
+        let Some(schedule) = schedule else {
+            return record_pdu(query, 1, records);
+        };
+        let mut subscription = Subscription {
+            query: query.clone(),
+            schedule,
+            response_serial_number: 0,
+        };
+        let response = subscription.respond(records);
+        self.subscriptions.push(subscription);
+        response
+    }
+
+    /// Applies a Set Record-R PDU and returns the Record-R PDU that answers it
+    pub fn handle_set_record(&mut self, request: &SetRecordReliablePdu) -> RecordReliablePdu {
+        let records = self.provider.set_records(&request.record_sets.record_sets);
+        let mut response = RecordReliablePdu::new();
+        response.originating_entity_id = request.receiving_entity_id;
+        response.receiving_entity_id = request.originating_entity_id;
+        response.request_id = request.request_id;
+        response.required_reliability_service = request.required_reliability_service;
+        response.event_type = RecordREventType::Other;
+        response.response_serial_number = 1;
+        response.record_sets = RecordSpecification::from_record_sets(records);
+        response
+    }
+
+    /// Ends a standing query; returns whether it was standing
+    pub fn cancel(&mut self, id: QueryId) -> bool {
+        let before = self.subscriptions.len();
+        self.subscriptions
+            .retain(|subscription| QueryId::of(&subscription.query) != id);
+        self.subscriptions.len() != before
+    }
+
+    /// Earliest time a periodic query is due
+    #[must_use]
+    pub fn next_due(&self) -> Option<Instant> {
+        self.subscriptions
+            .iter()
+            .filter_map(|subscription| match subscription.schedule {
+                Schedule::Periodic { next_due, .. } => Some(next_due),
+                Schedule::EventDriven => None,
+            })
+            .min()
+    }
+
+    /// Returns the Record-R PDUs of the periodic queries due at `now`
+    pub fn poll(&mut self, now: Instant) -> Vec<RecordReliablePdu> {
+        let mut responses = Vec::new();
+        for subscription in &mut self.subscriptions {
+            let Schedule::Periodic { interval, next_due } = &mut subscription.schedule else {
+                continue;
+            };
+            if *next_due > now {
+                continue;
+            }
+            // Skip the intervals missed while not polled rather than sending a burst
+            while *next_due <= now {
+                *next_due += *interval;
+            }
+            let records = self.provider.records(&subscription.query.record_ids);
+            responses.push(subscription.respond(records));
+        }
+        responses
+    }
+
+    /// Returns the Record-R PDUs of the event-driven queries that ask for any of the
+    /// `changed` records, each carrying only the changed records it asks for
+    pub fn notify(&mut self, changed: &[u32]) -> Vec<RecordReliablePdu> {
+        let mut responses = Vec::new();
+        for subscription in &mut self.subscriptions {
+            if !matches!(subscription.schedule, Schedule::EventDriven) {
+                continue;
+            }
+            let record_ids: Vec<u32> = subscription
+                .query
+                .record_ids
+                .iter()
+                .copied()
+                .filter(|record_id| changed.contains(record_id))
+                .collect();
+            if record_ids.is_empty() {
+                continue;
+            }
+            let records = self.provider.records(&record_ids);
+            responses.push(subscription.respond(records));
+        }
+        responses
+    }
+}
+
+fn record_pdu(
+    query: &RecordQueryReliablePdu,
+    response_serial_number: u32,
+    records: Vec<RecordSpecificationElement>,
+) -> RecordReliablePdu {
+    let mut response = RecordReliablePdu::new();
+    response.originating_entity_id = query.receiving_entity_id;
+    response.receiving_entity_id = query.originating_entity_id;
+    response.request_id = query.request_id;
+    response.required_reliability_service = query.required_reliability_service;
+    response.event_type = RecordREventType::Other;
+    response.response_serial_number = response_serial_number;
+    response.record_sets = RecordSpecification::from_record_sets(records);
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct Records(HashMap<u32, u32>);
+
+    impl RecordProvider for Records {
+        fn records(&mut self, record_ids: &[u32]) -> Vec<RecordSpecificationElement> {
+            record_ids
+                .iter()
+                .filter_map(|record_id| {
+                    self.0.get(record_id).map(|value| {
+                        RecordSpecificationElement::new(
+                            *record_id,
+                            1,
+                            32,
+                            1,
+                            value.to_be_bytes().to_vec(),
+                        )
+                    })
+                })
+                .collect()
+        }
+
+        fn set_records(
+            &mut self,
+            record_sets: &[RecordSpecificationElement],
+        ) -> Vec<RecordSpecificationElement> {
+            for record in record_sets {
+                if let Ok(value) = <[u8; 4]>::try_from(record.record_values.as_slice()) {
+                    self.0.insert(record.record_id, u32::from_be_bytes(value));
+                }
+            }
+            let record_ids: Vec<u32> = record_sets.iter().map(|record| record.record_id).collect();
+            self.records(&record_ids)
+        }
+    }
+
+    fn service() -> RecordService<Records> {
+        RecordService::new(Records(HashMap::from([(10, 100), (20, 200)])))
+    }
+
+    fn query(event_type: RecordQueryREventType, time: u32) -> RecordQueryReliablePdu {
+        let mut query = RecordQueryReliablePdu::new();
+        query.originating_entity_id = EntityId::new(1, 1, 1);
+        query.receiving_entity_id = EntityId::new(1, 2, 1);
+        query.request_id = 5;
+        query.event_type = event_type;
+        query.time = time;
+        query.record_ids = vec![10, 20, 30];
+        query
+    }
+
+    #[test]
+    fn one_off_query_is_answered_once() {
+        let now = Instant::now();
+        let mut service = service();
+        let response = service.handle_query(&query(RecordQueryREventType::Periodic, 0), now);
+        assert_eq!(response.request_id, 5);
+        assert_eq!(response.receiving_entity_id, EntityId::new(1, 1, 1));
+        assert_eq!(response.response_serial_number, 1);
+        assert_eq!(response.record_sets.number_of_record_sets, 2);
+        assert_eq!(service.subscription_count(), 0);
+        assert!(service.poll(now + Duration::from_secs(10)).is_empty());
+    }
+
+    #[test]
+    fn periodic_query_is_answered_every_interval() {
+        let now = Instant::now();
+        let mut service = service();
+        let _ = service.handle_query(&query(RecordQueryREventType::Periodic, 500), now);
+        assert_eq!(service.next_due(), Some(now + Duration::from_millis(500)));
+        assert!(service.poll(now + Duration::from_millis(499)).is_empty());
+
+        let responses = service.poll(now + Duration::from_millis(1_200));
+        assert_eq!(responses.len(), 1);
+        assert_eq!(responses[0].response_serial_number, 2);
+        assert_eq!(service.next_due(), Some(now + Duration::from_millis(1_500)));
+
+        assert!(service.cancel(QueryId::new(EntityId::new(1, 1, 1), 5)));
+        assert!(service.poll(now + Duration::from_secs(10)).is_empty());
+    }
+
+    #[test]
+    fn event_driven_query_sends_changed_records() {
+        let now = Instant::now();
+        let mut service = service();
+        let _ = service.handle_query(
+            &query(RecordQueryREventType::InternalEntityStateData, 0),
+            now,
+        );
+        assert!(service.notify(&[99]).is_empty());
+
+        service.provider_mut().0.insert(20, 201);
+        let responses = service.notify(&[20]);
+        assert_eq!(responses.len(), 1);
+        let records = &responses[0].record_sets.record_sets;
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].record_id, 20);
+        assert_eq!(records[0].record_values, 201u32.to_be_bytes().to_vec());
+        assert!(service.poll(now + Duration::from_secs(10)).is_empty());
+    }
+
+    #[test]
+    fn set_record_is_answered_with_the_new_values() {
+        let mut service = service();
+        let mut request = SetRecordReliablePdu::new();
+        request.request_id = 9;
+        request.record_sets =
+            RecordSpecification::from_record_sets(vec![RecordSpecificationElement::new(
+                10,
+                1,
+                32,
+                1,
+                7u32.to_be_bytes().to_vec(),
+            )]);
+        let response = service.handle_set_record(&request);
+        assert_eq!(response.request_id, 9);
+        assert_eq!(
+            response.record_sets.record_sets[0].record_values,
+            7u32.to_be_bytes().to_vec()
+        );
+        assert_eq!(service.provider().0.get(&10), Some(&7));
+    }
+}
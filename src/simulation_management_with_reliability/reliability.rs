@@ -0,0 +1,613 @@
+//     open-dis-rust - Rust implementation of the IEEE 1278.1-2012 Distributed Interactive
+//                     Simulation (DIS) application protocol
+//     Copyright (C) 2025 Cameron Howell
+//
+//     Licensed under the BSD 2-Clause License
+
+//! The reliability service of the SIMAN-R protocol family, IEEE 1278.1-2012 §5.12.4
+//!
+//! On the sending side, a [`ReliabilityEngine`] numbers each reliable request, retransmits it
+//! when no reply has arrived within the timeout, gives up after the retry limit, and completes
+//! it when the matching Acknowledge-R, Action Response-R, Data-R or Record-R PDU arrives. On the
+//! receiving side, it tells new requests from retransmissions and replays the reply sent for a
+//! request it has already answered. The engine does no I/O of its own; time is passed in by
+//! the caller.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::{Duration, Instant},
+};
+
+use num_traits::FromPrimitive;
+
+use crate::{
+    common::{
+        data_types::entity_id::EntityId,
+        enums::{
+            AcknowledgeFlag, AcknowledgeResponseFlag, ActionResponseRequestStatus,
+            RequiredReliabilityService,
+        },
+    },
+    simulation_management_with_reliability::{
+        AcknowledgeReliablePdu, ActionRequestReliablePdu, ActionResponseReliablePdu,
+        CreateEntityReliablePdu, DataQueryReliablePdu, DataReliablePdu, RecordQueryReliablePdu,
+        RecordReliablePdu, RemoveEntityReliablePdu, SetDataReliablePdu, SetRecordReliablePdu,
+        StartResumeReliablePdu, StopFreezeReliablePdu,
+    },
+};
+
+/// Default time to wait for a reply before retransmitting a request
+pub const DEFAULT_RETRANSMIT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Default number of retransmissions before a request is given up
+pub const DEFAULT_MAX_RETRIES: u32 = 3;
+
+/// Default time a received request is remembered to recognize its retransmissions
+pub const DEFAULT_DUPLICATE_WINDOW: Duration = Duration::from_mins(1);
+
+/// Timeouts and retry limits of a [`ReliabilityEngine`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ReliabilityConfig {
+    pub retransmit_timeout: Duration,
+    pub max_retries: u32,
+    pub duplicate_window: Duration,
+}
+
+impl Default for ReliabilityConfig {
+    fn default() -> Self {
+        Self {
+            retransmit_timeout: DEFAULT_RETRANSMIT_TIMEOUT,
+            max_retries: DEFAULT_MAX_RETRIES,
+            duplicate_window: DEFAULT_DUPLICATE_WINDOW,
+        }
+    }
+}
+
+impl ReliabilityConfig {
+    #[must_use]
+    pub const fn with_retransmit_timeout(mut self, timeout: Duration) -> Self {
+        self.retransmit_timeout = timeout;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_duplicate_window(mut self, window: Duration) -> Self {
+        self.duplicate_window = window;
+        self
+    }
+}
+
+/// A SIMAN-R request that is answered by a reply carrying the same request ID
+#[derive(Clone, Debug)]
+pub enum ReliableRequest {
+    CreateEntity(CreateEntityReliablePdu),
+    RemoveEntity(RemoveEntityReliablePdu),
+    StartResume(StartResumeReliablePdu),
+    StopFreeze(StopFreezeReliablePdu),
+    ActionRequest(ActionRequestReliablePdu),
+    DataQuery(DataQueryReliablePdu),
+    SetData(SetDataReliablePdu),
+    RecordQuery(RecordQueryReliablePdu),
+    SetRecord(SetRecordReliablePdu),
+}
+
+macro_rules! with_request {
+    ($request:expr, $pdu:ident => $body:expr) => {
+        match $request {
+            ReliableRequest::CreateEntity($pdu) => $body,
+            ReliableRequest::RemoveEntity($pdu) => $body,
+            ReliableRequest::StartResume($pdu) => $body,
+            ReliableRequest::StopFreeze($pdu) => $body,
+            ReliableRequest::ActionRequest($pdu) => $body,
+            ReliableRequest::DataQuery($pdu) => $body,
+            ReliableRequest::SetData($pdu) => $body,
+            ReliableRequest::RecordQuery($pdu) => $body,
+            ReliableRequest::SetRecord($pdu) => $body,
+        }
+    };
+}
+
+impl ReliableRequest {
+    #[must_use]
+    pub const fn request_id(&self) -> u32 {
+        with_request!(self, pdu => pdu.request_id)
+    }
+
+    pub const fn set_request_id(&mut self, request_id: u32) {
+        with_request!(self, pdu => pdu.request_id = request_id);
+    }
+
+    #[must_use]
+    pub const fn originating_entity_id(&self) -> EntityId {
+        with_request!(self, pdu => pdu.originating_entity_id)
+    }
+
+    #[must_use]
+    pub const fn receiving_entity_id(&self) -> EntityId {
+        with_request!(self, pdu => pdu.receiving_entity_id)
+    }
+
+    #[must_use]
+    pub fn required_reliability_service(&self) -> RequiredReliabilityService {
+        match self {
+            Self::CreateEntity(pdu) => pdu.required_reliability_service,
+            Self::RemoveEntity(pdu) => pdu.required_reliability_service,
+            Self::StartResume(pdu) => pdu.required_reliability_service,
+            Self::StopFreeze(pdu) => {
+                RequiredReliabilityService::from_u8(pdu.required_reliability_service)
+                    .unwrap_or_default()
+            }
+            Self::ActionRequest(pdu) => pdu.required_reliability_service,
+            Self::DataQuery(pdu) => pdu.required_reliability_service,
+            Self::SetData(pdu) => pdu.required_reliability_service,
+            Self::RecordQuery(pdu) => pdu.required_reliability_service,
+            Self::SetRecord(pdu) => pdu.required_reliability_service,
+        }
+    }
+
+    /// The acknowledge flag of the Acknowledge-R PDU that answers this request, if it is
+    /// answered by one
+    #[must_use]
+    pub const fn acknowledge_flag(&self) -> Option<AcknowledgeFlag> {
+        match self {
+            Self::CreateEntity(_) => Some(AcknowledgeFlag::CreateEntity),
+            Self::RemoveEntity(_) => Some(AcknowledgeFlag::RemoveEntity),
+            Self::StartResume(_) => Some(AcknowledgeFlag::StartResume),
+            Self::StopFreeze(_) => Some(AcknowledgeFlag::StopFreeze),
+            Self::ActionRequest(_)
+            | Self::DataQuery(_)
+            | Self::SetData(_)
+            | Self::RecordQuery(_)
+            | Self::SetRecord(_) => None,
+        }
+    }
+
+    /// Builds the Acknowledge-R PDU that answers this request, if it is answered by one
+    #[must_use]
+    pub fn acknowledgement(
+        &self,
+        response_flag: AcknowledgeResponseFlag,
+    ) -> Option<AcknowledgeReliablePdu> {
+        let acknowledge_flag = self.acknowledge_flag()?;
+        let mut pdu = AcknowledgeReliablePdu::new();
+        pdu.originating_entity_id = self.receiving_entity_id();
+        pdu.receiving_entity_id = self.originating_entity_id();
+        pdu.acknowledge_flag = acknowledge_flag;
+        pdu.response_flag = response_flag;
+        pdu.request_id = self.request_id();
+        Some(pdu)
+    }
+
+    /// Whether `response` is the kind of reply this request expects
+    fn is_answered_by(&self, response: &ReliableResponse) -> bool {
+        match response {
+            ReliableResponse::Acknowledge(pdu) => {
+                self.acknowledge_flag() == Some(pdu.acknowledge_flag)
+            }
+            ReliableResponse::ActionResponse(_) => matches!(self, Self::ActionRequest(_)),
+            ReliableResponse::Data(_) => matches!(self, Self::DataQuery(_) | Self::SetData(_)),
+            ReliableResponse::Record(_) => {
+                matches!(self, Self::RecordQuery(_) | Self::SetRecord(_))
+            }
+        }
+    }
+}
+
+/// A SIMAN-R reply that completes the request with the same request ID
+#[derive(Clone, Debug)]
+pub enum ReliableResponse {
+    Acknowledge(AcknowledgeReliablePdu),
+    ActionResponse(ActionResponseReliablePdu),
+    Data(DataReliablePdu),
+    Record(RecordReliablePdu),
+}
+
+impl ReliableResponse {
+    #[must_use]
+    pub const fn request_id(&self) -> u32 {
+        match self {
+            Self::Acknowledge(pdu) => pdu.request_id,
+            Self::ActionResponse(pdu) => pdu.request_id,
+            Self::Data(pdu) => pdu.request_id,
+            Self::Record(pdu) => pdu.request_id,
+        }
+    }
+
+    #[must_use]
+    pub const fn originating_entity_id(&self) -> EntityId {
+        match self {
+            Self::Acknowledge(pdu) => pdu.originating_entity_id,
+            Self::ActionResponse(pdu) => pdu.originating_entity_id,
+            Self::Data(pdu) => pdu.originating_entity_id,
+            Self::Record(pdu) => pdu.originating_entity_id,
+        }
+    }
+}
+
+/// What the engine did with a request whose timeout expired
+#[derive(Clone, Debug)]
+pub enum RetryOutcome {
+    /// The request is due to be sent again
+    Retransmit(ReliableRequest),
+    /// The retry limit was reached without a reply; the request is no longer tracked
+    GaveUp(ReliableRequest),
+}
+
+/// A request that has been answered
+#[derive(Clone, Debug)]
+pub struct Completion {
+    pub request: ReliableRequest,
+    pub response: ReliableResponse,
+}
+
+/// How a received request relates to the requests received before
+#[derive(Clone, Debug)]
+pub enum Received {
+    /// The request is new and should be carried out, then answered with
+    /// [`ReliabilityEngine::respond`]
+    New,
+    /// The request is a retransmission; the reply already sent, if any, is to be sent again
+    Duplicate(Option<ReliableResponse>),
+}
+
+#[derive(Debug)]
+struct Pending {
+    request: ReliableRequest,
+    deadline: Instant,
+    retries: u32,
+}
+
+#[derive(Debug)]
+struct Answered {
+    response: Option<ReliableResponse>,
+    received_at: Instant,
+}
+
+/// Sends reliable SIMAN-R requests and answers received ones
+#[derive(Debug)]
+pub struct ReliabilityEngine {
+    config: ReliabilityConfig,
+    next_request_id: u32,
+    pending: BTreeMap<u32, Pending>,
+    received: HashMap<(EntityId, u32), Answered>,
+}
+
+impl ReliabilityEngine {
+    #[must_use]
+    pub fn new(config: ReliabilityConfig) -> Self {
+        Self {
+            config,
+            next_request_id: 1,
+            pending: BTreeMap::new(),
+            received: HashMap::new(),
+        }
+    }
+
+    #[must_use]
+    pub const fn config(&self) -> &ReliabilityConfig {
+        &self.config
+    }
+
+    /// Assigns the next request ID to `request` and returns the PDU to send
+    ///
+    /// An acknowledged request is tracked until it is answered or given up. An unacknowledged
+    /// request that still expects a response is tracked without retransmission; one that
+    /// expects only an Acknowledge-R PDU is not tracked at all.
+    pub fn send(&mut self, mut request: ReliableRequest, now: Instant) -> ReliableRequest {
+        let request_id = self.next_request_id;
+        self.next_request_id = self.next_request_id.wrapping_add(1).max(1);
+        request.set_request_id(request_id);
+
+        let acknowledged =
+            request.required_reliability_service() == RequiredReliabilityService::Acknowledged;
+        if acknowledged || request.acknowledge_flag().is_none() {
+            self.pending.insert(
+                request_id,
+                Pending {
+                    request: request.clone(),
+                    deadline: now + self.config.retransmit_timeout,
+                    retries: if acknowledged {
+                        0
+                    } else {
+                        self.config.max_retries
+                    },
+                },
+            );
+        }
+        request
+    }
+
+    /// Number of requests awaiting a reply
+    #[must_use]
+    pub fn pending_len(&self) -> usize {
+        self.pending.len()
+    }
+
+    #[must_use]
+    pub fn is_pending(&self, request_id: u32) -> bool {
+        self.pending.contains_key(&request_id)
+    }
+
+    /// Stops tracking a request without waiting for its reply
+    pub fn cancel(&mut self, request_id: u32) -> Option<ReliableRequest> {
+        self.pending
+            .remove(&request_id)
+            .map(|pending| pending.request)
+    }
+
+    /// Earliest time a pending request times out
+    #[must_use]
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.pending.values().map(|pending| pending.deadline).min()
+    }
+
+    /// Retransmits or gives up the requests whose timeout has expired at `now`, and forgets
+    /// received requests older than the duplicate window
+    pub fn poll(&mut self, now: Instant) -> Vec<RetryOutcome> {
+        let window = self.config.duplicate_window;
+        self.received
+            .retain(|_, answered| now.saturating_duration_since(answered.received_at) < window);
+
+        let expired: Vec<u32> = self
+            .pending
+            .iter()
+            .filter(|(_, pending)| pending.deadline <= now)
+            .map(|(request_id, _)| *request_id)
+            .collect();
+
+        let mut outcomes = Vec::with_capacity(expired.len());
+        for request_id in expired {
+            let Some(pending) = self.pending.get_mut(&request_id) else {
+                continue;
+            };
+            if pending.retries < self.config.max_retries {
+                pending.retries += 1;
+                pending.deadline = now + self.config.retransmit_timeout;
+                outcomes.push(RetryOutcome::Retransmit(pending.request.clone()));
+            } else if let Some(pending) = self.pending.remove(&request_id) {
+                outcomes.push(RetryOutcome::GaveUp(pending.request));
+            }
+        }
+        outcomes
+    }
+
+    /// Matches a received reply against the pending requests
+    ///
+    /// Returns the completed request, or `None` if the reply answers no pending request. An
+    /// Action Response-R PDU asking for the request to be retransmitted keeps the request
+    /// pending: now makes it due at once, later restarts its timeout.
+    pub fn handle_response(
+        &mut self,
+        response: ReliableResponse,
+        now: Instant,
+    ) -> Option<Completion> {
+        let request_id = response.request_id();
+        let pending = self.pending.get_mut(&request_id)?;
+        if !pending.request.is_answered_by(&response) {
+            return None;
+        }
+
+        if let ReliableResponse::ActionResponse(pdu) = &response {
+            match pdu.request_status {
+                ActionResponseRequestStatus::RetransmitRequestNow => {
+                    pending.deadline = now;
+                    return None;
+                }
+                ActionResponseRequestStatus::RetransmitRequestLater => {
+                    pending.deadline = now + self.config.retransmit_timeout;
+                    return None;
+                }
+                _ => {}
+            }
+        }
+
+        self.pending.remove(&request_id).map(|pending| Completion {
+            request: pending.request,
+            response,
+        })
+    }
+
+    /// Classifies a received request as new or as a retransmission of one already received
+    pub fn receive(&mut self, request: &ReliableRequest, now: Instant) -> Received {
+        let key = (request.originating_entity_id(), request.request_id());
+        if let Some(answered) = self.received.get(&key) {
+            return Received::Duplicate(answered.response.clone());
+        }
+        self.received.insert(
+            key,
+            Answered {
+                response: None,
+                received_at: now,
+            },
+        );
+        Received::New
+    }
+
+    /// Records the reply to a received request so that retransmissions of the request are
+    /// answered with it, and returns the reply to send
+    pub fn respond(
+        &mut self,
+        request: &ReliableRequest,
+        response: ReliableResponse,
+        now: Instant,
+    ) -> ReliableResponse {
+        let key = (request.originating_entity_id(), request.request_id());
+        let answered = self.received.entry(key).or_insert(Answered {
+            response: None,
+            received_at: now,
+        });
+        answered.response = Some(response.clone());
+        response
+    }
+
+    /// Acknowledges a received Create Entity-R, Remove Entity-R, Start/Resume-R or
+    /// Stop/Freeze-R request, recording the acknowledgement for retransmissions
+    ///
+    /// Returns `None` for requests that are not answered by an Acknowledge-R PDU.
+    pub fn acknowledge(
+        &mut self,
+        request: &ReliableRequest,
+        response_flag: AcknowledgeResponseFlag,
+        now: Instant,
+    ) -> Option<AcknowledgeReliablePdu> {
+        let acknowledgement = request.acknowledgement(response_flag)?;
+        self.respond(
+            request,
+            ReliableResponse::Acknowledge(acknowledgement.clone()),
+            now,
+        );
+        Some(acknowledgement)
+    }
+}
+
+impl Default for ReliabilityEngine {
+    fn default() -> Self {
+        Self::new(ReliabilityConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const fn entity(number: u16) -> EntityId {
+        EntityId::new(1, 1, number)
+    }
+
+    fn create_entity() -> ReliableRequest {
+        let mut pdu = CreateEntityReliablePdu::new();
+        pdu.originating_entity_id = entity(1);
+        pdu.receiving_entity_id = entity(2);
+        ReliableRequest::CreateEntity(pdu)
+    }
+
+    #[test]
+    fn request_is_retransmitted_then_given_up() {
+        let start = Instant::now();
+        let mut engine = ReliabilityEngine::new(ReliabilityConfig::default().with_max_retries(2));
+        let sent = engine.send(create_entity(), start);
+        assert_eq!(sent.request_id(), 1);
+        assert!(engine.poll(start + Duration::from_millis(999)).is_empty());
+
+        for retry in 1..=2 {
+            let outcomes = engine.poll(start + Duration::from_secs(retry));
+            assert!(matches!(
+                outcomes.as_slice(),
+                [RetryOutcome::Retransmit(request)] if request.request_id() == 1
+            ));
+        }
+        let outcomes = engine.poll(start + Duration::from_secs(3));
+        assert!(matches!(outcomes.as_slice(), [RetryOutcome::GaveUp(_)]));
+        assert_eq!(engine.pending_len(), 0);
+    }
+
+    #[test]
+    fn matching_acknowledgement_completes_the_request() {
+        let start = Instant::now();
+        let mut sender = ReliabilityEngine::default();
+        let mut receiver = ReliabilityEngine::default();
+        let request = sender.send(create_entity(), start);
+
+        assert!(matches!(receiver.receive(&request, start), Received::New));
+        let ack = receiver
+            .acknowledge(&request, AcknowledgeResponseFlag::AbleToComply, start)
+            .expect("create entity is acknowledged");
+        assert_eq!(ack.receiving_entity_id, entity(1));
+
+        let mut wrong = ack.clone();
+        wrong.acknowledge_flag = AcknowledgeFlag::RemoveEntity;
+        assert!(
+            sender
+                .handle_response(ReliableResponse::Acknowledge(wrong), start)
+                .is_none()
+        );
+
+        let completion = sender
+            .handle_response(ReliableResponse::Acknowledge(ack), start)
+            .expect("request is completed");
+        assert_eq!(completion.request.request_id(), request.request_id());
+        assert!(!sender.is_pending(request.request_id()));
+        assert!(sender.poll(start + Duration::from_secs(5)).is_empty());
+    }
+
+    #[test]
+    fn retransmitted_request_is_answered_again() {
+        let start = Instant::now();
+        let mut receiver = ReliabilityEngine::default();
+        let mut request = create_entity();
+        request.set_request_id(7);
+
+        assert!(matches!(receiver.receive(&request, start), Received::New));
+        assert!(matches!(
+            receiver.receive(&request, start),
+            Received::Duplicate(None)
+        ));
+        let _ = receiver.acknowledge(&request, AcknowledgeResponseFlag::AbleToComply, start);
+        assert!(matches!(
+            receiver.receive(&request, start),
+            Received::Duplicate(Some(ReliableResponse::Acknowledge(ack))) if ack.request_id == 7
+        ));
+
+        let _ = receiver.poll(start + DEFAULT_DUPLICATE_WINDOW);
+        assert!(matches!(
+            receiver.receive(&request, start + DEFAULT_DUPLICATE_WINDOW),
+            Received::New
+        ));
+    }
+
+    #[test]
+    fn action_response_can_ask_for_retransmission() {
+        let start = Instant::now();
+        let mut engine = ReliabilityEngine::default();
+        let request = engine.send(
+            ReliableRequest::ActionRequest(ActionRequestReliablePdu::new()),
+            start,
+        );
+
+        let mut response = ActionResponseReliablePdu::new();
+        response.request_id = request.request_id();
+        response.request_status = ActionResponseRequestStatus::RetransmitRequestNow;
+        let later = start + Duration::from_millis(10);
+        assert!(
+            engine
+                .handle_response(ReliableResponse::ActionResponse(response.clone()), later)
+                .is_none()
+        );
+        assert!(matches!(
+            engine.poll(later).as_slice(),
+            [RetryOutcome::Retransmit(_)]
+        ));
+
+        response.request_status = ActionResponseRequestStatus::Complete;
+        assert!(
+            engine
+                .handle_response(ReliableResponse::ActionResponse(response), later)
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn unacknowledged_create_is_not_tracked() {
+        let start = Instant::now();
+        let mut engine = ReliabilityEngine::default();
+        let mut pdu = CreateEntityReliablePdu::new();
+        pdu.required_reliability_service = RequiredReliabilityService::Unacknowledged;
+        let _ = engine.send(ReliableRequest::CreateEntity(pdu), start);
+        assert_eq!(engine.pending_len(), 0);
+
+        let mut query = DataQueryReliablePdu::new();
+        query.required_reliability_service = RequiredReliabilityService::Unacknowledged;
+        let _ = engine.send(ReliableRequest::DataQuery(query), start);
+        assert_eq!(engine.pending_len(), 1);
+        assert!(matches!(
+            engine.poll(start + DEFAULT_RETRANSMIT_TIMEOUT).as_slice(),
+            [RetryOutcome::GaveUp(_)]
+        ));
+    }
+}
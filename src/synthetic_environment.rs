@@ -6,6 +6,8 @@
 
 //! The Synthetic Environment protocol family
 
+pub mod object_database;
+
 use crate::{
     common::{
         GenericHeader, SerializedLength,
@@ -13,12 +15,13 @@ use crate::{
             ClockTime, EntityId, EntityType, EulerAngles, WorldCoordinate,
             environment::Environment, grid_axis_descriptor::GridAxisDescriptor,
             grid_data_record::GridDataRecord, linear_segment_parameter::LinearSegmentParameter,
-            object_identifier::ObjectIdentifier, object_type::ObjectType,
-            simulation_address::SimulationAddress,
+            object_appearance::SpecificObjectAppearance, object_identifier::ObjectIdentifier,
+            object_type::ObjectType, simulation_address::SimulationAddress,
         },
         enums::{
             ForceId, GriddedDataConstantGrid, GriddedDataCoordinateSystem,
-            ObjectStateAppearanceGeneral, PduType, ProtocolFamily,
+            ObjectStateAppearanceGeneral, ObjectStateModificationArealObject,
+            ObjectStateModificationPointObject, PduType, ProtocolFamily,
         },
         pdu::Pdu,
         pdu_header::PduHeader,
@@ -40,6 +43,7 @@ define_pdu! {
             pub environment_status: u8,
             pub number_of_environment_records: u16,
             pub sequence_number: u16,
+            #[count = number_of_environment_records]
             pub environment_records: Vec<Environment>,
         }
     }
@@ -85,7 +89,7 @@ define_pdu! {
             pub referenced_object_id: EntityId,
             pub update_number: u16,
             pub force_id: ForceId,
-            pub modifications: u8,
+            pub modifications: ObjectStateModificationPointObject,
             pub object_type: ObjectType,
             pub object_location: WorldCoordinate,
             pub object_orientation: EulerAngles,
@@ -99,6 +103,21 @@ define_pdu! {
     }
 }
 
+impl PointObjectStatePdu {
+    /// Interprets the specific object appearance according to the object type
+    #[must_use]
+    pub fn specific_appearance(&self) -> SpecificObjectAppearance {
+        SpecificObjectAppearance::for_point_object(
+            &self.object_type,
+            self.specific_object_appearance,
+        )
+    }
+
+    pub const fn set_specific_appearance(&mut self, appearance: SpecificObjectAppearance) {
+        self.specific_object_appearance = appearance.to_u32();
+    }
+}
+
 define_pdu! {
     #[derive(Debug)]
     /// Implemented according to IEEE 1278.1-2012 §7.10.5
@@ -120,6 +139,16 @@ define_pdu! {
     }
 }
 
+impl LinearObjectStatePdu {
+    /// Interprets the specific appearance of the segment at `index` according to the object type
+    #[must_use]
+    pub fn segment_appearance(&self, index: usize) -> Option<SpecificObjectAppearance> {
+        self.linear_segment_parameters
+            .get(index)
+            .map(|segment| segment.specific_appearance(&self.object_type))
+    }
+}
+
 define_pdu! {
     #[derive(Debug)]
     /// Implemented according to IEEE 1278.1-2012 §7.10.6
@@ -132,9 +161,9 @@ define_pdu! {
             pub referenced_object_id: ObjectIdentifier,
             pub update_number: u16,
             pub force_id: ForceId,
-            pub modifications: u8, // TODO(@anyone) Replace with Modifications UID 242
+            pub modifications: ObjectStateModificationArealObject,
             pub object_type: ObjectType,
-            pub specific_object_appearance: u32, // TODO(@anyone) Implement Specific Object Appearance
+            pub specific_object_appearance: u32,
             pub general_object_appearance: ObjectStateAppearanceGeneral,
             pub number_of_points: u16,
             pub requester_id: SimulationAddress,
@@ -144,6 +173,21 @@ define_pdu! {
     }
 }
 
+impl ArealObjectStatePdu {
+    /// Interprets the specific object appearance according to the object type
+    #[must_use]
+    pub fn specific_appearance(&self) -> SpecificObjectAppearance {
+        SpecificObjectAppearance::for_areal_object(
+            &self.object_type,
+            self.specific_object_appearance,
+        )
+    }
+
+    pub const fn set_specific_appearance(&mut self, appearance: SpecificObjectAppearance) {
+        self.specific_object_appearance = appearance.to_u32();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -152,6 +196,11 @@ mod tests {
 
     mod environmental_process_pdu_tests {
         use super::*;
+        use crate::common::data_types::environment::{
+            BoundingSphereRecord, ConeRecord1, EnvironmentRecord, GaussianPuffRecord, LineRecord1,
+            SphereRecord2,
+        };
+        use bytes::Buf;
 
         #[test]
         fn cast_to_any() {
@@ -179,6 +228,81 @@ mod tests {
             let pdu = EnvironmentalProcessPdu::new();
             assert_eq!(pdu.header().length, DEFAULT_LENGTH);
         }
+
+        #[test]
+        fn serialize_then_deserialize_environment_records() {
+            let mut pdu = EnvironmentalProcessPdu::new();
+            pdu.environment_records = vec![
+                Environment::new(
+                    1,
+                    EnvironmentRecord::BoundingSphere(BoundingSphereRecord {
+                        centroid_location: WorldCoordinate::new(1.0, 2.0, 3.0),
+                        radius: 150.0,
+                    }),
+                ),
+                Environment::new(
+                    2,
+                    EnvironmentRecord::GaussianPuff(GaussianPuffRecord {
+                        centroid_height: 12.5,
+                        ..Default::default()
+                    }),
+                ),
+                Environment::new(
+                    3,
+                    EnvironmentRecord::Unknown {
+                        environment_type: 42,
+                        record_specific_fields: vec![1, 2, 3, 4, 5, 6, 7, 8],
+                    },
+                ),
+            ];
+            let mut serialize_buf = BytesMut::new();
+            let _ = pdu.serialize(&mut serialize_buf);
+            assert_eq!(pdu.number_of_environment_records, 3);
+            assert_eq!(usize::from(pdu.header().length), serialize_buf.len());
+
+            let mut deserialize_buf = serialize_buf.freeze();
+            let new_pdu =
+                EnvironmentalProcessPdu::deserialize(&mut deserialize_buf).unwrap_or_default();
+            assert_eq!(new_pdu.environment_records, pdu.environment_records);
+            assert_eq!(deserialize_buf.remaining(), 0);
+        }
+
+        #[test]
+        fn environment_records_are_padded_to_64_bits() {
+            let line = Environment::new(0, EnvironmentRecord::Line1(LineRecord1::default()));
+            assert_eq!(line.length(), 448);
+
+            let cone = Environment::new(0, EnvironmentRecord::Cone1(ConeRecord1::default()));
+            assert_eq!(cone.length(), 448);
+
+            let sphere = Environment::new(0, EnvironmentRecord::Sphere2(SphereRecord2::default()));
+            assert_eq!(sphere.length(), 512);
+
+            let mut buf = BytesMut::new();
+            cone.serialize(&mut buf);
+            assert_eq!(buf.len() * 8, usize::from(cone.length()));
+        }
+
+        #[test]
+        fn truncated_environment_records_are_kept_as_unknown() {
+            let sphere = Environment::new(
+                1,
+                EnvironmentRecord::BoundingSphere(BoundingSphereRecord::default()),
+            );
+            let mut buf = BytesMut::new();
+            sphere.serialize(&mut buf);
+            buf.truncate(20);
+
+            let mut deserialize_buf = buf.freeze();
+            let truncated = Environment::deserialize(&mut deserialize_buf);
+            assert_eq!(deserialize_buf.remaining(), 0);
+            assert_eq!(truncated.environment_type(), sphere.environment_type());
+            assert!(matches!(
+                truncated.record,
+                EnvironmentRecord::Unknown { ref record_specific_fields, .. }
+                    if record_specific_fields.len() == 12
+            ));
+        }
     }
 
     mod gridded_data_pdu_tests {
@@ -213,6 +337,10 @@ mod tests {
 
     mod point_object_state_pdu_tests {
         use super::*;
+        use crate::common::{
+            data_types::object_appearance::{BurstAppearance, CraterAppearance},
+            enums::{AppearanceObjectSpecificChemicalType, ObjectKind},
+        };
 
         #[test]
         fn cast_to_any() {
@@ -240,10 +368,57 @@ mod tests {
             let pdu = PointObjectStatePdu::new();
             assert_eq!(pdu.header().length, DEFAULT_LENGTH);
         }
+
+        #[test]
+        fn serialize_then_deserialize_burst_appearance() {
+            let mut pdu = PointObjectStatePdu::new();
+            pdu.object_type = ObjectType::new(0, ObjectKind::TacticalSmoke as u8, 1, 0);
+            pdu.modifications = ObjectStateModificationPointObject::IsLocationModified;
+            pdu.set_specific_appearance(SpecificObjectAppearance::Burst(BurstAppearance {
+                opacity: 80,
+                size: 25,
+                height: 10,
+                number_of_bursts: 3,
+                chemical_type: AppearanceObjectSpecificChemicalType::WhitePhosphorous,
+            }));
+            assert_eq!(pdu.specific_object_appearance, 0x830A_1950);
+
+            let mut serialize_buf = BytesMut::new();
+            let _ = pdu.serialize(&mut serialize_buf);
+            let mut deserialize_buf = serialize_buf.freeze();
+            let new_pdu =
+                PointObjectStatePdu::deserialize(&mut deserialize_buf).unwrap_or_default();
+            assert_eq!(new_pdu.modifications, pdu.modifications);
+            assert_eq!(new_pdu.specific_appearance(), pdu.specific_appearance());
+        }
+
+        #[test]
+        fn crater_appearance_is_keyed_by_category() {
+            let mut pdu = PointObjectStatePdu::new();
+            pdu.object_type = ObjectType::new(0, ObjectKind::Obstacle as u8, 3, 0);
+            pdu.specific_object_appearance = 12;
+            assert_eq!(
+                pdu.specific_appearance(),
+                SpecificObjectAppearance::Crater(CraterAppearance { size: 12 })
+            );
+
+            pdu.object_type = ObjectType::new(0, ObjectKind::CulturalFeature as u8, 3, 0);
+            assert_eq!(
+                pdu.specific_appearance(),
+                SpecificObjectAppearance::Other(12)
+            );
+        }
     }
 
     mod linear_object_state_pdu_tests {
         use super::*;
+        use crate::common::{
+            data_types::object_appearance::SmokeAppearance,
+            enums::{
+                AppearanceObjectSpecificChemicalType, ObjectKind,
+                ObjectStateModificationLinearObject,
+            },
+        };
 
         #[test]
         fn cast_to_any() {
@@ -270,10 +445,39 @@ mod tests {
             let pdu = LinearObjectStatePdu::new();
             assert_eq!(pdu.header().length, DEFAULT_LENGTH);
         }
+
+        #[test]
+        fn segment_appearance_uses_object_type() {
+            let mut pdu = LinearObjectStatePdu::new();
+            pdu.object_type = ObjectType::new(0, ObjectKind::TacticalSmoke as u8, 1, 0);
+            let mut segment = LinearSegmentParameter::default();
+            segment.segment_modification = ObjectStateModificationLinearObject::all();
+            segment.set_specific_appearance(SpecificObjectAppearance::Smoke(SmokeAppearance {
+                opacity: 50,
+                attached: true,
+                chemical_type: AppearanceObjectSpecificChemicalType::Hydrochloric,
+            }));
+            pdu.number_of_segments = 1;
+            pdu.linear_segment_parameters.push(segment);
+
+            assert_eq!(
+                pdu.segment_appearance(0),
+                Some(SpecificObjectAppearance::Smoke(SmokeAppearance {
+                    opacity: 50,
+                    attached: true,
+                    chemical_type: AppearanceObjectSpecificChemicalType::Hydrochloric,
+                }))
+            );
+            assert_eq!(pdu.segment_appearance(1), None);
+        }
     }
 
     mod areal_object_state_pdu_tests {
         use super::*;
+        use crate::common::{
+            data_types::object_appearance::BreachAppearance,
+            enums::{AppearanceObjectSpecificBreachState, ObjectKind},
+        };
 
         #[test]
         fn cast_to_any() {
@@ -301,5 +505,28 @@ mod tests {
             let pdu = ArealObjectStatePdu::new();
             assert_eq!(pdu.header().length, DEFAULT_LENGTH);
         }
+
+        #[test]
+        fn serialize_then_deserialize_minefield_appearance() {
+            let mut pdu = ArealObjectStatePdu::new();
+            pdu.object_type = ObjectType::new(0, ObjectKind::Obstacle as u8, 1, 0);
+            pdu.modifications = ObjectStateModificationArealObject::IsLocationModified;
+            pdu.set_specific_appearance(SpecificObjectAppearance::Breach(BreachAppearance {
+                breach_state: AppearanceObjectSpecificBreachState::Cleared,
+            }));
+
+            let mut serialize_buf = BytesMut::new();
+            let _ = pdu.serialize(&mut serialize_buf);
+            let mut deserialize_buf = serialize_buf.freeze();
+            let new_pdu =
+                ArealObjectStatePdu::deserialize(&mut deserialize_buf).unwrap_or_default();
+            assert_eq!(new_pdu.modifications, pdu.modifications);
+            assert_eq!(
+                new_pdu.specific_appearance(),
+                SpecificObjectAppearance::Breach(BreachAppearance {
+                    breach_state: AppearanceObjectSpecificBreachState::Cleared,
+                })
+            );
+        }
     }
 }
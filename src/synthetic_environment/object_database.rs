@@ -0,0 +1,465 @@
+//     open-dis-rust - Rust implementation of the IEEE 1278.1-2012 Distributed Interactive
+//                     Simulation (DIS) application protocol
+//     Copyright (C) 2025 Cameron Howell
+//
+//     Licensed under the BSD 2-Clause License
+
+//! A database of the synthetic environment objects published through the point, linear and
+//! areal object state PDUs
+//!
+//! The database does no I/O of its own: PDUs are handed to it together with the time they were
+//! received, and objects that stop heartbeating are dropped by calling
+//! [`ObjectDatabase::expire`].
+
+use std::{
+    collections::{HashMap, HashSet},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    common::{
+        constants::NO_OBJECT,
+        data_types::{
+            EulerAngles, WorldCoordinate, object_appearance::SpecificObjectAppearance,
+            object_identifier::ObjectIdentifier, object_type::ObjectType,
+        },
+        enums::ForceId,
+    },
+    synthetic_environment::{ArealObjectStatePdu, LinearObjectStatePdu, PointObjectStatePdu},
+};
+
+/// Default time after which an object that has not been updated is removed, 2.4 times the
+/// standard 5 second heartbeat
+pub const DEFAULT_OBJECT_TIMEOUT: Duration = Duration::from_secs(12);
+
+/// A single segment of a linear object
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ObjectSegment {
+    pub segment_number: u8,
+    pub location: WorldCoordinate,
+    pub orientation: EulerAngles,
+    /// Length of the segment in meters, along its X-axis
+    pub length: f32,
+    /// Width of the segment in meters, along its Y-axis
+    pub width: f32,
+    /// Height of the segment above ground in meters
+    pub height: f32,
+    /// Depth of the segment below ground in meters
+    pub depth: f32,
+    pub general_appearance: u32,
+    pub specific_appearance: SpecificObjectAppearance,
+}
+
+/// The geometry of an object in world coordinates
+#[derive(Clone, Debug, PartialEq)]
+pub enum ObjectGeometry {
+    Point {
+        location: WorldCoordinate,
+        orientation: EulerAngles,
+    },
+    Linear {
+        segments: Vec<ObjectSegment>,
+    },
+    Areal {
+        points: Vec<WorldCoordinate>,
+    },
+}
+
+impl ObjectGeometry {
+    /// Every location describing the object: the point itself, the segment locations or the
+    /// vertices of the area
+    #[must_use]
+    pub fn locations(&self) -> Vec<WorldCoordinate> {
+        match self {
+            Self::Point { location, .. } => vec![*location],
+            Self::Linear { segments } => segments.iter().map(|segment| segment.location).collect(),
+            Self::Areal { points } => points.clone(),
+        }
+    }
+}
+
+/// The last known state of an object
+#[derive(Clone, Debug, PartialEq)]
+pub struct ObjectState {
+    pub object_id: ObjectIdentifier,
+    /// The object this object is associated with, if any
+    pub referenced_object_id: Option<ObjectIdentifier>,
+    pub update_number: u16,
+    pub force_id: ForceId,
+    pub object_type: ObjectType,
+    pub geometry: ObjectGeometry,
+    /// Specific appearance of point and areal objects; linear objects carry theirs per segment
+    pub specific_appearance: Option<SpecificObjectAppearance>,
+    /// Time of the last accepted update
+    pub last_update: Instant,
+}
+
+/// The result of handing an object state PDU to the database
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ObjectUpdate {
+    /// The object was not known and has been added
+    Created,
+    /// The object was known and its state has been replaced
+    Updated,
+    /// The PDU carried an update number that is not newer than the one already held
+    Stale,
+}
+
+/// Tracks synthetic environment objects by their object identifier
+#[derive(Clone, Debug)]
+pub struct ObjectDatabase {
+    objects: HashMap<ObjectIdentifier, ObjectState>,
+    timeout: Duration,
+}
+
+impl Default for ObjectDatabase {
+    fn default() -> Self {
+        Self::new(DEFAULT_OBJECT_TIMEOUT)
+    }
+}
+
+impl ObjectDatabase {
+    /// Creates an empty database that drops objects not updated within `timeout`
+    #[must_use]
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            objects: HashMap::new(),
+            timeout,
+        }
+    }
+
+    #[must_use]
+    pub const fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    pub fn ingest_point(&mut self, pdu: &PointObjectStatePdu, now: Instant) -> ObjectUpdate {
+        let object_id = ObjectIdentifier::from(pdu.object_id);
+        self.apply(ObjectState {
+            object_id,
+            referenced_object_id: reference(object_id, pdu.referenced_object_id.into()),
+            update_number: pdu.update_number,
+            force_id: pdu.force_id,
+            object_type: pdu.object_type,
+            geometry: ObjectGeometry::Point {
+                location: pdu.object_location,
+                orientation: pdu.object_orientation,
+            },
+            specific_appearance: Some(pdu.specific_appearance()),
+            last_update: now,
+        })
+    }
+
+    pub fn ingest_linear(&mut self, pdu: &LinearObjectStatePdu, now: Instant) -> ObjectUpdate {
+        let segments = pdu
+            .linear_segment_parameters
+            .iter()
+            .map(|segment| ObjectSegment {
+                segment_number: segment.segment_number,
+                location: segment.segment_location,
+                orientation: segment.segment_orientation,
+                length: segment.segment_length,
+                width: segment.segment_width,
+                height: segment.segment_height,
+                depth: segment.segment_depth,
+                general_appearance: segment.general_segment_appearance,
+                specific_appearance: segment.specific_appearance(&pdu.object_type),
+            })
+            .collect();
+        self.apply(ObjectState {
+            object_id: pdu.object_id,
+            referenced_object_id: reference(pdu.object_id, pdu.referenced_object_id),
+            update_number: pdu.update_number,
+            force_id: pdu.force_id,
+            object_type: pdu.object_type,
+            geometry: ObjectGeometry::Linear { segments },
+            specific_appearance: None,
+            last_update: now,
+        })
+    }
+
+    pub fn ingest_areal(&mut self, pdu: &ArealObjectStatePdu, now: Instant) -> ObjectUpdate {
+        self.apply(ObjectState {
+            object_id: pdu.object_id,
+            referenced_object_id: reference(pdu.object_id, pdu.referenced_object_id),
+            update_number: pdu.update_number,
+            force_id: pdu.force_id,
+            object_type: pdu.object_type,
+            geometry: ObjectGeometry::Areal {
+                points: pdu.object_location.clone(),
+            },
+            specific_appearance: Some(pdu.specific_appearance()),
+            last_update: now,
+        })
+    }
+
+    fn apply(&mut self, state: ObjectState) -> ObjectUpdate {
+        match self.objects.get_mut(&state.object_id) {
+            Some(existing) if !is_newer(state.update_number, existing.update_number) => {
+                ObjectUpdate::Stale
+            }
+            Some(existing) => {
+                *existing = state;
+                ObjectUpdate::Updated
+            }
+            None => {
+                self.objects.insert(state.object_id, state);
+                ObjectUpdate::Created
+            }
+        }
+    }
+
+    /// Removes every object that has not been updated within the timeout and returns their
+    /// identifiers
+    pub fn expire(&mut self, now: Instant) -> Vec<ObjectIdentifier> {
+        let timeout = self.timeout;
+        let expired: Vec<ObjectIdentifier> = self
+            .objects
+            .values()
+            .filter(|state| now.saturating_duration_since(state.last_update) > timeout)
+            .map(|state| state.object_id)
+            .collect();
+        for object_id in &expired {
+            self.objects.remove(object_id);
+        }
+        expired
+    }
+
+    /// Removes an object, e.g. after its owner announced that it was deleted
+    pub fn remove(&mut self, object_id: &ObjectIdentifier) -> Option<ObjectState> {
+        self.objects.remove(object_id)
+    }
+
+    #[must_use]
+    pub fn get(&self, object_id: &ObjectIdentifier) -> Option<&ObjectState> {
+        self.objects.get(object_id)
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.objects.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &ObjectState> {
+        self.objects.values()
+    }
+
+    /// Follows the `referenced_object_id` chain starting at `object_id`
+    ///
+    /// The returned list starts with the object itself and ends at the first object that has no
+    /// reference, whose reference is not known to the database, or that would close a cycle.
+    #[must_use]
+    pub fn reference_chain(&self, object_id: &ObjectIdentifier) -> Vec<&ObjectState> {
+        let mut chain = Vec::new();
+        let mut visited = HashSet::new();
+        let mut next = Some(*object_id);
+        while let Some(id) = next {
+            if !visited.insert(id) {
+                break;
+            }
+            let Some(state) = self.objects.get(&id) else {
+                break;
+            };
+            chain.push(state);
+            next = state.referenced_object_id;
+        }
+        chain
+    }
+
+    /// The last object of the reference chain starting at `object_id`
+    #[must_use]
+    pub fn root_of(&self, object_id: &ObjectIdentifier) -> Option<&ObjectState> {
+        self.reference_chain(object_id).pop()
+    }
+
+    /// Objects that directly reference `object_id`
+    pub fn referencing<'a>(
+        &'a self,
+        object_id: &'a ObjectIdentifier,
+    ) -> impl Iterator<Item = &'a ObjectState> {
+        self.objects
+            .values()
+            .filter(move |state| state.referenced_object_id.as_ref() == Some(object_id))
+    }
+
+    /// Objects with at least one location within `radius` meters of `center`
+    pub fn within(
+        &self,
+        center: WorldCoordinate,
+        radius: f64,
+    ) -> impl Iterator<Item = &ObjectState> {
+        self.objects.values().filter(move |state| {
+            state
+                .geometry
+                .locations()
+                .iter()
+                .any(|location| location.distance_to(&center) <= radius)
+        })
+    }
+
+    /// Objects whose specific appearance matches `predicate`, e.g. every crater
+    pub fn with_appearance<F>(&self, predicate: F) -> impl Iterator<Item = &ObjectState>
+    where
+        F: Fn(&SpecificObjectAppearance) -> bool,
+    {
+        self.objects.values().filter(move |state| {
+            state.specific_appearance.as_ref().is_some_and(&predicate)
+                || matches!(&state.geometry, ObjectGeometry::Linear { segments }
+                    if segments.iter().any(|segment| predicate(&segment.specific_appearance)))
+        })
+    }
+}
+
+/// A reference to the object itself or to `NO_OBJECT` means the object references nothing
+fn reference(
+    object_id: ObjectIdentifier,
+    referenced_object_id: ObjectIdentifier,
+) -> Option<ObjectIdentifier> {
+    (referenced_object_id != object_id && referenced_object_id.object_number != NO_OBJECT)
+        .then_some(referenced_object_id)
+}
+
+/// Compares update numbers with serial number arithmetic so that the sequence survives
+/// wrapping around from 65535 to 0
+const fn is_newer(candidate: u16, current: u16) -> bool {
+    let delta = candidate.wrapping_sub(current);
+    delta != 0 && delta < 0x8000
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{
+        data_types::{
+            EntityId, linear_segment_parameter::LinearSegmentParameter,
+            object_appearance::CraterAppearance, simulation_address::SimulationAddress,
+        },
+        enums::ObjectKind,
+    };
+
+    fn object_id(object_number: u16) -> ObjectIdentifier {
+        ObjectIdentifier::from_parts(SimulationAddress::new(1, 2), object_number)
+    }
+
+    fn crater(
+        object_number: u16,
+        update_number: u16,
+        location: WorldCoordinate,
+    ) -> PointObjectStatePdu {
+        let mut pdu = PointObjectStatePdu::new();
+        pdu.object_id = EntityId::new(1, 2, object_number);
+        pdu.update_number = update_number;
+        pdu.object_type = ObjectType::new(1, ObjectKind::Obstacle as u8, 3, 0);
+        pdu.object_location = location;
+        pdu.specific_object_appearance = 8;
+        pdu
+    }
+
+    #[test]
+    fn stale_updates_are_ignored() {
+        let now = Instant::now();
+        let mut database = ObjectDatabase::default();
+
+        let first = crater(1, 5, WorldCoordinate::new(1.0, 0.0, 0.0));
+        assert_eq!(database.ingest_point(&first, now), ObjectUpdate::Created);
+
+        let older = crater(1, 4, WorldCoordinate::new(2.0, 0.0, 0.0));
+        assert_eq!(database.ingest_point(&older, now), ObjectUpdate::Stale);
+        assert_eq!(database.ingest_point(&first, now), ObjectUpdate::Stale);
+
+        let newer = crater(1, 6, WorldCoordinate::new(3.0, 0.0, 0.0));
+        assert_eq!(database.ingest_point(&newer, now), ObjectUpdate::Updated);
+        assert_eq!(
+            database
+                .get(&object_id(1))
+                .map(|state| state.geometry.locations()),
+            Some(vec![WorldCoordinate::new(3.0, 0.0, 0.0)])
+        );
+    }
+
+    #[test]
+    fn update_numbers_wrap_around() {
+        assert!(is_newer(0, u16::MAX));
+        assert!(is_newer(10, 65_530));
+        assert!(!is_newer(65_530, 10));
+        assert!(!is_newer(7, 7));
+    }
+
+    #[test]
+    fn objects_expire_without_heartbeat() {
+        let start = Instant::now();
+        let mut database = ObjectDatabase::new(Duration::from_secs(10));
+        database.ingest_point(&crater(1, 1, WorldCoordinate::default()), start);
+        database.ingest_point(
+            &crater(2, 1, WorldCoordinate::default()),
+            start + Duration::from_secs(8),
+        );
+
+        assert!(database.expire(start + Duration::from_secs(10)).is_empty());
+        assert_eq!(
+            database.expire(start + Duration::from_secs(11)),
+            vec![object_id(1)]
+        );
+        assert_eq!(database.len(), 1);
+    }
+
+    #[test]
+    fn reference_chains_are_resolved() {
+        let now = Instant::now();
+        let mut database = ObjectDatabase::default();
+
+        let mut lane = LinearObjectStatePdu::new();
+        lane.object_id = object_id(10);
+        lane.update_number = 1;
+        lane.object_type = ObjectType::new(1, ObjectKind::ObstacleMarker as u8, 1, 0);
+        lane.linear_segment_parameters = vec![LinearSegmentParameter::default()];
+        lane.referenced_object_id = object_id(20);
+
+        let mut minefield = ArealObjectStatePdu::new();
+        minefield.object_id = object_id(20);
+        minefield.update_number = 1;
+        minefield.object_type = ObjectType::new(1, ObjectKind::Obstacle as u8, 2, 0);
+        minefield.referenced_object_id = object_id(10);
+
+        database.ingest_linear(&lane, now);
+        database.ingest_areal(&minefield, now);
+
+        let chain: Vec<ObjectIdentifier> = database
+            .reference_chain(&object_id(10))
+            .iter()
+            .map(|state| state.object_id)
+            .collect();
+        assert_eq!(chain, vec![object_id(10), object_id(20)]);
+        assert_eq!(
+            database.referencing(&object_id(20)).count(),
+            1,
+            "the lane references the minefield"
+        );
+    }
+
+    #[test]
+    fn terrain_objects_are_queryable() {
+        let now = Instant::now();
+        let mut database = ObjectDatabase::default();
+        database.ingest_point(&crater(1, 1, WorldCoordinate::new(100.0, 0.0, 0.0)), now);
+        database.ingest_point(&crater(2, 1, WorldCoordinate::new(900.0, 0.0, 0.0)), now);
+
+        let nearby: Vec<ObjectIdentifier> = database
+            .within(WorldCoordinate::default(), 150.0)
+            .map(|state| state.object_id)
+            .collect();
+        assert_eq!(nearby, vec![object_id(1)]);
+
+        let craters = database
+            .with_appearance(|appearance| {
+                *appearance == SpecificObjectAppearance::Crater(CraterAppearance { size: 8 })
+            })
+            .count();
+        assert_eq!(craters, 2);
+    }
+}
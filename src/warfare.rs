@@ -11,6 +11,7 @@ use crate::{
         data_types::{
             ClockTime, EntityCoordinateVector, EntityId, EntityType, EventId, LinearVelocity,
             VariableParameter, WorldCoordinate, directed_energy_damage::DirectedEnergyDamage,
+            directed_energy_fire_record::DirectedEnergyFireRecord,
             munition_descriptor::MunitionDescriptor,
         },
         enums::{DEFirePulseShape, DetonationResult, PduType, ProtocolFamily},
@@ -90,7 +91,8 @@ define_pdu! {
             padding3: u32,
             padding4: u16,
             pub number_of_de_records: u16,
-            pub damage_descriptions: Vec<DirectedEnergyDamage>,
+            #[count = number_of_de_records]
+            pub de_records: Vec<DirectedEnergyFireRecord>,
         }
     }
 }
@@ -107,11 +109,37 @@ define_pdu! {
             padding: u16,
             padding2: u16,
             pub number_of_damage_descriptions: u16,
+            #[count = number_of_damage_descriptions]
             pub damage_descriptions: Vec<DirectedEnergyDamage>,
         }
     }
 }
 
+impl EntityDamageStatusPdu {
+    /// Creates a damage report for `damaged_entity_id` from a list of component damages
+    #[must_use]
+    pub fn from_component_damages<I>(damaged_entity_id: EntityId, damages: I) -> Self
+    where
+        I: IntoIterator<Item = DirectedEnergyDamage>,
+    {
+        let mut pdu = Self::new();
+        pdu.damaged_entity_id = damaged_entity_id;
+        for damage in damages {
+            pdu.add_damage_description(damage);
+        }
+        pdu
+    }
+
+    /// Appends a component damage to the report, keeping the description count and the PDU
+    /// length up to date
+    pub fn add_damage_description(&mut self, damage: DirectedEnergyDamage) {
+        self.damage_descriptions.push(damage);
+        self.number_of_damage_descriptions =
+            u16::try_from(self.damage_descriptions.len()).unwrap_or(u16::MAX);
+        self.finalize();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -180,6 +208,15 @@ mod tests {
 
     mod directed_energy_fire_pdu_tests {
         use super::*;
+        use crate::common::{
+            data_types::{
+                beam_antenna_pattern::BeamAntennaPattern,
+                directed_energy_fire_record::{
+                    DEAreaAimpoint, DEPrecisionAimpoint, DETargetEnergyDeposition,
+                },
+            },
+            enums::DEPrecisionAimpointBeamSpotType,
+        };
 
         #[test]
         fn cast_to_any() {
@@ -207,10 +244,76 @@ mod tests {
             let pdu = DirectedEnergyFirePdu::new();
             assert_eq!(pdu.header().length, DEFAULT_LENGTH);
         }
+
+        #[test]
+        fn serialize_then_deserialize_aimpoint_records() {
+            let mut precision = DEPrecisionAimpoint::new();
+            precision.target_entity_id = EntityId::new(1, 2, 3);
+            precision.beam_spot_type = DEPrecisionAimpointBeamSpotType::Gaussian;
+            precision.peak_irradiance = 1.5e6;
+
+            let area = DEAreaAimpoint::new(
+                vec![BeamAntennaPattern::default()],
+                vec![
+                    DETargetEnergyDeposition::new(EntityId::new(1, 2, 4), 2.0e5),
+                    DETargetEnergyDeposition::new(EntityId::new(1, 2, 5), 3.0e5),
+                ],
+            );
+
+            let mut pdu = DirectedEnergyFirePdu::new();
+            pdu.de_records = vec![
+                DirectedEnergyFireRecord::PrecisionAimpoint(precision),
+                DirectedEnergyFireRecord::AreaAimpoint(area),
+            ];
+            let mut serialize_buf = BytesMut::new();
+            let _ = pdu.serialize(&mut serialize_buf);
+            assert_eq!(pdu.number_of_de_records, 2);
+            assert_eq!(pdu.de_records[0].record_length(), 96);
+            // 8 header + 4 counts + 40 + 2 * 12 = 76, padded to 80
+            assert_eq!(pdu.de_records[1].record_length(), 80);
+            assert_eq!(usize::from(pdu.header().length), serialize_buf.len());
+
+            let mut deserialize_buf = serialize_buf.freeze();
+            let new_pdu =
+                DirectedEnergyFirePdu::deserialize(&mut deserialize_buf).unwrap_or_default();
+            assert_eq!(new_pdu.de_records, pdu.de_records);
+        }
+
+        #[test]
+        fn aimpoint_records_keep_only_what_fits() {
+            let area = DirectedEnergyFireRecord::AreaAimpoint(DEAreaAimpoint::new(
+                vec![BeamAntennaPattern::default()],
+                vec![DETargetEnergyDeposition::new(EntityId::new(1, 2, 4), 2.0e5)],
+            ));
+            let mut buf = BytesMut::new();
+            area.serialize(&mut buf);
+            // Beam antenna pattern count, after the record header
+            buf[8..10].copy_from_slice(&1000u16.to_be_bytes());
+            let decoded = DirectedEnergyFireRecord::deserialize(&mut buf.freeze());
+            assert_eq!(decoded, area);
+
+            let precision = DirectedEnergyFireRecord::PrecisionAimpoint(DEPrecisionAimpoint::new());
+            let mut buf = BytesMut::new();
+            precision.serialize(&mut buf);
+            buf.truncate(40);
+            let decoded = DirectedEnergyFireRecord::deserialize(&mut buf.freeze());
+            assert!(matches!(
+                decoded,
+                DirectedEnergyFireRecord::Unknown { ref record_specific_fields, .. }
+                    if record_specific_fields.len() == 32
+            ));
+        }
     }
 
     mod entity_damage_status_pdu_tests {
         use super::*;
+        use crate::common::{
+            data_types::directed_energy_damage::ComponentVisualDamageStatus,
+            enums::{
+                ComponentDamageStatus, ComponentIdentification, ComponentVisualDamageStatusSmoke,
+                ComponentVisualDamageStatusSurfaceDamage, ComponentVisualSmokeColor,
+            },
+        };
 
         #[test]
         fn cast_to_any() {
@@ -238,5 +341,45 @@ mod tests {
             let pdu = EntityDamageStatusPdu::new();
             assert_eq!(pdu.header().length, DEFAULT_LENGTH);
         }
+
+        #[test]
+        fn build_from_component_damages() {
+            let mut visual = ComponentVisualDamageStatus::new();
+            visual.set_is_fire_present(true);
+            visual.set_smoke(ComponentVisualDamageStatusSmoke::HeavySmoke);
+            visual.set_surface_damage(ComponentVisualDamageStatusSurfaceDamage::LightCharring);
+            assert_eq!(visual.to_u8(), 0b0_1111);
+
+            let mut pdu = EntityDamageStatusPdu::from_component_damages(
+                EntityId::new(1, 2, 3),
+                [
+                    DirectedEnergyDamage::for_component(
+                        ComponentIdentification::EnginePropulsionSystem,
+                        ComponentDamageStatus::MajorDamage,
+                    )
+                    .with_visual_damage(visual, ComponentVisualSmokeColor::Black),
+                    DirectedEnergyDamage::for_component(
+                        ComponentIdentification::CrewMember,
+                        ComponentDamageStatus::MinorDamage,
+                    ),
+                ],
+            );
+            assert_eq!(pdu.number_of_damage_descriptions, 2);
+            assert_eq!(pdu.header().length, 192 / 8 + 2 * 40);
+
+            let mut serialize_buf = BytesMut::new();
+            let _ = pdu.serialize(&mut serialize_buf);
+            assert_eq!(usize::from(pdu.header().length), serialize_buf.len());
+
+            let mut deserialize_buf = serialize_buf.freeze();
+            let new_pdu =
+                EntityDamageStatusPdu::deserialize(&mut deserialize_buf).unwrap_or_default();
+            assert_eq!(new_pdu.damage_descriptions, pdu.damage_descriptions);
+            assert!(
+                new_pdu.damage_descriptions[0]
+                    .component_visual_damage_status
+                    .is_fire_present()
+            );
+        }
     }
 }